@@ -0,0 +1,250 @@
+use super::*;
+
+pub const INPUT_CONFIG_FILE: &str = "input.cfg";
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InputAction
+{
+    MoveUp, MoveDown, MoveLeft, MoveRight, Fire, Pause, Confirm, WatchReplay, Restart, ToggleCheckpoint,
+    // Stage Editor
+    OpenEditor, PreviousTool, NextTool, Erase, NextEnemyType, Playtest, Export, PreviousStage, NextStage,
+}
+impl InputAction
+{
+    pub fn all() -> [InputAction; 19]
+    {
+        [
+            InputAction::MoveUp, InputAction::MoveDown, InputAction::MoveLeft, InputAction::MoveRight,
+            InputAction::Fire, InputAction::Pause, InputAction::Confirm, InputAction::WatchReplay,
+            InputAction::Restart, InputAction::ToggleCheckpoint,
+            InputAction::OpenEditor, InputAction::PreviousTool, InputAction::NextTool, InputAction::Erase, InputAction::NextEnemyType,
+            InputAction::Playtest, InputAction::Export, InputAction::PreviousStage, InputAction::NextStage,
+        ]
+    }
+    pub fn get_name(&self) -> String { format!("{:?}", self) }
+    pub fn from_name(name: &str) -> Option<InputAction>
+    {
+        InputAction::all().iter().find(|action| action.get_name() == name).copied()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum InputBinding
+{
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+impl InputBinding
+{
+    pub fn is_down(&self) -> bool
+    {
+        match self
+        {
+            InputBinding::Key(key) => is_key_down(*key),
+            InputBinding::Mouse(button) => is_mouse_button_down(*button),
+        }
+    }
+    pub fn is_released(&self) -> bool
+    {
+        match self
+        {
+            InputBinding::Key(key) => is_key_released(*key),
+            InputBinding::Mouse(button) => is_mouse_button_released(*button),
+        }
+    }
+    pub fn get_name(&self) -> String
+    {
+        match self
+        {
+            InputBinding::Key(key) => format!("{:?}", key),
+            InputBinding::Mouse(MouseButton::Left) => "MouseLeft".to_string(),
+            InputBinding::Mouse(MouseButton::Right) => "MouseRight".to_string(),
+            InputBinding::Mouse(MouseButton::Middle) => "MouseMiddle".to_string(),
+            InputBinding::Mouse(_) => "MouseUnknown".to_string(),
+        }
+    }
+    pub fn from_name(name: &str) -> Option<InputBinding>
+    {
+        match name
+        {
+            "MouseLeft" => return Some(InputBinding::Mouse(MouseButton::Left)),
+            "MouseRight" => return Some(InputBinding::Mouse(MouseButton::Right)),
+            "MouseMiddle" => return Some(InputBinding::Mouse(MouseButton::Middle)),
+            _ => {}
+        }
+        BINDABLE_KEYS.iter().find(|key| format!("{:?}", key) == name).map(|key| InputBinding::Key(*key))
+    }
+}
+
+// Every Action with its Bindings -> loaded from INPUT_CONFIG_FILE, falls back to the Defaults
+#[derive(Clone)]
+pub struct ActionMap
+{
+    bindings: Vec<(InputAction, Vec<InputBinding>)>,
+}
+impl Default for ActionMap
+{
+    fn default() -> Self
+    {
+        let key = |code: KeyCode| InputBinding::Key(code);
+        Self {
+            bindings: vec![
+                (InputAction::MoveUp,       vec![key(KeyCode::W), key(KeyCode::Up)]),
+                (InputAction::MoveDown,     vec![key(KeyCode::S), key(KeyCode::Down)]),
+                (InputAction::MoveLeft,     vec![key(KeyCode::A), key(KeyCode::Left)]),
+                (InputAction::MoveRight,    vec![key(KeyCode::D), key(KeyCode::Right)]),
+                (InputAction::Fire,         vec![key(KeyCode::Space), InputBinding::Mouse(MouseButton::Left)]),
+                (InputAction::Pause,        vec![key(KeyCode::Tab)]),
+                (InputAction::Confirm,      vec![key(KeyCode::Space), InputBinding::Mouse(MouseButton::Left)]),
+                (InputAction::WatchReplay,  vec![key(KeyCode::R)]),
+                (InputAction::Restart,      vec![key(KeyCode::Enter)]),
+                (InputAction::ToggleCheckpoint, vec![key(KeyCode::C)]),
+                (InputAction::OpenEditor,   vec![key(KeyCode::E)]),
+                (InputAction::PreviousTool, vec![key(KeyCode::Q)]),
+                (InputAction::NextTool,     vec![key(KeyCode::E)]),
+                (InputAction::Erase,        vec![InputBinding::Mouse(MouseButton::Right)]),
+                (InputAction::NextEnemyType, vec![key(KeyCode::T)]),
+                (InputAction::Playtest,     vec![key(KeyCode::P)]),
+                (InputAction::Export,       vec![key(KeyCode::X)]),
+                (InputAction::PreviousStage, vec![key(KeyCode::B)]),
+                (InputAction::NextStage,    vec![key(KeyCode::N)]),
+            ],
+        }
+    }
+}
+impl ActionMap
+{
+    pub fn load(path: &str) -> Self
+    {
+        let mut map = ActionMap::default();
+        match std::fs::read_to_string(path)
+        {
+            Ok(text) => { map.apply_config(&text); }
+            Err(_) => { println!("No Input Config found at {} -> Default Bindings", path); }
+        }
+        map
+    }
+    pub fn save(&self, path: &str)
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            match std::fs::write(path, self.to_config())
+            {
+                Ok(()) => {}
+                Err(error) => { println!("Could not save Input Config: {}", error); }
+            }
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = path;
+        }
+    }
+
+    // Config Format -> one Line per Action: "MoveUp = W, Up"  |  "#" starts a Comment
+    pub fn apply_config(&mut self, text: &str)
+    {
+        for (line_number, line) in config_lines(text)
+        {
+            let (name, values) = match config_entry(line_number, line)
+            {
+                Ok(pair) => pair,
+                Err(error) => { println!("Input Config {}", error); continue; }
+            };
+            let action = match InputAction::from_name(name)
+            {
+                Some(action) => action,
+                None => { println!("Input Config Line {}: unknown Action {}", line_number, name); continue; }
+            };
+
+            let mut bindings: Vec<InputBinding> = Vec::new();
+            for value in values.split(',').map(|v| v.trim()).filter(|v| !v.is_empty())
+            {
+                match InputBinding::from_name(value)
+                {
+                    Some(binding) => { bindings.push(binding); }
+                    None => { println!("Input Config Line {}: unknown Binding {}", line_number, value); }
+                }
+            }
+            self.set_bindings(action, bindings);
+        }
+    }
+    pub fn to_config(&self) -> String
+    {
+        let mut text = "# Action = Binding, Binding\n".to_string();
+        for (action, bindings) in self.bindings.iter()
+        {
+            let names: Vec<String> = bindings.iter().map(|b| b.get_name()).collect();
+            text.push_str(format!("{} = {}\n", action.get_name(), names.join(", ")).as_str());
+        }
+        text
+    }
+
+    pub fn get_bindings(&self, action: InputAction) -> Vec<InputBinding>
+    {
+        match self.bindings.iter().find(|(a, _)| *a == action)
+        {
+            Some((_, bindings)) => bindings.clone(),
+            None => Vec::new(),
+        }
+    }
+    pub fn set_bindings(&mut self, action: InputAction, bindings: Vec<InputBinding>)
+    {
+        match self.bindings.iter_mut().find(|(a, _)| *a == action)
+        {
+            Some(entry) => { entry.1 = bindings; }
+            None => { self.bindings.push((action, bindings)); }
+        }
+    }
+    // Runtime Rebinding -> the new Binding replaces all old ones of that Action
+    pub fn rebind(&mut self, action: InputAction, binding: InputBinding)
+    {
+        self.set_bindings(action, vec![binding]);
+    }
+
+    pub fn is_down(&self, action: InputAction) -> bool
+    {
+        self.get_bindings(action).iter().any(|b| b.is_down())
+    }
+    pub fn is_released(&self, action: InputAction) -> bool
+    {
+        self.get_bindings(action).iter().any(|b| b.is_released())
+    }
+
+    // First Key or Mousebutton pressed in this Frame -> used while waiting for a new Binding
+    pub fn capture_binding() -> Option<InputBinding>
+    {
+        match get_last_key_pressed()
+        {
+            Some(key) if BINDABLE_KEYS.contains(&key) => return Some(InputBinding::Key(key)),
+            _ => {}
+        }
+        for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle]
+        {
+            if is_mouse_button_pressed(button)
+            {
+                return Some(InputBinding::Mouse(button));
+            }
+        }
+        None
+    }
+}
+
+const BINDABLE_KEYS: &[KeyCode] = &[
+    KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+    KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+    KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+    KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4,
+    KeyCode::Key5, KeyCode::Key6, KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+    KeyCode::Space, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Minus, KeyCode::Period, KeyCode::Slash,
+    KeyCode::Semicolon, KeyCode::Equal, KeyCode::LeftBracket, KeyCode::Backslash, KeyCode::RightBracket, KeyCode::GraveAccent,
+    KeyCode::Escape, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace, KeyCode::Insert, KeyCode::Delete,
+    KeyCode::Right, KeyCode::Left, KeyCode::Down, KeyCode::Up,
+    KeyCode::PageUp, KeyCode::PageDown, KeyCode::Home, KeyCode::End,
+    KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6,
+    KeyCode::F7, KeyCode::F8, KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12,
+    KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4,
+    KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7, KeyCode::Kp8, KeyCode::Kp9,
+    KeyCode::KpEnter, KeyCode::KpAdd, KeyCode::KpSubtract,
+    KeyCode::LeftShift, KeyCode::LeftControl, KeyCode::LeftAlt,
+    KeyCode::RightShift, KeyCode::RightControl, KeyCode::RightAlt,
+];
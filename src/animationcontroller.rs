@@ -115,7 +115,7 @@ impl Animation
         if self.frame_t > 0.0
         {
 
-            self.frame_t -= 1.0 * self.anim_speed * get_frame_time();
+            self.frame_t -= 1.0 * self.anim_speed * get_tick_time();
             self.alpha = self.frame_t;
             
             //println!("alpha: {}", self.alpha);
@@ -142,7 +142,7 @@ impl Animation
     {
         if self.frame_t > 0.0
         {
-            self.frame_t -= 1.0 * self.anim_speed * get_frame_time();
+            self.frame_t -= 1.0 * self.anim_speed * get_tick_time();
             self.alpha = self.frame_t;
             if self.frame_t < 0.0 { self.frame_t = 0.0; self.alpha = 0.0;}
             
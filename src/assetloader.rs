@@ -14,11 +14,13 @@ pub enum EAssetType
 pub struct AssetLibrary
 {
     pub assets: Vec<Asset>,
+    // Everything that failed to load -> those Assets hold Placeholders instead
+    pub errors: Vec<LoadError>,
 }
 impl AssetLibrary 
 {
     pub fn new() -> Self {
-        Self { assets: Vec::new() }
+        Self { assets: Vec::new(), errors: Vec::new() }
     }
     pub async fn asset_loader_init(&mut self)
     {
@@ -26,38 +28,108 @@ impl AssetLibrary
         //self.init_load_executable("./resources/sounds".to_string()).await;
         self.init_load_for_wasm("resources/".to_string()).await;
     }
+    // No Window -> registers every Asset Name with empty Data, Gameplay falls back to its default Sizes
+    pub fn asset_loader_init_headless(&mut self)
+    {
+        for file in AssetLibrary::asset_files()
+        {
+            let mut name = file.to_string();
+            let offset = file.find(".").unwrap();
+            name.drain(offset..);
+
+            let mut new_asset = Asset::new( self, name);
+            if file.contains(".png") {
+                new_asset.asset_type = EAssetType::TextureFile;
+                new_asset.data.texture_asset = Some(TextureAsset::new());
+            }
+            if file.contains(".wav") {
+                new_asset.asset_type = EAssetType::SoundFile;
+                new_asset.data.sound_asset = Some(SoundAsset::new());
+            }
+            self.assign_asset(new_asset.asset_id, new_asset);
+        }
+    }
+    fn asset_files() -> Vec<&'static str>
+    {
+        vec![
+
+            "enemy_1.png",
+            "enemy_2.png",
+            "enemy_3.png",
+            "player.png",
+            "Ship_sheet.png",
+            "player_missle_1.png",
+            "tile_texture_atlas.png",
+            "weapon_sheet.png",
+            "spawner_sheet.png",
+
+            "enemy_laserShoot_1.wav",
+            "explosion_1.wav",
+            "explosion_2.wav",
+            "explosion_3.wav",
+            "fire_1.wav",
+            "hit_1.wav",
+            "hurt_sound_1.wav",
+            "laserShoot_1.wav",
+            "laserShoot_2.wav",
+            "pickup_sound_1.wav",
+        ]
+    }
     pub async fn init_load_executable(&mut self, folder_path: String)
     {
-        let dir_values = fs::read_dir(folder_path).unwrap();
+        let dir_values = match fs::read_dir(&folder_path)
+        {
+            Ok(dir_values) => dir_values,
+            Err(error) => {
+                self.errors.push(LoadError::new(&folder_path, LoadErrorKind::Missing, &error.to_string()));
+                return;
+            }
+        };
         let mut path_vec : Vec<Box<DirEntry>> = Vec::new();
 
         for path in dir_values
         {
-            let p = Box::new(path.unwrap());
-            path_vec.push( p);
+            match path
+            {
+                Ok(path) => { path_vec.push(Box::new(path)); }
+                Err(error) => { self.errors.push(LoadError::new(&folder_path, LoadErrorKind::Missing, &error.to_string())); }
+            }
         }
 
         for path in path_vec
         {
-            let mut new_asset = Asset::new( self, path.path().file_stem().unwrap().to_owned().into_string().unwrap());
+            // Files without a Name or Extension are no Assets
+            let name = match path.path().file_stem().and_then(|stem| stem.to_str().map(|stem| stem.to_string()))
+            {
+                Some(name) => name,
+                None => { continue; }
+            };
+            let extension = path.path().extension().and_then(|extension| extension.to_str().map(|extension| extension.to_string())).unwrap_or_default();
+            let full_path = path.path().to_string_lossy().to_string();
+
+            let mut new_asset = Asset::new( self, name);
         
             let mut asset_type = EAssetType::TextureFile;
             let mut display_type = "";
 
-            if path.path().extension().unwrap().to_str().unwrap() == "png"{
+            if extension == "png"{
                 asset_type = EAssetType::TextureFile;
                 display_type = "TextureFile";
             }
-            if path.path().extension().unwrap().to_str().unwrap() == "wav"{
+            if extension == "wav"{
                 asset_type = EAssetType::SoundFile;  
                 display_type = "SoundFile";
             }
             println!("LOAD NEW ASSET: ");
             new_asset.asset_type = asset_type.clone();
-            new_asset.load_data(path.path().as_path().to_str().unwrap(), new_asset.asset_name.to_string().as_str(), asset_type).await;
+            match new_asset.load_data(full_path.as_str(), new_asset.asset_name.to_string().as_str(), asset_type).await
+            {
+                Ok(()) => {}
+                Err(error) => { self.errors.push(error); }
+            }
             println!("Asset Name: {}", new_asset.asset_name);
             println!("Asset ID: {}", new_asset.asset_id);
-            println!("Asset Path: {}", path.path().as_path().to_str().unwrap());
+            println!("Asset Path: {}", full_path);
             println!("Asset Type: {}", display_type );
             
             self.assign_asset(new_asset.asset_id,new_asset);
@@ -71,29 +143,7 @@ impl AssetLibrary
         let path_dir = folder_path.as_str();
         let mut asset_paths : (Vec<String>,Vec<String>) = (Vec::new(), Vec::new());
 
-        let files = vec![
-
-            "enemy_1.png",
-            "enemy_2.png",
-            "enemy_3.png",
-            "player.png",
-            "Ship_sheet.png",
-            "player_missle_1.png",
-            "tile_texture_atlas.png",
-            "weapon_sheet.png",
-            "spawner_sheet.png",
-
-            "enemy_laserShoot_1.wav",
-            "explosion_1.wav",
-            "explosion_2.wav",
-            "explosion_3.wav",
-            "fire_1.wav",
-            "hit_1.wav",
-            "hurt_sound_1.wav",
-            "laserShoot_1.wav",
-            "laserShoot_2.wav",
-            "pickup_sound_1.wav",
-        ];
+        let files = AssetLibrary::asset_files();
 
         for file in files
         {
@@ -143,7 +193,14 @@ impl AssetLibrary
             println!("Asset Path: {}", path.as_str());
             println!("Asset Type: {}", display_type );
 
-            new_asset.load_data(path.as_str(), new_asset.asset_name.to_string().as_str(), asset_type).await;
+            match new_asset.load_data(path.as_str(), new_asset.asset_name.to_string().as_str(), asset_type).await
+            {
+                Ok(()) => {}
+                Err(error) => {
+                    println!("Could not load Asset: {}", error);
+                    self.errors.push(error);
+                }
+            }
             
             self.assign_asset(new_asset.asset_id,new_asset);
         }
@@ -168,6 +225,21 @@ impl AssetLibrary
         }
         None
     }
+    // Gameplay Lookup -> unknown Names are recorded once and get an empty Asset instead of a Panic
+    pub fn get_asset(&mut self, asset_name: &str) -> Asset
+    {
+        match self.get_asset_by_name(asset_name.to_string())
+        {
+            Some(asset) => asset,
+            None => {
+                if !self.errors.iter().any(|error| error.file == asset_name)
+                {
+                    self.errors.push(LoadError::new(asset_name, LoadErrorKind::Missing, "no Asset with this Name"));
+                }
+                Asset { asset_id: self.assets.len(), asset_name: asset_name.to_string(), asset_type: EAssetType::Undefined, data: AssetData::new() }
+            }
+        }
+    }
     pub fn add_asset(&mut self, asset: Asset)
     {
         self.assets.push(asset);
@@ -229,15 +301,24 @@ impl Asset {
             None => {println!("No Texture Data Found!");SoundData::empty()}
         }
     }
-    pub async fn load_data(&mut self, path: &str, filename: &str, asset_type: EAssetType)
+    // Failed Files still get Data -> Placeholder Texture or silent Sound, the Error is returned for the Error Screen
+    pub async fn load_data(&mut self, path: &str, filename: &str, asset_type: EAssetType) -> Result<(), LoadError>
     {
+        let mut result = Ok(());
         match asset_type
         {
             EAssetType::Undefined => {},
             EAssetType::TextureFile => 
             {
                 let mut texture_asset = TextureAsset::new();
-                texture_asset.load(path, filename).await;
+                match texture_asset.load(path, filename).await
+                {
+                    Ok(()) => {}
+                    Err(error) => {
+                        texture_asset.texture_data = placeholder_texture();
+                        result = Err(error);
+                    }
+                }
                 if filename.contains("_atlas") {
                     texture_asset.tileset = true;
                     texture_asset.grid = ((texture_asset.texture_data.width() / 16.0) as usize, (texture_asset.texture_data.height() / 16.0 ) as usize);
@@ -247,10 +328,11 @@ impl Asset {
             EAssetType::SoundFile => 
             {
                 let mut sound_asset = SoundAsset::new();
-                sound_asset.load(path, filename,true).await;
+                result = sound_asset.load(path, filename,true).await;
                 self.data.sound_asset = Some(sound_asset);
             }
         }
+        result
     }
 }
 
@@ -293,11 +375,16 @@ impl TextureAsset
             animation_controller: AnimationController::new(sheet_size),
         }
     }
-    async fn load(&mut self, path: &str, filename: &str)
+    async fn load(&mut self, path: &str, filename: &str) -> Result<(), LoadError>
     {
         path.to_string().push_str(filename);
-        self.texture_data = load_texture(path.to_string().as_str()).await.unwrap();
+        let bytes = load_file(path.to_string().as_str()).await
+            .map_err(|error| LoadError::new(path, LoadErrorKind::Missing, &error.to_string()))?;
+        let image = LevelLoader::decode_png(&bytes)
+            .map_err(|error| LoadError::new(path, LoadErrorKind::Decode, &error))?;
+        self.texture_data = Texture2D::from_image(&image);
         self.texture_data.set_filter(FilterMode::Nearest);
+        Ok(())
     }
 
     pub fn setup_sheet(&mut self ,x: usize, y: usize)
@@ -359,14 +446,17 @@ pub struct SoundAsset
 impl SoundAsset 
 {
     pub fn new() -> Self {Self { sound_data: SoundData::empty() }}
-    async fn load(&mut self, path: &str, filename: &str, fullpath: bool)
+    // Failed Sounds stay empty -> playing them is silent
+    async fn load(&mut self, path: &str, filename: &str, fullpath: bool) -> Result<(), LoadError>
     {
         if !fullpath
         {
             path.to_string().push_str(filename);
         } 
-        let sound_data = SoundData::new(load_sound(path.to_string().as_str()).await.unwrap());
-        self.sound_data = sound_data;
+        let sound = load_sound(path.to_string().as_str()).await
+            .map_err(|error| LoadError::new(path, LoadErrorKind::Missing, &error.to_string()))?;
+        self.sound_data = SoundData::new(sound);
+        Ok(())
     }    
 }
 
@@ -1,6 +1,74 @@
+use macroquad::audio::{play_sound, stop_sound, PlaySoundParams, Sound};
 use super::*;
 
 pub trait AudioSource
 {
     fn play_sfx(&self);
-}
\ No newline at end of file
+}
+
+pub trait AudioBackend
+{
+    fn play(&mut self, sound: &SoundData, volume: f32);
+    // Master Volume (0.0 - 1.0) -> scales every played Sound
+    fn set_volume(&mut self, _volume: f32) {}
+    // Looping Stage Music -> replaces the Music that is playing
+    fn play_music(&mut self, _sound: &SoundData) {}
+    fn stop_music(&mut self) {}
+}
+
+pub struct MacroquadAudio
+{
+    volume: f32,
+    music: Option<Sound>,
+}
+impl Default for MacroquadAudio
+{
+    fn default() -> Self { Self::new() }
+}
+impl MacroquadAudio
+{
+    pub fn new() -> Self { Self { volume: 1.0, music: None } }
+}
+impl AudioBackend for MacroquadAudio
+{
+    fn play(&mut self, sound: &SoundData, volume: f32) {
+        if let Some(data) = sound.sound
+        {
+            play_sound(data, PlaySoundParams { volume: volume * self.volume, ..Default::default() });
+        }
+    }
+    fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+    }
+    fn play_music(&mut self, sound: &SoundData) {
+        self.stop_music();
+        if let Some(data) = sound.sound
+        {
+            play_sound(data, PlaySoundParams { looped: true, volume: self.volume * 0.5 });
+            self.music = Some(data);
+        }
+    }
+    fn stop_music(&mut self) {
+        if let Some(data) = self.music.take() { stop_sound(data); }
+    }
+}
+
+// Headless -> counts the Sounds instead of playing them
+pub struct NullAudio
+{
+    pub played: usize,
+}
+impl Default for NullAudio
+{
+    fn default() -> Self { Self::new() }
+}
+impl NullAudio
+{
+    pub fn new() -> Self { Self { played: 0 } }
+}
+impl AudioBackend for NullAudio
+{
+    fn play(&mut self, _sound: &SoundData, _volume: f32) {
+        self.played += 1;
+    }
+}
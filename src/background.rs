@@ -0,0 +1,288 @@
+use super::*;
+
+pub const DEFAULT_BACKGROUND_NAME: &str = "Default";
+// Layers repeat after this many Pixel -> Elements are only rolled once per Layer
+const LAYER_SPAN: f32 = GAME_SIZE_X * 2.0;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BackgroundLayerKind
+{
+    // Small Dots in random Brightness
+    Starfield,
+    // Soft Clouds of overlapping transparent Circles
+    Nebula,
+    // Dark Silhouettes rising from the Bottom
+    Structures,
+}
+impl BackgroundLayerKind
+{
+    pub fn from_name(name: &str) -> Option<BackgroundLayerKind>
+    {
+        match name
+        {
+            "starfield" => Some(BackgroundLayerKind::Starfield),
+            "nebula" => Some(BackgroundLayerKind::Nebula),
+            "structures" => Some(BackgroundLayerKind::Structures),
+            _ => None,
+        }
+    }
+}
+
+// One Shape of a Layer -> Position inside the Layer Span
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct BackgroundElement
+{
+    position: Vec2,
+    size: Vec2,
+    color: Color,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct BackgroundLayer
+{
+    pub kind: BackgroundLayerKind,
+    // Part of the Level Offset the Layer moves with -> 0 stands still, 1 moves with the Stage
+    pub parallax: f32,
+    pub count: usize,
+    // Base Size of one Element in Pixel
+    pub size: f32,
+    pub color: Color,
+    pub seed: u64,
+    elements: Vec<BackgroundElement>,
+}
+impl BackgroundLayer
+{
+    pub fn new(kind: BackgroundLayerKind) -> Self
+    {
+        Self { kind, parallax: 0.1, count: 100, size: 2.0, color: WHITE, seed: 0, elements: Vec::new() }
+    }
+
+    // Same Seed, same Layer -> Elements are rolled once when the Set is loaded
+    pub fn build(&mut self)
+    {
+        let mut random = RandomStream::new(self.seed);
+        self.elements.clear();
+        for _ in 0..self.count
+        {
+            let x = random.range_f32(0.0, LAYER_SPAN);
+            let element = match self.kind
+            {
+                BackgroundLayerKind::Starfield => {
+                    let brightness = random.range_f32(0.3, 1.0);
+                    let size = self.size * random.range_f32(0.5, 1.5);
+                    BackgroundElement {
+                        position: vec2(x, random.range_f32(0.0, GAME_SIZE_Y)),
+                        size: vec2(size, size),
+                        color: Color::new(self.color.r * brightness, self.color.g * brightness, self.color.b * brightness, self.color.a),
+                    }
+                }
+                BackgroundLayerKind::Nebula => {
+                    let radius = self.size * random.range_f32(0.6, 1.4);
+                    BackgroundElement {
+                        position: vec2(x, random.range_f32(0.0, GAME_SIZE_Y)),
+                        size: vec2(radius, radius),
+                        color: self.color,
+                    }
+                }
+                BackgroundLayerKind::Structures => {
+                    let width = self.size * random.range_f32(0.5, 1.5);
+                    let height = random.range_f32(GAME_SIZE_Y * 0.1, GAME_SIZE_Y * 0.45);
+                    BackgroundElement {
+                        position: vec2(x, GAME_SIZE_Y - height),
+                        size: vec2(width, height),
+                        color: self.color,
+                    }
+                }
+            };
+            self.elements.push(element);
+        }
+    }
+
+    pub fn draw(&self, render_offset: f32)
+    {
+        // Left View Edge inside the Layer Span
+        let layer_offset = (render_offset * self.parallax).rem_euclid(LAYER_SPAN);
+        for element in self.elements.iter()
+        {
+            // Wraps around -> every Element shows up once per Span
+            let mut x = element.position.x - layer_offset;
+            if x + element.size.x < 0.0
+            {
+                x += LAYER_SPAN;
+            }
+            if x - element.size.x > GAME_SIZE_X
+            {
+                continue;
+            }
+            self.draw_element(element, render_offset + x);
+        }
+    }
+    fn draw_element(&self, element: &BackgroundElement, x: f32)
+    {
+        match self.kind
+        {
+            BackgroundLayerKind::Starfield => {
+                draw_rectangle(x, element.position.y, element.size.x, element.size.y, element.color);
+            }
+            BackgroundLayerKind::Nebula => {
+                // Inner Circles stack up -> brighter Core, soft Border
+                for i in 0..4
+                {
+                    let radius = element.size.x * (1.0 - i as f32 * 0.22);
+                    draw_circle(x, element.position.y, radius, element.color);
+                }
+            }
+            BackgroundLayerKind::Structures => {
+                draw_rectangle(x, element.position.y, element.size.x, element.size.y, element.color);
+                // Antenna
+                draw_line(x + element.size.x * 0.5, element.position.y, x + element.size.x * 0.5, element.position.y - element.size.x * 0.6, 2.0, element.color);
+            }
+        }
+    }
+}
+
+// Layer Set of a Stage -> drawn back to front behind the Level
+#[derive(Clone, PartialEq, Debug)]
+pub struct Background
+{
+    pub name: String,
+    pub clear_color: Color,
+    pub layers: Vec<BackgroundLayer>,
+}
+impl Default for Background
+{
+    // Plain Black -> Stages look like before Backgrounds existed
+    fn default() -> Self
+    {
+        Background::new(DEFAULT_BACKGROUND_NAME)
+    }
+}
+impl Background
+{
+    pub fn new(name: &str) -> Self
+    {
+        Self { name: name.to_string(), clear_color: BLACK, layers: Vec::new() }
+    }
+
+    // Format -> "name" and "color", then one "[layer]" Block per Layer with "key = value" Lines  |  "#" starts a Comment
+    // Layer Keys -> kind (starfield, nebula, structures), parallax, count, size, color (r, g, b[, a]), seed
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        let mut background = Background::default();
+        for (line_number, line) in config_lines(text)
+        {
+            if line == "[layer]"
+            {
+                let mut layer = BackgroundLayer::new(BackgroundLayerKind::Starfield);
+                // Layers without a Seed still differ from each other
+                layer.seed = background.layers.len() as u64 + 1;
+                background.layers.push(layer);
+                continue;
+            }
+            let (key, value) = config_entry(line_number, line)?;
+
+            match background.layers.last_mut()
+            {
+                // Set Keys
+                None => match key
+                {
+                    "name" => { background.name = value.to_string(); }
+                    "color" => { background.clear_color = parse_color(value).map_err(|error| format!("Line {}: {}", line_number, error))?; }
+                    _ => { println!("Background Line {}: unknown Key {}", line_number, key); }
+                },
+                // Layer Keys
+                Some(layer) => match key
+                {
+                    "kind" => {
+                        layer.kind = BackgroundLayerKind::from_name(value).ok_or(format!("Line {}: unknown Layer Kind {}", line_number, value))?;
+                    }
+                    "parallax" => {
+                        layer.parallax = value.parse::<f32>().map_err(|_| format!("Line {}: parallax is not a Number", line_number))?;
+                    }
+                    "count" => {
+                        layer.count = value.parse::<usize>().map_err(|_| format!("Line {}: count is not a Number", line_number))?;
+                    }
+                    "size" => {
+                        layer.size = value.parse::<f32>().map_err(|_| format!("Line {}: size is not a Number", line_number))?;
+                    }
+                    "seed" => {
+                        layer.seed = value.parse::<u64>().map_err(|_| format!("Line {}: seed is not a Number", line_number))?;
+                    }
+                    "color" => { layer.color = parse_color(value).map_err(|error| format!("Line {}: {}", line_number, error))?; }
+                    _ => { println!("Background Line {}: unknown Key {}", line_number, key); }
+                },
+            }
+        }
+        for layer in background.layers.iter_mut()
+        {
+            layer.build();
+        }
+        Ok(background)
+    }
+
+    // Called right after the Camera is set -> covers the Clear Color of the Renderer
+    pub fn draw(&self, render_offset: f32)
+    {
+        if self.clear_color != BLACK
+        {
+            draw_rectangle(render_offset, 0.0, GAME_SIZE_X, GAME_SIZE_Y, self.clear_color);
+        }
+        for layer in self.layers.iter()
+        {
+            layer.draw(render_offset);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_layers()
+    {
+        let text = "name = Test\ncolor = 10, 20, 30\n[layer]\nkind = nebula\ncount = 4\nparallax = 0.5\n[layer]\nkind = structures\ncolor = 1, 2, 3, 4\nseed = 9\n";
+        let background = Background::parse(text).unwrap();
+        assert_eq!(background.name, "Test");
+        assert_eq!(background.clear_color, color_u8!(10, 20, 30, 255));
+        assert_eq!(background.layers.len(), 2);
+        assert_eq!(background.layers[0].kind, BackgroundLayerKind::Nebula);
+        assert_eq!(background.layers[0].elements.len(), 4);
+        assert_eq!(background.layers[0].seed, 1);
+        assert_eq!(background.layers[1].seed, 9);
+        // Same Seed -> same Layer
+        assert_eq!(Background::parse(text).unwrap(), background);
+    }
+    #[test]
+    fn rejects_broken_backgrounds()
+    {
+        assert!(Background::parse("[layer]\nkind = rain\n").is_err());
+        assert!(Background::parse("color = 1, 2\n").is_err());
+        assert!(Background::parse("[layer]\ncount = many\n").is_err());
+    }
+    #[test]
+    fn stages_override_the_pack_background()
+    {
+        let manifest = "name = Test\nbackground = pack.cfg\n[stage]\nname = Pack\nstructure = stage.png\n[stage]\nname = Own\nstructure = stage.png\nbackground = own.cfg\n[stage]\nname = Broken\nstructure = stage.png\nbackground = broken.cfg\n";
+        let pack = "name = Pack Sky\ncolor = 10, 20, 30\n[layer]\nkind = starfield\n";
+        let own = "name = Own Sky\n[layer]\nkind = nebula\n[layer]\nkind = structures\n";
+        let folder = write_test_pack("background", &[(MANIFEST_FILE, manifest), ("pack.cfg", pack), ("own.cfg", own), ("broken.cfg", "[layer]\nkind = rain\n")]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+        assert_eq!(loader.levels.len(), 3);
+
+        assert_eq!(loader.levels[0].background.name, "Pack Sky");
+        assert_eq!(loader.levels[0].background.clear_color, color_u8!(10, 20, 30, 255));
+        assert_eq!(loader.levels[1].background.name, "Own Sky");
+        assert_eq!(loader.levels[1].background.layers.len(), 2);
+
+        // Broken Background -> plain Black, the Stage still loads
+        let broken = &loader.levels[2].background;
+        assert_eq!(broken.name, DEFAULT_BACKGROUND_NAME);
+        assert_eq!(broken.clear_color, BLACK);
+        assert!(broken.layers.is_empty());
+        assert_eq!(loader.errors.len(), 1);
+        assert!(loader.errors[0].file.ends_with("broken.cfg"));
+    }
+}
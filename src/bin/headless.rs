@@ -0,0 +1,57 @@
+use macroquad::prelude::*;
+use vust_nexus::*;
+
+// Runs Stages without a Window
+// usage: headless [stage] [max ticks] [seed (hex)]
+//        headless [replay file]
+fn main()
+{
+    let args: Vec<String> = std::env::args().collect();
+    let folder = DEFAULT_LEVEL_PACK;
+
+    let mut game;
+    let max_ticks: usize;
+    let replay_path = args.get(1).filter(|a| a.ends_with(".vnr"));
+    match replay_path
+    {
+        Some(path) => {
+            // Recorded Input -> same Stage, same Seed, same Result
+            let replay = match Replay::load(path)
+            {
+                Ok(replay) => replay,
+                Err(error) => { println!("Could not load Replay {}: {}", path, error); return; }
+            };
+            max_ticks = replay.len();
+            let header = replay.header;
+            game = Game::init_headless(folder, Box::new(ReplayPlayer::new(replay)));
+            game.start_from_header(header);
+        }
+        None => {
+            let stage: usize = args.get(1).and_then(|a| a.parse().ok()).unwrap_or(0);
+            max_ticks = args.get(2).and_then(|a| a.parse().ok()).unwrap_or(120 * 60);
+            let seed: u64 = args.get(3).and_then(|a| u64::from_str_radix(a, 16).ok()).unwrap_or(0);
+
+            // Hold Fire and aim straight ahead
+            let mut frame = InputFrame::new();
+            frame.set_down(InputAction::Fire, true);
+            frame.aim = vec2(GAME_SIZE_X, GAME_SIZE_Y * 0.5);
+            let input = ScriptedInput::new(vec![frame; max_ticks]);
+
+            game = Game::init_headless(folder, Box::new(input));
+            game.start_stage(stage, seed);
+        }
+    }
+
+    let mut ticks = 0;
+    while ticks < max_ticks && game.step_headless()
+    {
+        ticks += 1;
+    }
+
+    println!("Stage: {}", game.get_selected_level());
+    println!("Seed: {:X}", game.get_seed());
+    println!("Ticks: {} ({:.2}s)", ticks, ticks as f32 * SIMULATION_TICK);
+    println!("State: {:?}", game.get_gamestate());
+    println!("Score: {}", game.get_score());
+    println!("Health: {}", game.get_player_health());
+}
@@ -0,0 +1,39 @@
+use vust_nexus::*;
+
+// Checks every Stage of one or more Level Packs
+// usage: validate_levels [pack folder ...]
+fn main()
+{
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let folders = if args.is_empty() { vec![DEFAULT_LEVEL_PACK.to_string()] } else { args };
+
+    let mut errors = 0;
+    let mut warnings = 0;
+    for folder in folders.iter()
+    {
+        // Pack Folders always end with a Slash, like in the Manifest
+        let folder = if folder.ends_with('/') { folder.clone() } else { format!("{}/", folder) };
+        let mut validator = LevelValidator::new(&folder);
+        validator.validate_pack();
+        for (stage, palette) in validator.palettes.iter()
+        {
+            println!("{}: Palette {}", stage, palette);
+        }
+        for issue in validator.issues.iter()
+        {
+            println!("{}", issue);
+        }
+        println!("{}: {} Errors, {} Warnings", folder, validator.error_count(), validator.warning_count());
+        errors += validator.error_count();
+        warnings += validator.warning_count();
+    }
+
+    if folders.len() > 1
+    {
+        println!("Total: {} Errors, {} Warnings", errors, warnings);
+    }
+    if errors > 0
+    {
+        std::process::exit(1);
+    }
+}
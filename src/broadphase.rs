@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use super::*;
+
+pub const BROADPHASE_CELL_SIZE: f32 = 128.0;
+
+// Uniform Grid over the World -> every Cell stores the Handles of the Entities overlapping it
+#[derive(Clone)]
+pub struct SpatialHash
+{
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<EntityHandle>>,
+}
+impl SpatialHash
+{
+    pub fn new(cell_size: f32) -> Self
+    {
+        Self { cell_size, cells: HashMap::new() }
+    }
+    pub fn clear(&mut self)
+    {
+        self.cells.clear();
+    }
+    pub fn insert(&mut self, handle: EntityHandle, rect: Rect)
+    {
+        let (min_x, min_y, max_x, max_y) = self.cell_range(rect);
+        for y in min_y..=max_y
+        {
+            for x in min_x..=max_x
+            {
+                self.cells.entry((x, y)).or_default().push(handle);
+            }
+        }
+    }
+    // Candidates only -> the Caller still has to test the real Intersection
+    pub fn query(&self, rect: Rect) -> Vec<EntityHandle>
+    {
+        let mut found: Vec<EntityHandle> = Vec::new();
+        let (min_x, min_y, max_x, max_y) = self.cell_range(rect);
+        for y in min_y..=max_y
+        {
+            for x in min_x..=max_x
+            {
+                if let Some(cell) = self.cells.get(&(x, y))
+                {
+                    for handle in cell.iter()
+                    {
+                        if !found.contains(handle)
+                        {
+                            found.push(*handle);
+                        }
+                    }
+                }
+            }
+        }
+        found
+    }
+    fn cell_range(&self, rect: Rect) -> (i32, i32, i32, i32)
+    {
+        (
+            (rect.x / self.cell_size).floor() as i32,
+            (rect.y / self.cell_size).floor() as i32,
+            ((rect.x + rect.w) / self.cell_size).floor() as i32,
+            ((rect.y + rect.h) / self.cell_size).floor() as i32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // Every intersecting Pair as (lower Index, higher Index)
+    fn brute_force_pairs(rects: &[Rect]) -> Vec<(usize, usize)>
+    {
+        let mut pairs = Vec::new();
+        for a in 0..rects.len()
+        {
+            for b in a + 1..rects.len()
+            {
+                if resolve_intersection(rects[a], rects[b])
+                {
+                    pairs.push((a, b));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn finds_the_same_pairs_as_brute_force()
+    {
+        let mut random = RandomStream::new(0xB0A7);
+        let mut rects: Vec<Rect> = (0..150)
+            .map(|_| Rect::new(random.range_f32(-300.0, 900.0), random.range_f32(-200.0, 500.0), random.range_f32(4.0, 160.0), random.range_f32(4.0, 160.0)))
+            .collect();
+        // Straddling Cell Borders -> Corners, Edges and negative Cells
+        rects.push(Rect::new(120.0, 120.0, 16.0, 16.0));
+        rects.push(Rect::new(127.0, 0.0, 2.0, 300.0));
+        rects.push(Rect::new(-8.0, -8.0, 16.0, 16.0));
+        rects.push(Rect::new(250.0, 126.0, 20.0, 4.0));
+
+        let mut hash = SpatialHash::new(BROADPHASE_CELL_SIZE);
+        let handles: Vec<EntityHandle> = (0..rects.len()).map(|index| EntityHandle { index, generation: 0 }).collect();
+        for (handle, rect) in handles.iter().zip(rects.iter())
+        {
+            hash.insert(*handle, *rect);
+        }
+
+        let mut pairs = Vec::new();
+        for (a, rect) in rects.iter().enumerate()
+        {
+            let candidates = hash.query(*rect);
+            assert_eq!(candidates.iter().filter(|h| h.index == a).count(), 1);
+            for handle in candidates
+            {
+                if handle.index > a && resolve_intersection(*rect, rects[handle.index])
+                {
+                    pairs.push((a, handle.index));
+                }
+            }
+        }
+        pairs.sort();
+        let expected = brute_force_pairs(&rects);
+        assert!(expected.len() > rects.len() / 4);
+        assert_eq!(pairs, expected);
+    }
+}
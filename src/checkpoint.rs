@@ -0,0 +1,45 @@
+use super::*;
+
+pub const CHECKPOINT_MARKER_KIND: &str = "checkpoint";
+
+// Stage Progress that has to survive a Restore -> Elements & Triggers already done stay done
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct LevelProgress
+{
+    // Cells of destroyed Destructibles & Turrets and of cleared Spawners
+    pub cleared: Vec<Vec2>,
+    pub fired_triggers: Vec<bool>,
+    pub released_groups: Vec<String>,
+    pub opened_areas: Vec<Rect>,
+    pub speed_override: Option<f32>,
+}
+
+// Snapshot taken once the Player passes a Checkpoint
+#[derive(Clone, PartialEq)]
+pub struct CheckpointState
+{
+    pub stage: usize,
+    pub seed: u64,
+    pub difficulty: i32,
+    // Column of the Checkpoint
+    pub column: f32,
+    pub level_offset: f32,
+    // Player Position relative to the View
+    pub view_position: Vec2,
+    pub player: EntityParams,
+    pub score: i32,
+    pub progress: LevelProgress,
+}
+
+// Checkpoint Markers & the "checkpoint" Keys of the Manifest -> sorted Columns, one per Column
+pub fn collect_checkpoint_columns(loaded: &LoadedLevelData) -> Vec<f32>
+{
+    let mut columns: Vec<f32> = loaded.markers.iter()
+        .filter(|m| m.kind == CHECKPOINT_MARKER_KIND)
+        .map(|m| m.position.x)
+        .chain(loaded.stage.checkpoints.iter().cloned())
+        .collect();
+    columns.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    columns.dedup();
+    columns
+}
@@ -1,8 +1,61 @@
+use std::ops::{BitOr, BitOrAssign};
 use super::*;
 
+// Bitset of Collision Layers -> an Entity sits on its Layer and only reacts to the Layers in its Mask
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct CollisionLayer(u32);
+impl CollisionLayer
+{
+    pub const NONE: Self                = Self(0);
+    pub const PLAYER: Self              = Self(1 << 0);
+    pub const PLAYER_PROJECTILE: Self   = Self(1 << 1);
+    pub const ENEMY: Self               = Self(1 << 2);
+    pub const ENEMY_PROJECTILE: Self    = Self(1 << 3);
+    pub const TERRAIN: Self             = Self(1 << 4);
+    pub const HAZARD: Self              = Self(1 << 5);
+    pub const TRIGGER: Self             = Self(1 << 6);
+    // Shootable Level Elements (Destructibles, Spawners, Turrets, Switches, Doors) -> stop Player Missles, never hurt the Player
+    pub const STRUCTURE: Self           = Self(1 << 7);
+    pub const ALL: Self                 = Self(u32::MAX);
+
+    pub fn bits(&self) -> u32 { self.0 }
+    pub fn is_empty(&self) -> bool { self.0 == 0 }
+    pub fn contains(&self, other: CollisionLayer) -> bool { (self.0 & other.0) == other.0 }
+    pub fn intersects(&self, other: CollisionLayer) -> bool { (self.0 & other.0) != 0 }
+}
+impl BitOr for CollisionLayer
+{
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self { Self(self.0 | rhs.0) }
+}
+impl BitOrAssign for CollisionLayer
+{
+    fn bitor_assign(&mut self, rhs: Self) { self.0 |= rhs.0; }
+}
+
 pub trait Collision
 {
+    fn get_collision_entity(&self) -> &Entity;
     fn on_collision(&mut self, entity: &mut Entity);
+
+    // Broadphase Query with the Mask of our Entity -> on_collision only sees Layers we care about
+    fn resolve_collisions(&mut self, world: &World)
+    {
+        let entity = self.get_collision_entity();
+        if entity.collision_mask.is_empty() || !entity.collision_is_enabled
+        {
+            return;
+        }
+        let handle = entity.handle;
+        let overlaps = world.query_overlaps(entity.transform.rect, entity.collision_mask);
+        for mut other in overlaps
+        {
+            if other.handle != handle
+            {
+                self.on_collision(&mut other);
+            }
+        }
+    }
 }
 
 pub fn resolve_windowborder(rect: Rect, level_progress: f32) -> bool
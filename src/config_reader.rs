@@ -0,0 +1,79 @@
+use super::*;
+
+// Shared by every "key = value" File of the Game -> Manifest, Palette, Tile Atlas, Backgrounds, Spawners, Triggers, Hazards & Input
+
+// Lines that carry Content with their Line Number (1 = first Line)  |  "#" starts a Comment
+pub fn config_lines(text: &str) -> impl Iterator<Item = (usize, &str)>
+{
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.split('#').next().unwrap_or("").trim()))
+        .filter(|(_, line)| !line.is_empty())
+}
+// "key = value" -> both trimmed
+pub fn config_entry(line_number: usize, line: &str) -> Result<(&str, &str), String>
+{
+    match line.split_once('=')
+    {
+        Some((key, value)) => Ok((key.trim(), value.trim())),
+        None => Err(format!("Line {}: missing '='", line_number)),
+    }
+}
+
+// "1, 2.5, 3" -> None if any of them is not a Number
+pub fn parse_numbers(value: &str) -> Option<Vec<f32>>
+{
+    value.split(',').map(|n| n.trim().parse::<f32>()).collect::<Result<Vec<f32>, _>>().ok()
+}
+// "x, y" -> Cells, Cell Offsets or Pixel Offsets
+pub fn parse_vec2(value: &str) -> Option<Vec2>
+{
+    match parse_numbers(value).as_deref()
+    {
+        Some([x, y]) => Some(vec2(*x, *y)),
+        _ => None,
+    }
+}
+// "r, g, b" or "r, g, b, a" -> 0 - 255 per Channel
+pub fn parse_color(value: &str) -> Result<Color, String>
+{
+    let channels: Vec<u8> = value.split(',')
+        .map(|c| c.trim().parse::<u8>())
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| "Color has to be 0 - 255".to_string())?;
+    match channels.as_slice()
+    {
+        [r, g, b] => Ok(color_u8!(*r, *g, *b, 255)),
+        [r, g, b, a] => Ok(color_u8!(*r, *g, *b, *a)),
+        _ => Err("Color needs 3 or 4 Channels".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn skips_comments_and_counts_lines()
+    {
+        let text = "# Header\nname = Test # trailing\n\n  [block]  \nbroken\n";
+        let lines: Vec<(usize, &str)> = config_lines(text).collect();
+        assert_eq!(lines, vec![(2, "name = Test"), (4, "[block]"), (5, "broken")]);
+        assert_eq!(config_entry(2, lines[0].1), Ok(("name", "Test")));
+        assert_eq!(config_entry(5, lines[2].1), Err("Line 5: missing '='".to_string()));
+    }
+
+    #[test]
+    fn parses_numbers_and_colors()
+    {
+        assert_eq!(parse_numbers("1, 2.5,3"), Some(vec![1.0, 2.5, 3.0]));
+        assert_eq!(parse_numbers("1, x"), None);
+        assert_eq!(parse_vec2(" 4, -2 "), Some(vec2(4.0, -2.0)));
+        assert_eq!(parse_vec2("4"), None);
+        assert_eq!(parse_color("255, 0, 128"), Ok(color_u8!(255, 0, 128, 255)));
+        assert_eq!(parse_color("1, 2, 3, 4"), Ok(color_u8!(1, 2, 3, 4)));
+        assert!(parse_color("256, 0, 0").is_err());
+        assert!(parse_color("1, 2").is_err());
+    }
+}
@@ -1,5 +1,4 @@
 use super::*;
-use macroquad::audio::{play_sound, PlaySoundParams};
 
 #[derive(Clone)]
 pub struct Enemy
@@ -14,9 +13,12 @@ impl Enemy
 {
     pub fn new(index: usize,world: &mut World) -> Self
     {
+        let mut entity = Entity::new("Enemy", "Enemy", world);
+        entity.set_collision(CollisionLayer::ENEMY, CollisionLayer::PLAYER | CollisionLayer::PLAYER_PROJECTILE);
+
         Self { 
             enemy_id: index,
-            entity: Entity::new("Enemy", "Enemy", world), 
+            entity, 
             variant: EnemyVariant::get_variant(EnemyType::Default, world),
             in_viewspace: false,
         }
@@ -44,9 +46,7 @@ impl Enemy
         //println!("WF Speed: {}, name: {}", weapon.entity.entity_params.firespeed, weapon.entity.name);
         if weapon.shoot( misslepool, world)
         {
-            let mut params = PlaySoundParams::default();
-            params.volume = 0.15;
-            play_sound( self.variant.sfx_shoot.sound.unwrap(), params)
+            world.audio.play(&self.variant.sfx_shoot, 0.15);
         }
     }
 }
@@ -67,24 +67,26 @@ impl GameObject for Enemy
         let color =  color_u8!( self.variant.color.r * 255.0, self.variant.color.g * 255.0, self.variant.color.b * 255.0, 0);
         if self.entity.entity_params.health <= 0.0
         {
-            let mut params = PlaySoundParams::default();
-            params.volume = 0.5;
-            play_sound(world.assets.get_asset_by_name("explosion_2".to_string()).unwrap().get_sound_data().sound.unwrap(), params );
-
-            world.particlesystem_pool.spawn_system_at_position(self.entity.transform.position, 128, explosion_settings( self.variant.color, WHITE, color));
+            world.events.publish(GameEvent::EntityKilled { 
+                handle: self.entity.handle, 
+                source: EventSource::Enemy, 
+                position: self.entity.transform.position, 
+                color: self.variant.color 
+            });
+            world.events.publish(GameEvent::ScoreAwarded { points: self.variant.points, position: self.entity.transform.position });
 
             self.reset();
-            world.add_scorepoints( self.variant.points);
             world.set_entity(&mut self.entity);
             return;
         }
 
+        self.entity.transform.store_previous();
         self.entity.hit_cooldown();
 
         // MOVEMENT
         //println!("active {}", self.entity.is_active);
         let dir = (self.entity.transform.position - world.get_entity_by_tag("Player").as_ref().unwrap().transform.position).normalize();
-        let position = self.entity.transform.position - (dir * self.entity.entity_params.speed * get_frame_time());
+        let position = self.entity.transform.position - (dir * self.entity.entity_params.speed * get_tick_time());
         self.entity.transform.set_position(position);
         
         let rotation = f32::atan2(dir.x, dir.y) * -1.0;
@@ -100,7 +102,8 @@ impl GameObject for Enemy
         world.particlesystem_pool.spawn_system_at_position(
             self.entity.transform.position, 
             1, 
-            thruster_settings(spawn_position,vec2(-5.0, 0.0), color_u8!( color.r * 255.0, color.g * 255.0, color.b * 255.0, 0), vec2(-3.0, 3.0))
+            thruster_settings(spawn_position,vec2(-5.0, 0.0), color_u8!( color.r * 255.0, color.g * 255.0, color.b * 255.0, 0), vec2(-3.0, 3.0), &mut world.random.cosmetic),
+            &mut world.random.cosmetic
         );
 
 
@@ -138,14 +141,15 @@ impl GameObject for Enemy
             None => {}
         }
 
+        let render_rect = self.entity.transform.get_render_rect();
         if self.variant.sprite == Texture2D::empty()
         {
-            draw_rectangle(self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.transform.rect.w, self.entity.transform.rect.h, self.entity.get_rect_color());
+            draw_rectangle(render_rect.x, render_rect.y, render_rect.w, render_rect.h, self.entity.get_rect_color());
         }else
         {
 
             let params = DrawTextureParams { dest_size: Some(self.entity.transform.get_fullsize()), rotation: self.entity.transform.rotation,..Default::default() };
-            draw_texture_ex(self.variant.sprite, self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.get_rect_color(), params);
+            draw_texture_ex(self.variant.sprite, render_rect.x, render_rect.y, self.entity.get_rect_color(), params);
         }
         
         if SHOW_COLLISION 
@@ -165,26 +169,24 @@ impl GameObject for Enemy
 }
 impl Collision for Enemy
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             //self.rect_color = GREEN;
             return;
         }
-        match entity.tag.as_str()
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER)
         {
-            "Player" => {
-                self.entity.entity_params.health = 0.0;
-            }
-            "Player Weapon Missle" => {
-                //println!("HIT!!!");
-                if self.entity.hit_feedback_timer <= 0.0
-                {
-                    self.entity.hit(&entity.entity_params);
-                }
-
+            self.entity.entity_params.health = 0.0;
+        }
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
+        {
+            //println!("HIT!!!");
+            if self.entity.hit_feedback_timer <= 0.0
+            {
+                self.entity.hit(&entity.entity_params);
             }
-            _ => {}
         }
     }
 } 
\ No newline at end of file
@@ -5,11 +5,16 @@ use super::*;
 pub struct EnemySpawner
 {
     pub entity: Entity,
-    spawner_type: EnemyType,
-    spawn_count: usize,
+    waves: Vec<SpawnWave>,
+    // Wave in Progress & its Spawn Steps so far
+    wave: usize,
     spawned: usize,
     spawn_duration: f32,
     time: f32,
+    // Level Offset the Camera has to reach before the first Wave
+    trigger: Option<f32>,
+    // Waits for a Stage Trigger
+    held: bool,
 }
 impl EnemySpawner
 {
@@ -17,16 +22,6 @@ impl EnemySpawner
     {
         let entity = Entity::new("EnemySpawner", "Spawner", world);
 
-        let enemy_type = match spawner_type {
-            0 => { EnemyType::Default},
-            1 => { EnemyType::Gunner},
-            2 => { EnemyType::Tank},
-            3 => { EnemyType::HeavyGunner},
-            4 => { EnemyType::Exploder},
-            5 => { EnemyType::Boss},
-            _ => { EnemyType::Default},
-        };
-
         // Spawn Duration based on Difficulty
         let mut spawn_duration = 3.5 - (0.2 * world.difficulty_level as f32);
         if spawn_duration <= 0.8 {spawn_duration = 0.8;}
@@ -34,38 +29,93 @@ impl EnemySpawner
 
         Self {
             entity: entity,
-            spawner_type: enemy_type,
-            spawn_count: count,
+            // Empty Spawners never spawn
+            waves: if count > 0 { vec![SpawnWave::single(spawner_type, count)] } else { Vec::new() },
+            wave: 0,
             spawned: 0,
             spawn_duration: spawn_duration,
             time: 0.2,
+            trigger: None,
+            held: false,
+        }
+    }
+    pub fn set_held(&mut self, held: bool)
+    {
+        self.held = held;
+    }
+    // Waves & Trigger of the Definition replace the Spawnmap Count & Type
+    pub fn apply_definition(&mut self, definition: &SpawnerDefinition)
+    {
+        self.waves = definition.waves.clone();
+        self.trigger = definition.get_trigger_offset();
+        self.wave = 0;
+        self.spawned = 0;
+        if let Some(wave) = self.waves.first() { self.time += wave.delay; }
+    }
+    // Ids of the Spawnmap Blue Channel -> None for Ids without an EnemyType
+    pub fn enemy_type_from_id(id: usize) -> Option<EnemyType>
+    {
+        match id {
+            0 => Some(EnemyType::Default),
+            1 => Some(EnemyType::Gunner),
+            2 => Some(EnemyType::Tank),
+            3 => Some(EnemyType::HeavyGunner),
+            4 => Some(EnemyType::Exploder),
+            5 => Some(EnemyType::Boss),
+            _ => None,
         }
     }
     pub fn set_transform(&mut self, transform: &Transform)
     {
         self.entity.transform = transform.clone();
     }
+    pub fn is_exhausted(&self) -> bool
+    {
+        self.wave >= self.waves.len()
+    }
+    pub fn add_start_delay(&mut self, delay: f32)
+    {
+        self.time += delay;
+    }
     pub fn update(&mut self, enemypool: &mut EnemyPool, world: &mut World) {
 
-        if self.spawned < self.spawn_count
+        let triggered = !self.held && match self.trigger
+        {
+            Some(offset) => world.level_offset >= offset,
+            None => true,
+        };
+        if triggered && !self.is_exhausted()
         { 
             if self.time > 0.0
             {
-                self.time -= 1.0 * get_frame_time();
+                self.time -= 1.0 * get_tick_time();
             }else {
-                self.time = self.spawn_duration;
+                let wave = &self.waves[self.wave];
+                self.time = wave.interval.unwrap_or(self.spawn_duration);
                 // Spawn
                 self.spawn(enemypool, world);
                 self.spawned += 1;
+                // Wave done -> Delay of the next one
+                if self.spawned >= self.waves[self.wave].count
+                {
+                    self.wave += 1;
+                    self.spawned = 0;
+                    if let Some(next) = self.waves.get(self.wave) { self.time = next.delay; }
+                }
             }
             //println!("{}", self.time );
         }
         world.set_entity(&mut self.entity);
-        //println!("{} / {}", self.spawned , self.spawn_count);
     }
+    // One Enemy per Formation Offset -> Types cycle through the Wave
     pub fn spawn(&mut self ,enemypool: &mut EnemyPool, world: &mut World)
     {
-        //println!("spawn");
-        enemypool.spawn_enemy( self.entity.transform.position + self.entity.transform.get_halfsize(), &self.spawner_type, world);
+        let wave = &self.waves[self.wave];
+        let center = self.entity.transform.position + self.entity.transform.get_halfsize();
+        for (i, offset) in wave.formation.iter().enumerate()
+        {
+            let enemy_type = EnemySpawner::enemy_type_from_id(wave.get_enemy_type(self.spawned * wave.formation.len() + i)).unwrap_or(EnemyType::Default);
+            enemypool.spawn_enemy(center + *offset, &enemy_type, world);
+        }
     }
 }
\ No newline at end of file
@@ -1,5 +1,4 @@
 use super::*;
-use macroquad::rand::{gen_range, RandomRange};
 pub struct EnemyPool
 {
     pool: Vec<Enemy>,
@@ -37,6 +36,7 @@ impl EnemyPool
                 self.pool[slot].init(world);
                 self.pool[slot].entity.SetActive(true);
                 self.pool[slot].entity.transform.set_position( spawner_position);
+                self.pool[slot].entity.transform.store_previous();
 
                 self.active_pool.push(self.pool[slot].clone());
                 world.set_entity(&mut self.pool[slot].entity);
@@ -46,6 +46,10 @@ impl EnemyPool
             }
         }
     }
+    pub fn get_alive_count(&self) -> usize
+    {
+        self.pool.iter().filter(|e| e.entity.is_active).count()
+    }
     fn get_free_slot(&self) -> Option<usize>
     {
         for i in 0..self.pool.len()
@@ -94,10 +98,13 @@ impl GameObject for EnemyPool
     fn late_update(&mut self, world: &mut World) {
         for enemy in self.pool.iter_mut()
         {
-            for entity in world.get_actives().iter_mut()
+            if !enemy.entity.is_active
             {
-                enemy.on_collision(entity);
+                continue;
             }
+            let health = enemy.entity.entity_params.health;
+            enemy.resolve_collisions(world);
+            world.events.publish_damage(&enemy.entity, health, EventSource::Enemy);
         }
     }
     fn draw(&mut self) {
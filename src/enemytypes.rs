@@ -63,10 +63,10 @@ impl EnemyVariant
             weapon: weapon, 
             points: points,
 
-            sfx_move:       world.assets.get_asset_by_name("fire_1".to_string()).unwrap().get_sound_data(),
-            sfx_shoot:      world.assets.get_asset_by_name("laserShoot_1".to_string()).unwrap().get_sound_data(),
-            sfx_on_hit:     world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
-            sfx_explosion:  world.assets.get_asset_by_name("explosion_1".to_string()).unwrap().get_sound_data(),
+            sfx_move:       world.assets.get_asset("fire_1").get_sound_data(),
+            sfx_shoot:      world.assets.get_asset("laserShoot_1").get_sound_data(),
+            sfx_on_hit:     world.assets.get_asset("hurt_sound_1").get_sound_data(),
+            sfx_explosion:  world.assets.get_asset("explosion_1").get_sound_data(),
         }
     }
     fn tank_variant(world: &mut World) -> Self
@@ -97,10 +97,10 @@ impl EnemyVariant
             weapon: Some(weapon), 
             points: points,
 
-            sfx_move:       world.assets.get_asset_by_name("fire_1".to_string()).unwrap().get_sound_data(),
-            sfx_shoot:      world.assets.get_asset_by_name("laserShoot_1".to_string()).unwrap().get_sound_data(),
-            sfx_on_hit:     world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
-            sfx_explosion:  world.assets.get_asset_by_name("explosion_1".to_string()).unwrap().get_sound_data(),
+            sfx_move:       world.assets.get_asset("fire_1").get_sound_data(),
+            sfx_shoot:      world.assets.get_asset("laserShoot_1").get_sound_data(),
+            sfx_on_hit:     world.assets.get_asset("hurt_sound_1").get_sound_data(),
+            sfx_explosion:  world.assets.get_asset("explosion_1").get_sound_data(),
         }
     }
     fn gunner_variant(world: &mut World) -> Self
@@ -131,10 +131,10 @@ impl EnemyVariant
             weapon: Some(weapon), 
             points: points,
 
-            sfx_move:       world.assets.get_asset_by_name("fire_1".to_string()).unwrap().get_sound_data(),
-            sfx_shoot:      world.assets.get_asset_by_name("laserShoot_2".to_string()).unwrap().get_sound_data(),
-            sfx_on_hit:     world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
-            sfx_explosion:  world.assets.get_asset_by_name("explosion_1".to_string()).unwrap().get_sound_data(),
+            sfx_move:       world.assets.get_asset("fire_1").get_sound_data(),
+            sfx_shoot:      world.assets.get_asset("laserShoot_2").get_sound_data(),
+            sfx_on_hit:     world.assets.get_asset("hurt_sound_1").get_sound_data(),
+            sfx_explosion:  world.assets.get_asset("explosion_1").get_sound_data(),
         }
     }
 }
@@ -9,6 +9,9 @@ pub struct Transform
 
     pub scale: f32,
     pub rect: Rect,
+
+    // Position of the last Simulation Step -> used for Render Interpolation
+    pub previous_position: Vec2,
 }
 impl Transform
 {
@@ -20,6 +23,7 @@ impl Transform
             size: vec2(0.0,0.0),
             scale: 0.0,
             rect: Rect::new(0.0,0.0,0.0,0.0),
+            previous_position: vec2(0.0,0.0),
         }
     }
     pub fn default() -> Self
@@ -30,6 +34,7 @@ impl Transform
             size: vec2(10.0,10.0),
             scale: 1.0,
             rect: Rect::new(0.0,0.0,10.0,10.0),
+            previous_position: vec2(0.0,0.0),
         }
     }
     pub fn set_position(&mut self, new_position: Vec2)
@@ -57,6 +62,17 @@ impl Transform
         self.rect.w = self.size.x * new_scale;
         self.rect.h = self.size.y * new_scale;
     }
+    // Call at the Start of a Simulation Step
+    pub fn store_previous(&mut self)
+    {
+        self.previous_position = self.position;
+    }
+    // Rect between the last two Simulation Steps -> only for Drawing, Collision keeps using rect
+    pub fn get_render_rect(&self) -> Rect
+    {
+        let offset = (self.position - self.previous_position) * (1.0 - get_render_alpha());
+        Rect::new(self.rect.x - offset.x, self.rect.y - offset.y, self.rect.w, self.rect.h)
+    }
     pub fn get_fullsize(&self) -> Vec2 {self.size * self.scale}
     pub fn get_halfsize(&self) -> Vec2 {return (self.size * self.scale) * 0.5}
     pub fn get_centered_position(&self) -> Vec2
@@ -65,10 +81,27 @@ impl Transform
     }
 }
 
+// Generational Handle -> Index into the World Slots + Generation of that Slot
+// A Handle becomes stale as soon as its Slot gets removed or reused
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct EntityHandle
+{
+    pub index: usize,
+    pub generation: u32,
+}
+impl EntityHandle
+{
+    pub fn invalid() -> Self
+    {
+        Self { index: usize::MAX, generation: 0 }
+    }
+    pub fn is_invalid(&self) -> bool { self.index == usize::MAX }
+}
+
 #[derive(Clone,PartialEq)]
 pub struct Entity
 {
-    pub id: usize,
+    pub handle: EntityHandle,
     pub name: String,
     pub tag: String,
     
@@ -79,6 +112,8 @@ pub struct Entity
     pub in_view: bool,
 
     pub collision_is_enabled: bool,
+    pub collision_layer: CollisionLayer,
+    pub collision_mask: CollisionLayer,
     pub sprite_is_active: bool,
 
 
@@ -92,7 +127,7 @@ impl Entity
     pub fn new(name: &str, tag: &str, world: &mut World) -> Self
     {
         let mut instance = Self {
-            id: world.entities.len(),
+            handle: EntityHandle::invalid(),
             name: name.to_string(),
             tag: tag.to_string(),
             transform: Transform::default(),
@@ -101,6 +136,8 @@ impl Entity
             
             entity_params: EntityParams::default(),
             collision_is_enabled: true,
+            collision_layer: CollisionLayer::NONE,
+            collision_mask: CollisionLayer::NONE,
             sprite_is_active: true,
 
             rect_color: WHITE,
@@ -117,6 +154,12 @@ impl Entity
         self.is_active = state;
     }
 
+    pub fn set_collision(&mut self, layer: CollisionLayer, mask: CollisionLayer)
+    {
+        self.collision_layer = layer;
+        self.collision_mask = mask;
+    }
+
     pub fn get_rect_color(&self) -> Color {self.hit_color}
     pub fn set_rect_color(&mut self, color: Color){self.rect_color = color;}
     pub fn hit(&mut self, entity_params: &EntityParams)
@@ -128,7 +171,7 @@ impl Entity
     {
         if self.hit_feedback_timer > 0.0
         {
-            self.hit_feedback_timer -= get_frame_time();
+            self.hit_feedback_timer -= get_tick_time();
             self.hit_color = RED;
         }else
         {
@@ -48,6 +48,12 @@ impl EntitySettings{
         params.health = 10.0 + (1.0 * world.difficulty_level as f32);
         params.armor = 3.5 + (0.5 * world.difficulty_level as f32);
         params.damage = 2.5 + (1.35 * world.difficulty_level as f32);
+        params
+    }
+    pub fn hazard_settings( world: &mut World) -> EntityParams
+    {
+        let mut params = EntityParams::default();
+        params.damage = 3.0 + (1.25 * world.difficulty_level as f32);
         return params;
     }
     pub fn destructible_settings( world: &mut World) -> EntityParams
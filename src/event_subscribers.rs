@@ -0,0 +1,129 @@
+use super::*;
+
+// ---------------------------------
+// Score
+
+pub struct ScoreSubscriber {}
+impl Default for ScoreSubscriber
+{
+    fn default() -> Self { Self::new() }
+}
+impl ScoreSubscriber
+{
+    pub fn new() -> Self { Self {} }
+}
+impl EventSubscriber for ScoreSubscriber
+{
+    fn on_event(&mut self, event: &GameEvent, world: &mut World) {
+        if let GameEvent::ScoreAwarded { points, .. } = event
+        {
+            world.add_scorepoints(*points);
+        }
+    }
+}
+
+// ---------------------------------
+// Sound
+
+pub struct SoundSubscriber
+{
+    sfx_hurt: SoundData,
+    sfx_player_shot: SoundData,
+    sfx_player_explosion: SoundData,
+    sfx_enemy_explosion: SoundData,
+    sfx_spawner_explosion: SoundData,
+}
+impl SoundSubscriber
+{
+    pub fn new(world: &mut World) -> Self
+    {
+        Self {
+            sfx_hurt:               world.assets.get_asset("hurt_sound_1").get_sound_data(),
+            sfx_player_shot:        world.assets.get_asset("fire_1").get_sound_data(),
+            sfx_player_explosion:   world.assets.get_asset("explosion_1").get_sound_data(),
+            sfx_enemy_explosion:    world.assets.get_asset("explosion_2").get_sound_data(),
+            sfx_spawner_explosion:  world.assets.get_asset("explosion_3").get_sound_data(),
+        }
+    }
+}
+impl EventSubscriber for SoundSubscriber
+{
+    fn on_event(&mut self, event: &GameEvent, world: &mut World) {
+        match event
+        {
+            GameEvent::EntityDamaged { source, .. } => {
+                match source
+                {
+                    EventSource::Player         => { world.audio.play(&self.sfx_hurt, 0.15); }
+                    EventSource::EnemySpawner   => { world.audio.play(&self.sfx_hurt, 0.15); }
+                    EventSource::TrapWall       => { world.audio.play(&self.sfx_hurt, 0.07); }
+                    EventSource::Destructible   => { world.audio.play(&self.sfx_hurt, 0.07); }
+                    _ => {}
+                }
+            }
+            GameEvent::EntityKilled { source, .. } => {
+                match source
+                {
+                    EventSource::Player         => { world.audio.play(&self.sfx_player_explosion, 0.5); }
+                    EventSource::Enemy          => { world.audio.play(&self.sfx_enemy_explosion, 0.5); }
+                    EventSource::EnemySpawner   => { world.audio.play(&self.sfx_spawner_explosion, 0.5); }
+                    _ => {}
+                }
+            }
+            GameEvent::MissileFired { layer, .. } if *layer == CollisionLayer::PLAYER_PROJECTILE => {
+                world.audio.play(&self.sfx_player_shot, 0.15);
+            }
+            GameEvent::SoundRequested { name } => {
+                let sound = world.assets.get_asset(name).get_sound_data();
+                world.audio.play(&sound, 0.5);
+            }
+            _ => {}
+        }
+    }
+}
+
+// ---------------------------------
+// Particle Effects
+
+pub struct EffectSubscriber {}
+impl Default for EffectSubscriber
+{
+    fn default() -> Self { Self::new() }
+}
+impl EffectSubscriber
+{
+    pub fn new() -> Self { Self {} }
+}
+impl EventSubscriber for EffectSubscriber
+{
+    fn on_event(&mut self, event: &GameEvent, world: &mut World) {
+        match event
+        {
+            GameEvent::EntityKilled { source, position, color, .. } => {
+                let fade_color = color_u8!( color.r * 255.0, color.g * 255.0, color.b * 255.0, 0);
+                let rng = &mut world.random.cosmetic;
+                match source
+                {
+                    EventSource::Enemy => {
+                        world.particlesystem_pool.spawn_system_at_position(*position, 128, explosion_settings( *color, WHITE, fade_color, rng), rng);
+                    }
+                    EventSource::EnemySpawner => {
+                        world.particlesystem_pool.spawn_system_at_position(*position, 64, explosion_settings(MAGENTA, RED, fade_color, rng), rng);
+                    }
+                    EventSource::Turret => {
+                        world.particlesystem_pool.spawn_system_at_position(*position, 64, explosion_settings(YELLOW, RED, fade_color, rng), rng);
+                    }
+                    EventSource::TrapWall | EventSource::Destructible => {
+                        world.particlesystem_pool.spawn_system_at_position(*position, 64, destruction_settings(LIGHTGRAY, WHITE, DARKGRAY, rng), rng);
+                    }
+                    EventSource::Player => {}
+                }
+            }
+            GameEvent::MissileFired { position, direction, .. } => {
+                let rng = &mut world.random.cosmetic;
+                world.particlesystem_pool.spawn_system_at_position(*position, 16, fire_settings(*position, *direction, rng), rng);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use super::*;
+
+// Who caused / received a Gameplay Event -> Subscribers pick Sounds and Effects from it
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EventSource
+{
+    Player, Enemy, TrapWall, Destructible, EnemySpawner, Turret
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum GameEvent
+{
+    EntityDamaged { handle: EntityHandle, source: EventSource, position: Vec2, damage: f32 },
+    EntityKilled { handle: EntityHandle, source: EventSource, position: Vec2, color: Color },
+    ScoreAwarded { points: i32, position: Vec2 },
+    MissileFired { shooter: EntityHandle, layer: CollisionLayer, position: Vec2, direction: Vec2 },
+    LevelEndReached,
+    // Stage Trigger Actions
+    BannerShown { text: String },
+    SoundRequested { name: String },
+}
+
+// Events published during a Frame -> drained once per Frame by the Game and handed to every Subscriber
+#[derive(Clone)]
+pub struct EventQueue
+{
+    pending: Vec<GameEvent>,
+}
+impl Default for EventQueue
+{
+    fn default() -> Self { Self::new() }
+}
+impl EventQueue
+{
+    pub fn new() -> Self
+    {
+        Self { pending: Vec::new() }
+    }
+    pub fn publish(&mut self, event: GameEvent)
+    {
+        self.pending.push(event);
+    }
+    pub fn drain(&mut self) -> Vec<GameEvent>
+    {
+        std::mem::take(&mut self.pending)
+    }
+    // Collision Callbacks have no World Access -> compare the Health before and after resolving
+    pub fn publish_damage(&mut self, entity: &Entity, health_before: f32, source: EventSource)
+    {
+        if entity.entity_params.health < health_before
+        {
+            self.publish(GameEvent::EntityDamaged { 
+                handle: entity.handle, 
+                source, 
+                position: entity.transform.position, 
+                damage: health_before - entity.entity_params.health 
+            });
+        }
+    }
+    pub fn clear(&mut self) { self.pending.clear(); }
+    pub fn len(&self) -> usize { self.pending.len() }
+    pub fn is_empty(&self) -> bool { self.pending.is_empty() }
+}
+
+pub trait EventSubscriber
+{
+    fn on_event(&mut self, event: &GameEvent, world: &mut World);
+}
@@ -1,18 +1,22 @@
-use macroquad::audio::{play_sound, PlaySoundParams};
 use interpolation::*;
 use super::*;
+
+// Seconds a Stage Trigger Banner stays
+const BANNER_DURATION: f32 = 3.0;
+
 pub struct Game
 {
-    late_tick : f32,
-    fixed_tick: f32,
+    timestep: FixedTimestep,
 
-    camera: Camera2D,
-    render_target: RenderTarget,
+    renderer: Box<dyn RenderBackend>,
+    input: Box<dyn InputBackend>,
 
 
     local_score: i32,
-    last_score: i32,
-    high_score: i32,
+
+    // Scores, Progress & Settings -> stored between Launches (not in Headless Runs)
+    profile: Profile,
+    persist_profile: bool,
 
     player_settings: SavedEntitySettings,
 
@@ -24,28 +28,77 @@ pub struct Game
     pub available_levels: usize,
     pub level: Option<Level>,
     selected_level: usize,
+    // Manifest Entry of the loaded Stage -> generated Stages have no Entry in the Loader
+    current_stage: StageEntry,
     level_transition: BlackBlend,
 
     misslepool: MisslePool,
     enemypool: EnemyPool,
 
     player: Player,
+
+    event_subscribers: Vec<Box<dyn EventSubscriber>>,
+
+    // Input of the current Stage -> saved once the Stage ends (not in Headless Runs)
+    recording: Replay,
+    persist_replays: bool,
+    replay: Option<ReplayPlayer>,
+
+    // Action waiting for a new Binding in the Pause Menu
+    rebinding: Option<InputAction>,
+    rebind_cursor: usize,
+
+    // Assets & Stages that failed at Startup -> shown before the Main Menu
+    load_errors: Vec<LoadError>,
+
+    // Open Stage Editor -> stays alive while its Stage is playtested
+    editor: Option<StageEditor>,
+
+    // Stage Trigger Banner -> Text & Seconds left
+    banner: Option<(String, f32)>,
+
+    // Last Checkpoint of the current Stage -> Game Over continues from it if the Profile says so
+    checkpoint: Option<CheckpointState>,
+    // Continued Stage -> its Recording starts mid Stage and can not be replayed
+    continued: bool,
 }
 impl Game {
 
     pub fn Run(&mut self)
     {
         // Setup Camera
-        self.init_camera();
+        self.renderer.begin_frame();
+
+        // Input of this Frame
+        self.world.input = self.input.poll();
+
+        // Playtest ends on Pause or the Level End -> back to the Editor, nothing gets recorded
+        if self.editor.is_some() && self.gamestate == GameState::GameRunning
+            && (self.world.level_completed || self.world.input.is_released(InputAction::Pause))
+            {
+                self.return_to_editor();
+            }
 
         // Is Level Finished
-        if self.world.level_completed {
+        if self.world.level_completed && self.gamestate != GameState::Replay {
+            if self.gamestate == GameState::GameRunning
+            {
+                self.save_recording();
+            }
             self.gamestate = GameState::Transition;
             self.level_transition.blend_in();
-            self.level_transition.update_blend(self.world.level_offset);
+            self.level_transition.update_blend(self.world.get_render_offset());
             println!("Level Completed!");
         }
 
+        // Nothing to load a Level from -> stays on the Error Screen
+        if self.gamestate == GameState::LoadErrors
+        {
+            self.update_load_errors();
+            self.renderer.end_frame();
+            return;
+        }
+
         // Safety Check for Level
         if self.level.is_none()
         {
@@ -61,10 +114,15 @@ impl Game {
 
                 if self.level_transition.get_is_playing(){
                     // While In Blend
-                    self.update();
-                    self.late_update();
+                    self.timestep.accumulate(get_frame_time());
+                    while self.timestep.step()
+                    {
+                        self.simulate_tick();
+                    }
+                    set_render_alpha(self.timestep.alpha());
+                    self.dispatch_events();
                     self.draw();
-                    self.level_transition.update_blend(self.world.level_offset);
+                    self.level_transition.update_blend(self.world.get_render_offset());
                 }else{
                     // On Blend Finished
                     self.end_level();
@@ -72,85 +130,112 @@ impl Game {
                     self.level_transition.set_start_blend(BlendingType::BlendOut, 1.0);
                 }
             }
+            GameState::LoadErrors => {}
+            GameState::Editor => {
+                self.update_editor();
+            }
             GameState::MainMenu => {
-                if is_key_released(KeyCode::Space) || is_mouse_button_released(MouseButton::Left)
+                if self.world.input.is_released(InputAction::Confirm)
                 {
                     self.gamestate = GameState::GameRunning;
                     self.level_transition.set_start_blend(BlendingType::BlendOut, 1.0);
                 }
+                if self.world.input.is_released(InputAction::OpenEditor)
+                {
+                    self.open_editor(self.selected_level.min(self.level_loader.levels.len() - 1));
+                    return;
+                }
+                if self.world.input.is_released(InputAction::ToggleCheckpoint)
+                {
+                    self.profile.settings.continue_from_checkpoint = !self.profile.settings.continue_from_checkpoint;
+                    self.save_profile();
+                }
+                if self.world.input.is_released(InputAction::WatchReplay)
+                {
+                    match Replay::load(LAST_REPLAY_FILE)
+                    {
+                        Ok(replay) => {
+                            self.start_replay(replay);
+                            self.level_transition.set_start_blend(BlendingType::BlendOut, 1.0);
+                            return;
+                        }
+                        Err(error) => { println!("Could not load Replay: {}", error); }
+                    }
+                }
                 // UI
-                let text = "Press [Space or Left Mousebutton] to Start!";
+                let text = format!("Press [{}] to Start!", self.get_binding_text(InputAction::Confirm));
                 let text_size =  60.0;
                 let text_width = text.chars().count() as f32 * text_size;
                 let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
-                draw_text(text, centered_position, GAME_SIZE_Y * 0.5, text_size, WHITE);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5, text_size, WHITE);
 
+                let text = format!("Press [{}] to watch the last Replay", self.get_binding_text(InputAction::WatchReplay));
+                let text_size =  30.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 60.0, text_size, GRAY);
 
-                
+                let text = format!("High Score: {}  |  Best Stage: {}", self.profile.high_score, self.profile.best_stage + 1);
+                let text_size =  30.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 100.0, text_size, GRAY);
+
+                let text = format!("Press [{}] to edit the Stages", self.get_binding_text(InputAction::OpenEditor));
+                let text_size =  30.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 140.0, text_size, GRAY);
+
+                let text = format!("Press [{}] to toggle Continue from Checkpoint: {}", self.get_binding_text(InputAction::ToggleCheckpoint), if self.profile.settings.continue_from_checkpoint { "On" } else { "Off" });
+                let text_size =  30.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 180.0, text_size, GRAY);
 
             }
             GameState::GamePaused => {
-                if is_key_released(KeyCode::Tab) 
+                if self.world.input.is_released(InputAction::Pause) && self.rebinding.is_none()
                 {
                     self.gamestate = GameState::GameRunning;
                 }
-                // Camera Position
-                let level_position = vec2( GAME_SIZE_X * 0.5 + self.world.level_offset , GAME_SIZE_Y * 0.5);
-                self.camera.target = level_position;
-                set_camera(&self.camera);
                 // Draw Game
                 self.draw();
 
                 // Pause UI
-                draw_rectangle(self.world.level_offset, 0.0 , GAME_SIZE_X, GAME_SIZE_Y, color_u8!(0,0,0,220));
-                let text = "Paused! Press TAB again!";
+                let render_offset = self.world.get_render_offset();
+                draw_rectangle(render_offset, 0.0 , GAME_SIZE_X, GAME_SIZE_Y, color_u8!(0,0,0,220));
+                let text = format!("Paused! Press {} again!", self.get_binding_text(InputAction::Pause));
                 let text_size =  60.0;
                 let text_width = text.chars().count() as f32 * text_size;
                 let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
-                draw_text(text, centered_position + self.world.level_offset, GAME_SIZE_Y * 0.5, text_size, WHITE);
+                draw_text(text.as_str(), centered_position + render_offset, GAME_SIZE_Y * 0.5, text_size, WHITE);
+
+                self.update_rebinding();
+                self.draw_bindings(render_offset);
             }
             GameState::GameRunning => {
                 // Pause Game
-                if is_key_released(KeyCode::Tab)
+                if self.world.input.is_released(InputAction::Pause)
                 {
                     self.gamestate = GameState::GamePaused;
                 }
 
-                // Update Game -> Every possible Frame
-                self.update();
-                
-                // Fixed Update -> Mostly used for the Particle Updates
-                if self.fixed_tick <= 0.0
+                // Simulate Game -> fixed Steps, independent of the Framerate
+                self.timestep.accumulate(get_frame_time());
+                while self.timestep.step()
                 {
-                    self.fixed_update();
-                    self.fixed_tick = FIXED_UPDATE_TICK;
-                }else 
-                {
-                    self.fixed_tick -= get_frame_time();
+                    self.simulate_tick();
+                    if self.gamestate != GameState::GameRunning
+                    {
+                        break;
+                    }
                 }
+                // Leftover Time -> Draw between the last two Steps
+                set_render_alpha(self.timestep.alpha());
 
-                // Later Update -> for heavy stuff (Collision)
-                if self.late_tick <= 0.0
-                {
-                    self.late_update();
-                    self.late_tick = LATE_UPDATE_TICK;
-                }else 
-                {
-                    self.late_tick -= get_frame_time();
-                }
-                
-                // Check for Players health
-                if self.player.entity.entity_params.health <= 0.0
-                {
-                    self.gamestate = GameState::GameOver;
-                    self.world.level_offset = 0.0;
-                    
-                    let mut params = PlaySoundParams::default();
-                    params.volume = 0.5;
-                    play_sound(self.world.assets.get_asset_by_name("explosion_1".to_string()).unwrap().get_sound_data().sound.unwrap(), params );
-                    
-                    
-                }
+                // Hand this Frames Events to the Subscribers
+                self.dispatch_events();
 
                 // Draw Game
                 self.draw();
@@ -159,12 +244,50 @@ impl Game {
                 self.update_score();   
                 
             }
+            GameState::Replay => {
+                // Leave Replay
+                let mut finished = self.world.input.is_released(InputAction::Pause);
+
+                // Recorded Input -> one Frame per Simulation Step
+                self.timestep.accumulate(get_frame_time());
+                while !finished && self.timestep.step()
+                {
+                    match self.replay.as_mut().unwrap().next_frame()
+                    {
+                        Some(frame) => { self.world.input = frame; }
+                        None => { finished = true; break; }
+                    }
+                    self.simulate_tick();
+                    finished = self.gamestate != GameState::Replay || self.world.level_completed;
+                }
+                set_render_alpha(self.timestep.alpha());
+
+                self.dispatch_events();
+                self.draw();
+                self.update_score();
+
+                let render_offset = self.world.get_render_offset();
+                draw_text("REPLAY - Press TAB to leave", 5.0 + render_offset, GAME_SIZE_Y - 20.0, 30.0, RED);
+
+                if finished
+                {
+                    self.stop_replay();
+                }
+            }
             GameState::GameOver => {
+                // Died while playtesting
+                if self.editor.is_some()
+                {
+                    self.return_to_editor();
+                    self.renderer.end_frame();
+                    return;
+                }
                 
                 // Update Highscore
-                if self.local_score > self.high_score
+                if self.local_score > self.profile.high_score
                 {
-                    self.high_score = self.local_score;
+                    self.profile.high_score = self.local_score;
+                    self.save_profile();
                 }
                 // Reset Level
                 self.end_level();
@@ -185,23 +308,58 @@ impl Game {
                 draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 0.0, text_size, WHITE);
 
                 // Last Score
-                let text = format!("Last Score: {}", self.last_score);
+                let text = format!("Last Score: {}", self.profile.last_score);
                 let text_size =  25.0;
                 let text_width = text.chars().count() as f32 * text_size;
                 let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
                 draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 30.0, text_size, WHITE);
                 
                 // High Score
-                let text = format!("High Score: {}", self.high_score);
+                let text = format!("High Score: {}", self.profile.high_score);
                 let text_size =  60.0;
                 let text_width = text.chars().count() as f32 * text_size;
                 let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
                 draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 100.0, text_size, WHITE);
 
-                // Restart Game
-                if is_key_pressed(KeyCode::Space) || is_mouse_button_released(MouseButton::Left)
+                // Seed of this Run
+                let text = format!("Seed: {:X}", self.world.random.get_seed());
+                let text_size =  25.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 140.0, text_size, WHITE);
+
+                // Continue from the last Checkpoint -> the Run goes on, its Score is not submitted yet
+                let can_continue = self.profile.settings.continue_from_checkpoint && self.checkpoint.is_some();
+                if can_continue
+                {
+                    let text = format!("Press [{}] to continue from the last Checkpoint", self.get_binding_text(InputAction::Confirm));
+                    let text_size =  25.0;
+                    let text_width = text.chars().count() as f32 * text_size;
+                    let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                    draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 180.0, text_size, GRAY);
+                }
+                if can_continue && self.world.input.is_released(InputAction::Confirm)
                 {
-                    self.last_score = self.local_score;
+                    self.continue_from_checkpoint();
+                    self.level_transition.set_start_blend(BlendingType::BlendOut, 1.0);
+                    return;
+                }
+
+                // Restart Game -> ends the Run and submits its Score, Confirm restarts too while there is nothing to continue
+                let restart_keys = match can_continue
+                {
+                    true => self.get_binding_text(InputAction::Restart),
+                    false => format!("{} or {}", self.get_binding_text(InputAction::Confirm), self.get_binding_text(InputAction::Restart)),
+                };
+                let text = format!("Press [{}] to restart", restart_keys);
+                let text_size =  25.0;
+                let text_width = text.chars().count() as f32 * text_size;
+                let centered_position = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2);
+                draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 210.0, text_size, GRAY);
+                if self.world.input.is_released(InputAction::Restart) || (!can_continue && self.world.input.is_released(InputAction::Confirm))
+                {
+                    self.profile.submit_score(self.local_score);
+                    self.save_profile();
                     self.restart();
                     self.level_transition.set_start_blend(BlendingType::BlendOut, 1.0);
                     return;
@@ -232,7 +390,7 @@ impl Game {
                 draw_text(text.as_str(), centered_position, GAME_SIZE_Y * 0.5 + 0.0, text_size, WHITE);
 
 
-                if is_key_pressed(KeyCode::Space)
+                if self.world.input.is_released(InputAction::Confirm)
                 {
                     self.next_level();
                     return;
@@ -241,70 +399,108 @@ impl Game {
         }
 
         // Update Level Blending
-        self.level_transition.update_blend(self.world.level_offset);
+        self.level_transition.update_blend(self.world.get_render_offset());
 
         // Update Camera Space  ->  Especially used for Resizing the Game Window
-        self.draw_camera_to_screen();
+        self.renderer.end_frame();
     }
     pub fn end_level(&mut self)
     {
         self.world.level_completed = false;
-        self.world.level_offset = 0.0;
-        let level_position = vec2( GAME_SIZE_X * 0.5, GAME_SIZE_Y * 0.5);
-        self.camera.target = level_position;
-        set_camera(&self.camera);
+        self.world.audio.stop_music();
+        self.world.set_level_offset(0.0);
+        self.renderer.set_view(0.0);
     }
 
-    pub fn init_camera(&mut self)
+    pub fn restart(&mut self)
     {
-        // Setup Camera
-        let camera_rect = Rect::new(0.0,0.0, GAME_SIZE_X , GAME_SIZE_Y );
-        let mut camera =Camera2D::from_display_rect(camera_rect);
-        camera.render_target = Some(self.render_target);
-        set_camera(&camera);
-
-        //Draw & Clear Background
-        clear_background(BLACK);
-        draw_rectangle( 0.0, 0.0,  GAME_SIZE_X as f32 ,  GAME_SIZE_Y as f32 , BLACK);
+        self.start_stage(0, seed_from_time());
     }
-
-    pub fn draw_camera_to_screen(&mut self)
+    // Fresh Run from the given Stage -> no Menu, no Blending
+    pub fn start_stage(&mut self, stage: usize, seed: u64)
     {
-        // Set Default Camera
-        set_default_camera();
-        // calculate game view size based on window size
-        let game_diff_w = GAME_SIZE_X / GAME_SIZE_X as f32;
-        let game_diff_h = GAME_SIZE_Y / GAME_SIZE_Y as f32;
-        let aspect_diff = game_diff_w.min(game_diff_h);
-        
-        let scaled_game_size_w = screen_width() as f32 * aspect_diff;
-        let scaled_game_size_h = screen_height() as f32 * aspect_diff;
-        
-        let width_padding = (screen_width() - scaled_game_size_w) * 0.5f32;
-        let height_padding = (screen_height() - scaled_game_size_h) * 0.5f32;
-        
-        // Draw Game on Screen
-        clear_background(BLACK);
-        draw_texture_ex(
-            self.render_target.texture,
-            width_padding,
-            height_padding,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(Vec2::new(scaled_game_size_w, scaled_game_size_h)),
-                flip_y: true,
-                ..Default::default()
-            },
-        );
-
+        self.start_from_header(ReplayHeader { 
+            seed, 
+            stage, 
+            difficulty: 0, 
+            health: EntitySettings::player_settings().health 
+        });
     }
+    pub fn start_from_header(&mut self, header: ReplayHeader)
+    {
+        self.checkpoint = None;
+        self.continued = false;
+        self.enter_stage(header);
+        self.gamestate = GameState::GameRunning;
+    }
+    pub fn start_replay(&mut self, replay: Replay)
+    {
+        println!("Start Replay: Stage {} | Seed {:X} | {} Ticks", replay.header.stage, replay.header.seed, replay.len());
+        self.checkpoint = None;
+        self.continued = false;
+        self.enter_stage(replay.header);
+        self.replay = Some(ReplayPlayer::new(replay));
+        self.gamestate = GameState::Replay;
+    }
+    pub fn stop_replay(&mut self)
+    {
+        self.replay = None;
+        self.end_level();
+        // Fresh Run waiting behind the Menu
+        self.restart();
+        self.gamestate = GameState::MainMenu;
+    }
+    // Rebuilds the Stage of the last Checkpoint with its Snapshot -> false without one
+    pub fn continue_from_checkpoint(&mut self) -> bool
+    {
+        let checkpoint = match self.checkpoint.clone()
+        {
+            Some(checkpoint) => checkpoint,
+            None => { return false; }
+        };
+        println!("Continue from Checkpoint: Stage {} | Column {}", checkpoint.stage, checkpoint.column);
+        self.enter_stage(ReplayHeader { 
+            seed: checkpoint.seed, 
+            stage: checkpoint.stage, 
+            difficulty: checkpoint.difficulty, 
+            health: checkpoint.player.health 
+        });
+        self.place_view(checkpoint.level_offset, checkpoint.view_position + vec2(checkpoint.level_offset, 0.0));
+
+        let mut level = self.level.take().unwrap();
+        level.restore_progress(&checkpoint.progress, checkpoint.column, &mut self.world);
+        self.level = Some(level.clone());
+        self.world.level = Some(level);
 
-    pub fn restart(&mut self)
+        self.player_settings.save(checkpoint.player);
+        self.player.entity.entity_params = checkpoint.player;
+        self.world.add_scorepoints(checkpoint.score);
+        self.local_score = checkpoint.score;
+
+        self.continued = true;
+        self.gamestate = GameState::GameRunning;
+        true
+    }
+    // Moves the View to the Offset with the Player at the World Position
+    fn place_view(&mut self, offset: f32, player_position: Vec2)
+    {
+        self.world.set_level_offset(offset);
+        self.player.entity.transform.set_position(player_position);
+        self.player.entity.transform.store_previous();
+    }
+    // Rebuilds the Stage exactly as described by the Header
+    fn enter_stage(&mut self, header: ReplayHeader)
     {
         // Reload everything
         self.world.reload();
-        self.selected_level = 0;
-        self.world.difficulty_level = 0;
+        self.world.random = WorldRandom::new(header.seed);
+        self.selected_level = header.stage;
+        self.world.difficulty_level = header.difficulty;
+
+        let mut player_settings = EntitySettings::player_settings();
+        player_settings.health = header.health;
+        self.player_settings.save(player_settings);
+
         self.load_level();
 
         self.misslepool = MisslePool::new();
@@ -313,33 +509,65 @@ impl Game {
         self.enemypool = EnemyPool::new();
         self.enemypool.create_pool(128, &mut self.world);
 
-        self.player = Player::new(&mut self.world, &EntitySettings::player_settings());
+        self.player = Player::new(&mut self.world, &self.player_settings.get_settings());
         self.player.init(&mut self.world);
 
         self.local_score = 0;
-        self.gamestate = GameState::GameRunning;
-        self.world.level_offset = -GAME_SIZE_X * 0.5;
+        self.world.set_level_offset(-GAME_SIZE_X * 0.5);
+        self.timestep.reset();
+    }
+    fn save_profile(&mut self)
+    {
+        if self.persist_profile
+        {
+            self.profile.save();
+        }
+    }
+    fn save_recording(&mut self)
+    {
+        // Playtests are no Runs
+        if !self.persist_replays || self.editor.is_some() || self.continued
+        {
+            return;
+        }
+        match self.recording.save(LAST_REPLAY_FILE)
+        {
+            Ok(()) => { println!("Replay saved: {} ({} Ticks)", LAST_REPLAY_FILE, self.recording.len()); }
+            Err(error) => { println!("Could not save Replay: {}", error); }
+        }
     }
     pub async fn init() -> Self
     {
-        // Create Render Target for Game
-        let game_render_target = render_target(GAME_SIZE_X as u32, GAME_SIZE_Y as u32);
-        game_render_target.texture.set_filter(FilterMode::Linear);
+        // Create Render Target & Camera for Game
+        let renderer = MacroquadRenderer::new();
         request_new_screen_size(GAME_SIZE_X , GAME_SIZE_Y);
         next_frame().await;
-        // Create & Set Camera
-        let camera_rect = Rect::new(0.0,0.0, GAME_SIZE_X , GAME_SIZE_Y );
-        let mut camera =Camera2D::from_display_rect(camera_rect);
-        camera.render_target = Some(game_render_target);
-        set_camera(&camera);
 
-        
         // Create Game Systems
-        let mut world = World::new().await;
+        let world = World::new().await;
         
         let mut loader = LevelLoader::new();
         loader.level_loader_init().await;
 
+        let mut game = Game::from_parts(world, loader, Box::new(renderer), Box::new(MacroquadInput::new()));
+        game.profile = Profile::load();
+        game.persist_profile = true;
+        game.persist_replays = true;
+        game.world.audio.set_volume(game.profile.settings.sfx_volume);
+        game
+    }
+    // No Window -> Stages load from Disk, nothing gets drawn or played, Input comes from the given Backend
+    pub fn init_headless(level_folder: &str, input: Box<dyn InputBackend>) -> Self
+    {
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(level_folder);
+
+        let world = World::new_headless(loader.levels.len());
+        Game::from_parts(world, loader, Box::new(NullRenderer::new()), input)
+    }
+    pub fn from_parts(mut world: World, loader: LevelLoader, renderer: Box<dyn RenderBackend>, input: Box<dyn InputBackend>) -> Self
+    {
+        world.random = WorldRandom::new(seed_from_time());
 
         let mut misslepool = MisslePool::new();
         misslepool.create_pool(512, &mut world);
@@ -353,22 +581,37 @@ impl Game {
         player_settings.save(EntitySettings::player_settings());
         let mut player = Player::new(&mut world, &player_settings.get_settings());
         player.init(&mut world);
+
+        let event_subscribers: Vec<Box<dyn EventSubscriber>> = vec![
+            Box::new(ScoreSubscriber::new()),
+            Box::new(SoundSubscriber::new(&mut world)),
+            Box::new(EffectSubscriber::new()),
+        ];
         
+        let mut load_errors = world.assets.errors.clone();
+        load_errors.extend(loader.errors.iter().cloned());
+        // Without a single Stage the Game can not leave the Error Screen
+        if loader.levels.is_empty() && load_errors.is_empty()
+        {
+            load_errors.push(LoadError::new(&loader.manifest.name, LoadErrorKind::Invalid, "Level Pack has no Stages"));
+        }
+        let gamestate = if load_errors.is_empty() { GameState::MainMenu } else { GameState::LoadErrors };
+
         // Setup Game Data
         Self {
-            late_tick: 0.0,
-            fixed_tick: 0.0,
+            timestep: FixedTimestep::new(SIMULATION_TICK),
 
             local_score: 0,  
-            last_score: 0,
-            high_score: 0,
 
-            gamestate: GameState::MainMenu,
+            profile: Profile::new(),
+            persist_profile: false,
+
+            gamestate,
 
             player_settings: player_settings,
             //viewspace: viewspace,
-            camera: camera,
-            render_target: game_render_target,
+            renderer,
+            input,
 
             world: world,
 
@@ -376,25 +619,101 @@ impl Game {
             level_loader: loader,
             level: None,
             selected_level:0,
+            current_stage: StageEntry::new(""),
             level_transition: BlackBlend::default(),
 
             misslepool: misslepool,
             enemypool: enemypool,
 
             player: player,
+
+            event_subscribers,
+
+            recording: Replay::new(ReplayHeader { seed: 0, stage: 0, difficulty: 0, health: 0.0 }),
+            persist_replays: false,
+            replay: None,
+
+            rebinding: None,
+            rebind_cursor: 0,
+
+            load_errors,
+
+            editor: None,
+
+            banner: None,
+
+            checkpoint: None,
+            continued: false,
+        }
+
+    }
+
+    // ---------------------------------
+    // Headless
+
+    // One Simulation Step without a Frame around it -> returns false once the Stage is over
+    pub fn step_headless(&mut self) -> bool
+    {
+        if self.gamestate != GameState::GameRunning
+        {
+            return false;
         }
+        self.world.input = self.input.poll();
+        self.simulate_tick();
+        self.dispatch_events();
+        self.update_score();
 
+        if self.world.level_completed
+        {
+            self.world.level_completed = false;
+            self.gamestate = GameState::LevelCompleted;
+        }
+        self.gamestate == GameState::GameRunning
+    }
+    pub fn get_gamestate(&self) -> &GameState { &self.gamestate }
+    pub fn get_score(&self) -> i32 { self.local_score }
+    pub fn get_player_health(&self) -> f32 { self.player.entity.entity_params.health }
+    pub fn get_player_params(&self) -> &EntityParams { &self.player.entity.entity_params }
+    pub fn get_selected_level(&self) -> usize { self.selected_level }
+    pub fn get_seed(&self) -> u64 { self.world.random.get_seed() }
+    pub fn get_recording(&self) -> &Replay { &self.recording }
+    pub fn get_checkpoint(&self) -> Option<&CheckpointState> { self.checkpoint.as_ref() }
+
+    // One Simulation Step -> Movement, Weapons, Spawners and Collision all advance by SIMULATION_TICK
+    pub fn simulate_tick(&mut self)
+    {
+        if self.gamestate == GameState::GameRunning
+        {
+            self.recording.record(self.world.input);
+        }
+        self.update();
+        self.fixed_update();
+        self.late_update();
+
+        // Check for Players health
+        let is_playing = self.gamestate == GameState::GameRunning || self.gamestate == GameState::Replay;
+        if is_playing && self.player.entity.entity_params.health <= 0.0
+        {
+            if self.gamestate == GameState::GameRunning
+            {
+                self.save_recording();
+            }
+            self.gamestate = GameState::GameOver;
+            self.world.set_level_offset(0.0);
+            
+            self.world.events.publish(GameEvent::EntityKilled { 
+                handle: self.player.entity.handle, 
+                source: EventSource::Player, 
+                position: self.player.entity.transform.position, 
+                color: WHITE 
+            });
+        }
     }
     pub fn update(&mut self)
     {
         // Update World Entites
         self.world.update_actives();
         
-        // Update Camera
-        let level_position = vec2( GAME_SIZE_X * 0.5 + self.world.level_offset , GAME_SIZE_Y * 0.5);
-        self.camera.target = level_position;
-        set_camera(&self.camera);
-        
         // Update Missles
         self.misslepool.update(&mut self.world);
         
@@ -408,14 +727,28 @@ impl Game {
         self.player_settings.save(self.player.entity.entity_params);
         self.level_update();
 
+        // Banner fades out
+        if let Some((_, time)) = self.banner.as_mut()
+        {
+            *time -= get_tick_time();
+            if *time <= 0.0
+            {
+                self.banner = None;
+            }
+        }
+
     }
     pub fn fixed_update(&mut self)
     {
+        self.world.rebuild_broadphase();
         self.world.fixed_update();
         self.level_fixed_update();
     }
     pub fn late_update(&mut self)
     {
+        // Collision Queries of this Tick
+        self.world.rebuild_broadphase();
+
         // Update Level
         self.level_late_update();
         
@@ -426,7 +759,16 @@ impl Game {
     }
     pub fn draw(&mut self)
     {
-        // Draw Level
+        if !self.renderer.is_drawing()
+        {
+            return;
+        }
+        // Camera Position -> interpolated between the last two Steps
+        let render_offset = self.world.get_render_offset();
+        self.renderer.set_view(render_offset);
+
+        // Draw Background & Level
+        self.world.level.as_mut().unwrap().draw_background(render_offset);
         self.world.level.as_mut().unwrap().draw();
 
         // Draw Particles
@@ -438,28 +780,245 @@ impl Game {
         self.enemypool.draw();
         
         // Draw Background Panel
-        draw_rectangle(self.world.level_offset, 0.0, GAME_SIZE_X, 60.0, color_u8!(0,0,0,190));
+        draw_rectangle(render_offset, 0.0, GAME_SIZE_X, 60.0, color_u8!(0,0,0,190));
 
         // Draw Score UI
         let text = format!("Local Score: {}", self.local_score);
         let text_size =  50.0;
         let text_width = text.chars().count() as f32 * text_size;
-        let centered_position_x = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2) + self.world.level_offset;
+        let centered_position_x = ( GAME_SIZE_X * 0.5) - ( text_width * 0.2) + render_offset;
         draw_text(text.as_str(),centered_position_x, 40.0, text_size, WHITE);
 
         // Draw Health Points
         let health_rect_width = f32::lerp(&0.0, &250.0, &(self.player.entity.entity_params.health * 0.01));
 
-        draw_rectangle(self.world.level_offset + 300.0, 15.0, 250.0, 15.0, color_u8!(64,0,32,255));
-        draw_rectangle(self.world.level_offset + 300.0, 16.0, health_rect_width, 13.0, color_u8!(255,0,128,255));
-        draw_text(format!("HP: {}",self.player.entity.entity_params.health).as_str(), 570.0 + self.world.level_offset , 30.0, 30.0, WHITE);
+        draw_rectangle(render_offset + 300.0, 15.0, 250.0, 15.0, color_u8!(64,0,32,255));
+        draw_rectangle(render_offset + 300.0, 16.0, health_rect_width, 13.0, color_u8!(255,0,128,255));
+        draw_text(format!("HP: {}",self.player.entity.entity_params.health).as_str(), 570.0 + render_offset , 30.0, 30.0, WHITE);
         // Draw FPS
-        draw_text(format!("FPS: {}", get_fps()).as_str(), 5.0 + self.world.level_offset, 30.0, 25.0, WHITE);
+        draw_text(format!("FPS: {}", get_fps()).as_str(), 5.0 + render_offset, 30.0, 25.0, WHITE);
 
 
         // Draw Difficulty & Current Stage
-        draw_text(format!("Difficulty Level: {}", self.world.difficulty_level + 1).as_str(), (GAME_SIZE_X * 0.5 + 500.0) + self.world.level_offset, 30.0, 25.0, WHITE);
-        draw_text(format!("Stage: {} / {}", self.selected_level, self.world.available_levels).as_str(), (GAME_SIZE_X * 0.5 + 300.0) + self.world.level_offset, 30.0, 25.0, WHITE);
+        draw_text(format!("Difficulty Level: {}", self.world.difficulty_level + 1).as_str(), (GAME_SIZE_X * 0.5 + 500.0) + render_offset, 30.0, 25.0, WHITE);
+        let stage_text = if self.selected_level < self.available_levels
+        {
+            format!("{}  ({} / {})", self.current_stage.name, self.selected_level + 1, self.available_levels)
+        }
+        else
+        {
+            format!("{}  (Difficulty {})", self.current_stage.name, self.world.difficulty_level)
+        };
+        draw_text(stage_text.as_str(), (GAME_SIZE_X * 0.5 + 300.0) + render_offset, 30.0, 25.0, WHITE);
+
+        // Draw Stage Banner
+        if let Some((text, time)) = &self.banner
+        {
+            let text_size = 60.0;
+            let text_width = text.chars().count() as f32 * text_size;
+            let centered_position_x = (GAME_SIZE_X * 0.5) - (text_width * 0.2) + render_offset;
+            let alpha = (*time / 0.5).min(1.0);
+            draw_rectangle(render_offset, GAME_SIZE_Y * 0.3 - 50.0, GAME_SIZE_X, 70.0, Color::new(0.0, 0.0, 0.0, 0.6 * alpha));
+            draw_text(text.as_str(), centered_position_x, GAME_SIZE_Y * 0.3, text_size, Color::new(1.0, 1.0, 1.0, alpha));
+        }
+    }
+
+    // ---------------------------------
+    // Rebinding -> Up & Down select an Action, Enter waits for the next Key or Mousebutton as its Binding
+    // Fixed Keys on purpose -> a broken Binding can always be repaired
+
+    fn update_rebinding(&mut self)
+    {
+        let action_map = match self.input.get_action_map()
+        {
+            Some(action_map) => action_map,
+            None => { return; }
+        };
+        match self.rebinding
+        {
+            Some(action) => {
+                if let Some(binding) = ActionMap::capture_binding()
+                {
+                    action_map.rebind(action, binding);
+                    action_map.save(INPUT_CONFIG_FILE);
+                    println!("{} bound to {}", action.get_name(), binding.get_name());
+                    self.rebinding = None;
+                }
+            }
+            None => {
+                let count = InputAction::all().len();
+                if is_key_pressed(KeyCode::Down) { self.rebind_cursor = (self.rebind_cursor + 1) % count; }
+                if is_key_pressed(KeyCode::Up) { self.rebind_cursor = (self.rebind_cursor + count - 1) % count; }
+                if is_key_pressed(KeyCode::Enter)
+                {
+                    self.rebinding = Some(InputAction::all()[self.rebind_cursor]);
+                }
+            }
+        }
+    }
+    // ---------------------------------
+    // Stage Editor
+
+    fn open_editor(&mut self, stage: usize)
+    {
+        let data = self.level_loader.levels[stage].clone();
+        let palette = self.level_loader.find_palette(&data.palette);
+        self.editor = Some(StageEditor::new(stage, data, palette));
+        self.end_level();
+        self.gamestate = GameState::Editor;
+    }
+    fn update_editor(&mut self)
+    {
+        let stage_count = self.level_loader.levels.len();
+        let help: Vec<String> = EDITOR_HELP.iter().map(|(actions, label)| {
+            let keys: Vec<String> = actions.iter().map(|action| self.get_binding_text(*action)).collect();
+            format!("[{}] {}", keys.join("] ["), label)
+        }).collect();
+        let editor = self.editor.as_mut().unwrap();
+        let command = editor.update(&self.world.input, stage_count);
+
+        self.renderer.set_view(editor.scroll);
+        editor.draw();
+        editor.draw_ui(&self.world.input, stage_count, help.join("  ").as_str());
+
+        // Edits stay in the Loader -> the Stage plays as edited until the Game is closed
+        if editor.take_edited()
+        {
+            self.level_loader.levels[editor.stage_index] = editor.data.clone();
+        }
+        match command
+        {
+            EditorCommand::None => {}
+            EditorCommand::Playtest => { self.playtest(); }
+            EditorCommand::Export => {
+                let folder = self.level_loader.folder.clone();
+                editor.status = match editor.export(&folder)
+                {
+                    Ok(files) => format!("Exported {}", files.join(", ")),
+                    Err(error) => format!("Export failed: {}", error),
+                };
+                println!("{}", editor.status);
+                self.level_loader.levels[editor.stage_index] = editor.data.clone();
+            }
+            EditorCommand::SwitchStage(stage) => { self.open_editor(stage); }
+            EditorCommand::Leave => {
+                self.editor = None;
+                // Fresh Run waiting behind the Menu
+                self.restart();
+                self.gamestate = GameState::MainMenu;
+            }
+        }
+    }
+    // Plays the edited Stage starting at the Scroll Position of the Editor
+    fn playtest(&mut self)
+    {
+        let editor = self.editor.as_mut().unwrap();
+        if editor.data.level_end.is_empty()
+        {
+            editor.status = "Paint a Level End before playtesting".to_string();
+            return;
+        }
+        let stage = editor.stage_index;
+        let scroll = editor.scroll;
+        editor.status.clear();
+
+        self.start_stage(stage, seed_from_time());
+        // Stages start half a Screen before Column 0 -> Player moves along with the View
+        let shift = scroll + GAME_SIZE_X * 0.5;
+        let position = self.player.entity.transform.position + vec2(shift, 0.0);
+        self.place_view(scroll, position);
+    }
+    fn return_to_editor(&mut self)
+    {
+        self.end_level();
+        self.world.level_completed = false;
+        self.gamestate = GameState::Editor;
+        // This Frames Input already ended the Playtest -> the Editor must not see it again
+        let aim = self.world.input.aim;
+        self.world.input = InputFrame::new();
+        self.world.input.aim = aim;
+    }
+
+    fn update_load_errors(&mut self)
+    {
+        let playable = !self.level_loader.levels.is_empty();
+        if playable && self.world.input.is_released(InputAction::Confirm)
+        {
+            self.gamestate = GameState::MainMenu;
+            return;
+        }
+
+        draw_text("Some Files could not be loaded", 60.0, 100.0, 60.0, RED);
+        let text_size = 24.0;
+        let mut position_y = 160.0;
+        // More than fit on the Screen -> the Rest is only counted, the Console has all of them
+        let max_lines = 32;
+        for error in self.load_errors.iter().take(max_lines)
+        {
+            draw_text(error.to_string().as_str(), 60.0, position_y, text_size, WHITE);
+            position_y += text_size;
+        }
+        if self.load_errors.len() > max_lines
+        {
+            draw_text(format!("... and {} more", self.load_errors.len() - max_lines).as_str(), 60.0, position_y, text_size, GRAY);
+        }
+
+        let text = if playable { "Press [Space or Left Mousebutton] to continue with Placeholders" } else { "No playable Stage -> fix the Files above and restart" };
+        draw_text(text, 60.0, GAME_SIZE_Y - 60.0, 30.0, GRAY);
+    }
+    // Bindings of an Action for the UI -> the Action Name when the Input Backend can not be rebound
+    fn get_binding_text(&mut self, action: InputAction) -> String
+    {
+        match self.input.get_action_map()
+        {
+            Some(action_map) => {
+                let names: Vec<String> = action_map.get_bindings(action).iter().map(|b| b.get_name()).collect();
+                names.join("/")
+            }
+            None => action.get_name(),
+        }
+    }
+    fn draw_bindings(&mut self, render_offset: f32)
+    {
+        let rebinding = self.rebinding;
+        let cursor = self.rebind_cursor;
+        let action_map = match self.input.get_action_map()
+        {
+            Some(action_map) => action_map,
+            None => { return; }
+        };
+        let text_size = 22.0;
+        draw_text("[Up/Down] select an Action  [Enter] rebind it", GAME_SIZE_X * 0.35 + render_offset, GAME_SIZE_Y * 0.5 + 60.0, text_size, WHITE);
+        let mut position_y = GAME_SIZE_Y * 0.5 + 90.0;
+        for (index, action) in InputAction::all().iter().enumerate()
+        {
+            let names: Vec<String> = action_map.get_bindings(*action).iter().map(|b| b.get_name()).collect();
+            let text = match rebinding
+            {
+                Some(waiting) if waiting == *action => format!("{}: press a Key...", action.get_name()),
+                _ => format!("{}: {}", action.get_name(), names.join(", ")),
+            };
+            let color = if index == cursor { WHITE } else { GRAY };
+            draw_text(text.as_str(), GAME_SIZE_X * 0.35 + render_offset, position_y, text_size, color);
+            position_y += text_size;
+        }
+    }
+
+    pub fn dispatch_events(&mut self)
+    {
+        // Events published by Subscribers land in the next Frame
+        let events = self.world.events.drain();
+        for event in events.iter()
+        {
+            if let GameEvent::BannerShown { text } = event
+            {
+                self.banner = Some((text.clone(), BANNER_DURATION));
+            }
+            for subscriber in self.event_subscribers.iter_mut()
+            {
+                subscriber.on_event(event, &mut self.world);
+            }
+        }
     }
 
     pub fn update_score(&mut self)
@@ -469,6 +1028,8 @@ impl Game {
 
     pub fn next_level(&mut self) {
         self.world.reload_for_next_level();
+        self.checkpoint = None;
+        self.continued = false;
         if self.selected_level < self.available_levels -1
         {
             self.selected_level += 1;
@@ -478,8 +1039,16 @@ impl Game {
             player_settings.health += 25.0;
             if player_settings.health > 100.0 { player_settings.health = 100.0;}
             self.player_settings.save(player_settings);
+        }else if self.level_loader.manifest.generated_stages {
+            // Past the Campaign -> every Stage is a new generated one
+            self.selected_level += 1;
+            self.world.difficulty_level += 1;
+
+            let mut player_settings = self.player_settings.get_settings();
+            player_settings.health = (player_settings.health + 25.0).min(100.0);
+            self.player_settings.save(player_settings);
         }else {
-            self.selected_level = 5;
+            self.selected_level = self.level_loader.manifest.loop_stage;
             // Increase Difficulty after Level
             self.world.difficulty_level += 1;
 
@@ -487,6 +1056,10 @@ impl Game {
             player_settings.health = 100.0;
             self.player_settings.save(player_settings);
         }
+        if self.profile.submit_stage(self.selected_level)
+        {
+            self.save_profile();
+        }
         // Load Level
         println!("selected {} / available {}", self.selected_level, self.available_levels);
         self.load_level();
@@ -502,28 +1075,84 @@ impl Game {
         self.player.init(&mut self.world);
 
         self.gamestate = GameState::GameRunning;
-        println!("Count: {}", self.world.entities.len());
+        println!("Count: {}", self.world.entity_count());
 
-        self.world.level_offset = -GAME_SIZE_X * 0.5;
+        self.world.set_level_offset(-GAME_SIZE_X * 0.5);
+        self.timestep.reset();
     }
 
     
     pub fn load_level(&mut self)
     {
         //println!("Loader Data: {}", self.level_loader.levels[self.selected_level].enemy_spawner.len() );
-        let mut level = Level::new(&mut self.world, self.level_loader.levels[self.selected_level].clone());
+        // Same Stage + Seed -> same Sequence
+        self.world.random.begin_stage(self.selected_level);
+        self.recording = Replay::new(ReplayHeader { 
+            seed: self.world.random.get_seed(), 
+            stage: self.selected_level, 
+            difficulty: self.world.difficulty_level, 
+            health: self.player_settings.get_settings().health 
+        });
+        let level_data = self.get_stage_data(self.selected_level);
+        self.current_stage = level_data.stage.clone();
+        self.world.level_speed = level_data.stage.scroll_speed;
+        self.world.scroll_speed = level_data.stage.scroll_speed;
+        self.world.audio.play_music(&level_data.music);
+        self.banner = None;
+        let mut level = Level::new(&mut self.world, level_data);
         level.init(&mut self.world);
         self.level = Some(level.clone());
         self.world.level =  Some(level);
     }
 
+    // Campaign Stages come from the Pack -> every Stage after it is generated from the Run Seed & Difficulty
+    fn get_stage_data(&self, stage: usize) -> LoadedLevelData
+    {
+        if stage < self.level_loader.levels.len()
+        {
+            return self.level_loader.levels[stage].clone();
+        }
+        let mut generator = StageGenerator::for_stage(self.world.random.get_seed(), stage, self.world.difficulty_level);
+        generator.player_size = self.player.entity.transform.get_fullsize();
+        generator.player_speed = self.player_settings.get_settings().speed;
+        let name = format!("Generated {}", stage + 1 - self.level_loader.levels.len());
+        let mut level_data = generator.generate(&name);
+        level_data.tile_atlas = self.level_loader.tile_atlas.clone();
+        level_data.background = self.level_loader.find_background(&level_data.stage);
+        println!("{} generated from Seed {:X}", name, generator.seed);
+        level_data
+    }
+
     pub fn level_update(&mut self)
     {
         //self.level.as_mut().unwrap().late_update(self, misslepool);
         if self.level.is_some() {
             let mut lvl = self.level.as_mut().unwrap().clone();
+            lvl.stream(&mut self.world);
             lvl.update(&mut self.world);
             lvl.spawer_update( &mut  self.enemypool, &mut  self.world);
+            lvl.update_triggers(self.player.entity.transform.rect, &mut self.enemypool, &mut self.world);
+            // Playtests & Replays take no Snapshots
+            if let Some(column) = lvl.reach_checkpoint(self.player.entity.transform.rect)
+            {
+                if self.gamestate == GameState::GameRunning && self.editor.is_none()
+                {
+                    self.checkpoint = Some(CheckpointState {
+                        stage: self.selected_level,
+                        seed: self.world.random.get_seed(),
+                        difficulty: self.world.difficulty_level,
+                        column,
+                        level_offset: self.world.level_offset,
+                        view_position: self.player.entity.transform.position - vec2(self.world.level_offset, 0.0),
+                        player: self.player.entity.entity_params,
+                        score: self.world.get_collected_scorepoints(),
+                        progress: lvl.get_progress(),
+                    });
+                    self.world.events.publish(GameEvent::BannerShown { text: "Checkpoint".to_string() });
+                    println!("Checkpoint at Column {}", column);
+                }
+            }
+            lvl.update_scroll(&self.enemypool, &mut self.world);
             self.level = Some(lvl.clone());
             self.world.level =  Some(lvl);
         }
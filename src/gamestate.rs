@@ -2,4 +2,4 @@ use super::*;
 
 #[derive(PartialEq, Debug)]
 pub enum GameState 
-{ GameRunning, GamePaused, MainMenu, GameOver, LevelCompleted, Transition}
\ No newline at end of file
+{ GameRunning, GamePaused, MainMenu, GameOver, LevelCompleted, Transition, Replay, LoadErrors, Editor}
\ No newline at end of file
@@ -0,0 +1,249 @@
+use super::*;
+
+// Palette Kind & Tiled Class of Hazard Markers -> Image Markers take their Settings from the hazards File
+pub const HAZARD_MARKER_KIND: &str = "hazard";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum HazardKind
+{
+    // Solid Block sliding along its Path -> blocks & hurts
+    Crusher,
+    // Spinning Blade, optionally sliding along its Path -> hurts
+    Blade,
+    // Beam switching on & off with its Period -> hurts while on
+    LaserGate,
+    // Solid Block that slides open once its Switch is destroyed
+    Door,
+}
+impl HazardKind
+{
+    pub fn from_name(name: &str) -> Option<HazardKind>
+    {
+        match name
+        {
+            "crusher" => Some(HazardKind::Crusher),
+            "blade" => Some(HazardKind::Blade),
+            "laser_gate" | "laser" => Some(HazardKind::LaserGate),
+            "door" => Some(HazardKind::Door),
+            _ => None,
+        }
+    }
+}
+
+// Moving or timed Level Element of a Stage
+#[derive(Clone, PartialEq, Debug)]
+pub struct StageHazard
+{
+    pub name: String,
+    pub kind: HazardKind,
+    // In Cells -> Start Position & Size
+    pub area: Rect,
+    // Cell Offsets from the Start -> travelled to the last one and back again
+    pub path: Vec<Vec2>,
+    // Cells per Second along the Path or while a Door opens
+    pub speed: f32,
+    // Laser Gate Seconds -> whole Cycle, on Part of it, Start Offset
+    pub period: f32,
+    pub on_time: f32,
+    pub phase: f32,
+    // Blade Turns per Second
+    pub spin: f32,
+    // Door Switch Cell
+    pub switch: Option<Vec2>,
+    // Damage per Hit -> None uses the Difficulty Damage
+    pub damage: Option<f32>,
+}
+impl StageHazard
+{
+    pub fn new(name: &str, kind: HazardKind, area: Rect) -> Self
+    {
+        Self { name: name.to_string(), kind, area, path: Vec::new(), speed: 2.0, period: 2.0, on_time: 1.0, phase: 0.0, spin: 1.0, switch: None, damage: None }
+    }
+
+    // Keys -> kind (crusher, blade, laser_gate, door), path = x, y; x, y (Cell Offsets), speed, period, on_time, phase, spin, switch = x, y, damage
+    pub fn from_properties(name: &str, area: Rect, properties: &TiledProperties) -> Result<Self, String>
+    {
+        let kind = match find_property(properties, "kind")
+        {
+            Some(kind) => HazardKind::from_name(kind.trim()).ok_or(format!("{}: unknown kind {}", name, kind))?,
+            None => { return Err(format!("{} has no kind", name)); }
+        };
+        let mut hazard = StageHazard::new(name, kind, area);
+        for (key, value) in properties.iter()
+        {
+            let value = value.trim();
+            match key.as_str()
+            {
+                "name" => { hazard.name = value.to_string(); }
+                "path" => {
+                    hazard.path = value.split(';')
+                        .map(parse_vec2)
+                        .collect::<Option<Vec<Vec2>>>()
+                        .ok_or(format!("{}: path needs x, y Offsets split by ';'", hazard.name))?;
+                }
+                "speed" => { hazard.speed = parse_positive(value).ok_or(format!("{}: speed has to be above 0", hazard.name))?; }
+                "period" => { hazard.period = parse_positive(value).ok_or(format!("{}: period has to be above 0", hazard.name))?; }
+                "on_time" => { hazard.on_time = value.parse::<f32>().map_err(|_| format!("{}: on_time is not a Number", hazard.name))?; }
+                "phase" => { hazard.phase = value.parse::<f32>().map_err(|_| format!("{}: phase is not a Number", hazard.name))?; }
+                "spin" => { hazard.spin = value.parse::<f32>().map_err(|_| format!("{}: spin is not a Number", hazard.name))?; }
+                "switch" => { hazard.switch = Some(parse_vec2(value).ok_or(format!("{}: switch needs x, y", hazard.name))?); }
+                "damage" => { hazard.damage = Some(value.parse::<f32>().map_err(|_| format!("{}: damage is not a Number", hazard.name))?); }
+                // Tiled Objects carry their own Properties too
+                _ => {}
+            }
+        }
+        match hazard.kind
+        {
+            HazardKind::Crusher if hazard.path.is_empty() => Err(format!("{}: a crusher needs a path", hazard.name)),
+            HazardKind::Door if hazard.switch.is_none() => Err(format!("{}: a door needs a switch", hazard.name)),
+            HazardKind::LaserGate if hazard.on_time <= 0.0 || hazard.on_time >= hazard.period => {
+                Err(format!("{}: on_time has to be between 0 and period", hazard.name))
+            }
+            _ => Ok(hazard),
+        }
+    }
+
+    // Format -> one "[hazard]" Block per Hazard with "area = x, y, w, h" (Cells) and the Keys of from_properties  |  "#" starts a Comment
+    pub fn parse_all(text: &str) -> Result<Vec<StageHazard>, String>
+    {
+        let mut blocks: Vec<(Option<Rect>, TiledProperties)> = Vec::new();
+        for (line_number, line) in config_lines(text)
+        {
+            if line == "[hazard]"
+            {
+                blocks.push((None, TiledProperties::new()));
+                continue;
+            }
+            let block = match blocks.last_mut()
+            {
+                Some(block) => block,
+                None => { return Err(format!("Line {}: expected [hazard] first", line_number)); }
+            };
+            let (key, value) = config_entry(line_number, line)?;
+            if key == "area"
+            {
+                match parse_numbers(value).as_deref()
+                {
+                    Some([x, y, w, h]) if *w > 0.0 && *h > 0.0 => { block.0 = Some(Rect::new(*x, *y, *w, *h)); }
+                    _ => { return Err(format!("Line {}: area needs x, y, w, h", line_number)); }
+                }
+                continue;
+            }
+            block.1.push((key.to_string(), value.to_string()));
+        }
+
+        let mut hazards = Vec::new();
+        for (i, (area, properties)) in blocks.iter().enumerate()
+        {
+            let name = format!("Hazard {}", i + 1);
+            let area = area.ok_or(format!("{} has no area", name))?;
+            hazards.push(StageHazard::from_properties(&name, area, properties)?);
+        }
+        Ok(hazards)
+    }
+
+    // Player Movement stops at it
+    pub fn is_blocking(&self) -> bool
+    {
+        self.kind == HazardKind::Crusher || self.kind == HazardKind::Door
+    }
+    // Cell Offset from the Start after this many Seconds -> back & forth along the Path
+    pub fn get_path_offset(&self, time: f32) -> Vec2
+    {
+        let mut points = vec![vec2(0.0, 0.0)];
+        points.extend(self.path.iter().cloned());
+        let length: f32 = points.windows(2).map(|p| p[0].distance(p[1])).sum();
+        if length <= 0.0
+        {
+            return vec2(0.0, 0.0);
+        }
+        // Way there, then the same Way back
+        let mut distance = (time * self.speed).rem_euclid(length * 2.0);
+        if distance > length
+        {
+            distance = length * 2.0 - distance;
+        }
+        for segment in points.windows(2)
+        {
+            let segment_length = segment[0].distance(segment[1]);
+            if distance <= segment_length
+            {
+                return segment[0] + (segment[1] - segment[0]) * (distance / segment_length.max(0.0001));
+            }
+            distance -= segment_length;
+        }
+        *points.last().unwrap()
+    }
+    pub fn is_on(&self, time: f32) -> bool
+    {
+        (time + self.phase).rem_euclid(self.period) < self.on_time
+    }
+}
+
+fn parse_positive(value: &str) -> Option<f32>
+{
+    value.parse::<f32>().ok().filter(|n| *n > 0.0)
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_hazards()
+    {
+        let text = "[hazard]\narea = 79, 0, 1, 24\nkind = laser_gate\nperiod = 3\non_time = 1\n[hazard]\narea = 130, 0, 2, 4\nkind = crusher\npath = 0, 8\nspeed = 4\n";
+        let hazards = StageHazard::parse_all(text).unwrap();
+        assert_eq!(hazards.len(), 2);
+        assert_eq!(hazards[0].kind, HazardKind::LaserGate);
+        assert!(hazards[0].is_on(0.5));
+        assert!(!hazards[0].is_on(1.5));
+        assert_eq!(hazards[1].kind, HazardKind::Crusher);
+        assert!(hazards[1].is_blocking());
+        // There in 2 Seconds, back again after 4
+        assert_eq!(hazards[1].get_path_offset(1.0), vec2(0.0, 4.0));
+        assert_eq!(hazards[1].get_path_offset(2.0), vec2(0.0, 8.0));
+        assert_eq!(hazards[1].get_path_offset(3.0), vec2(0.0, 4.0));
+    }
+    #[test]
+    fn rejects_broken_hazards()
+    {
+        assert!(StageHazard::parse_all("[hazard]\narea = 0, 0, 1, 1\n").is_err());
+        assert!(StageHazard::parse_all("[hazard]\narea = 0, 0, 1, 1\nkind = crusher\n").is_err());
+        assert!(StageHazard::parse_all("[hazard]\narea = 0, 0, 1, 1\nkind = door\n").is_err());
+        assert!(StageHazard::parse_all("[hazard]\narea = 0, 0, 1, 1\nkind = laser\non_time = 3\n").is_err());
+        assert!(StageHazard::parse_all("[hazard]\narea = 0, 0, 0, 1\nkind = blade\n").is_err());
+    }
+    #[test]
+    fn hazard_elements_follow_their_kind()
+    {
+        let text = "[hazard]\narea = 2, 0, 1, 24\nkind = laser_gate\nperiod = 2\non_time = 1\n[hazard]\narea = 6, 2, 2, 2\nkind = crusher\npath = 0, 8\nspeed = 4\n[hazard]\narea = 12, 4, 1, 4\nkind = door\nswitch = 12, 3\nspeed = 6\n";
+        let hazards = StageHazard::parse_all(text).unwrap();
+        let mut world = World::new_headless(1);
+        let mut elements: Vec<HazardElement> = hazards.iter().map(|hazard| HazardElement::new(hazard, LEVEL_SCALE, &mut world)).collect();
+
+        // Laser Gates only collide while on
+        elements[0].update(0.5, &mut world);
+        assert!(elements[0].entity.collision_is_enabled);
+        elements[0].update(1.5, &mut world);
+        assert!(!elements[0].entity.collision_is_enabled);
+
+        // Crushers travel their Path from the Area Start
+        let start = elements[1].entity.transform.rect.point();
+        elements[1].update(1.0, &mut world);
+        assert_eq!(elements[1].entity.transform.rect.point(), start + vec2(0.0, 4.0 * LEVEL_SCALE));
+        elements[1].update(2.0, &mut world);
+        assert_eq!(elements[1].entity.transform.rect.point(), start + vec2(0.0, 8.0 * LEVEL_SCALE));
+
+        // Doors stay shut until their Switch is shot, then slide open & stop blocking
+        for tick in 0..120 { elements[2].update(tick as f32 * get_tick_time(), &mut world); }
+        assert!(elements[2].entity.is_active);
+        assert_eq!(elements[2].entity.transform.rect.y, 4.0 * LEVEL_SCALE);
+        elements[2].switch.as_mut().unwrap().entity.is_active = false;
+        elements[2].update(0.0, &mut world);
+        assert!(elements[2].entity.transform.rect.y < 4.0 * LEVEL_SCALE);
+        for _ in 0..120 { elements[2].update(0.0, &mut world); }
+        assert!(!elements[2].entity.is_active);
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+
+// Input of one Frame -> Gameplay reads only Actions from this, never the Keyboard or Mouse directly
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct InputFrame
+{
+    // One Bit per InputAction
+    down: u32,
+    // Only set in the Frame the Action got released -> Menu Input
+    released: u32,
+
+    // Mouse in Game Space (0.0 - GAME_SIZE) -> independent of the Window Size
+    // Whole Pixels only -> Replays store it exactly
+    pub aim: Vec2,
+    // Mouse Wheel of this Frame -> Editor Scrolling only, not recorded
+    pub wheel: f32,
+}
+impl Default for InputFrame
+{
+    fn default() -> Self { Self::new() }
+}
+impl InputFrame
+{
+    pub fn new() -> Self
+    {
+        Self { down: 0, released: 0, aim: vec2(GAME_SIZE_X * 0.5, GAME_SIZE_Y * 0.5), wheel: 0.0 }
+    }
+    pub fn is_down(&self, action: InputAction) -> bool { self.down & (1 << action as u32) != 0 }
+    pub fn is_released(&self, action: InputAction) -> bool { self.released & (1 << action as u32) != 0 }
+    pub fn set_down(&mut self, action: InputAction, value: bool)
+    {
+        if value { self.down |= 1 << action as u32; } else { self.down &= !(1 << action as u32); }
+    }
+    pub fn set_released(&mut self, action: InputAction, value: bool)
+    {
+        if value { self.released |= 1 << action as u32; } else { self.released &= !(1 << action as u32); }
+    }
+    pub fn get_down_bits(&self) -> u32 { self.down }
+    pub fn set_down_bits(&mut self, bits: u32) { self.down = bits; }
+}
+
+pub trait InputBackend
+{
+    fn poll(&mut self) -> InputFrame;
+    // Only Backends with real Devices can be rebound
+    fn get_action_map(&mut self) -> Option<&mut ActionMap> { None }
+}
+
+// ---------------------------------
+// Keyboard & Mouse
+
+pub struct MacroquadInput
+{
+    action_map: ActionMap,
+}
+impl Default for MacroquadInput
+{
+    fn default() -> Self { Self::new() }
+}
+impl MacroquadInput
+{
+    pub fn new() -> Self
+    {
+        Self { action_map: ActionMap::load(INPUT_CONFIG_FILE) }
+    }
+}
+impl InputBackend for MacroquadInput
+{
+    fn poll(&mut self) -> InputFrame {
+        let mut frame = InputFrame::new();
+        for action in InputAction::all()
+        {
+            frame.set_down(action, self.action_map.is_down(action));
+            frame.set_released(action, self.action_map.is_released(action));
+        }
+
+        // Scalefactor -> Adjust relative mouseposition to screen scale
+        let scale_factor = vec2( GAME_SIZE_X, GAME_SIZE_Y) / vec2(screen_width(), screen_height());
+        frame.aim = vec2(mouse_position().0 * scale_factor.x, mouse_position().1 * scale_factor.y).round();
+        frame.wheel = mouse_wheel().1;
+        frame
+    }
+    fn get_action_map(&mut self) -> Option<&mut ActionMap> {
+        Some(&mut self.action_map)
+    }
+}
+
+// ---------------------------------
+// Scripted -> plays back a fixed List of Frames, then stays neutral
+
+pub struct ScriptedInput
+{
+    frames: Vec<InputFrame>,
+    cursor: usize,
+}
+impl ScriptedInput
+{
+    pub fn new(frames: Vec<InputFrame>) -> Self
+    {
+        Self { frames, cursor: 0 }
+    }
+    pub fn is_finished(&self) -> bool { self.cursor >= self.frames.len() }
+}
+impl InputBackend for ScriptedInput
+{
+    fn poll(&mut self) -> InputFrame {
+        if self.is_finished()
+        {
+            return InputFrame::new();
+        }
+        let frame = self.frames[self.cursor];
+        self.cursor += 1;
+        frame
+    }
+}
@@ -1,42 +1,261 @@
 
-use macroquad::audio::{play_sound, PlaySoundParams};
-use macroquad::rand::gen_range;
-
+use std::rc::Rc;
 use super::*;
 
+// Size of one Structure Pixel in the Game
+pub const LEVEL_SCALE: f32 = 45.0;
+// Rows every Stage has -> one Screen high
+pub const LEVEL_ROWS: u32 = (GAME_SIZE_Y / LEVEL_SCALE) as u32;
+
+// Columns instantiated & retired together
+pub const CHUNK_COLUMNS: usize = 8;
+// Pixel in front of the View a Chunk gets instantiated -> two Chunks more than the 600 Pixel Spawners wake up early
+const STREAM_AHEAD: f32 = GAME_SIZE_X + 2.0 * CHUNK_COLUMNS as f32 * LEVEL_SCALE;
+// Pixel behind the View a Chunk stays alive
+const STREAM_BEHIND: f32 = CHUNK_COLUMNS as f32 * LEVEL_SCALE;
+
 #[derive(Clone)]
 pub struct Level
 {
     leveldata: LevelData,
+    // Whole Stage by Column Chunk -> shared, the Level gets cloned every Step
+    chunks: Rc<Vec<LevelChunk>>,
+    next_chunk: usize,
+    // Element Counts of every instantiated Chunk, oldest first -> its Elements are at the Front of each List
+    live_chunks: Vec<ChunkCounts>,
+    scroll_curve: Rc<ScrollCurve>,
+    stage_speed: f32,
+    // First Scroll Lock not cleared yet
+    next_lock: usize,
+    background: Rc<Background>,
+    triggers: Rc<Vec<StageTrigger>>,
+    trigger_states: Vec<TriggerState>,
+    // Set by a Trigger -> replaces the Scroll Curve
+    speed_override: Option<f32>,
+    // Checkpoint Columns -> sorted
+    checkpoints: Rc<Vec<f32>>,
+    next_checkpoint: usize,
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+struct TriggerState
+{
+    // Entered in the last Step -> Triggers fire when entered, not while inside
+    inside: bool,
+    fired: bool,
 }
 impl Level
 {
     pub fn new(world: &mut World, loaded: LoadedLevelData) -> Self
     {
         //println!("loaded data: {:?}", loaded.level_end);
-        let mut leveldata = LevelData::new(45.0);
-        leveldata.load_wall_fillings(&loaded, world);
+        let mut leveldata = LevelData::new(LEVEL_SCALE);
+        let chunks = LevelChunk::split(&loaded, &mut leveldata, world);
+        let checkpoints = collect_checkpoint_columns(&loaded);
         leveldata.load_level_end(loaded.level_end, world);
-        leveldata.load_blocking_walls(loaded.blockingWalls, world);
-        leveldata.load_trap_walls(loaded.trapWalls, world);
-        leveldata.load_destructibles(loaded.destructibles, world);
-        leveldata.load_enemyspawner(loaded.enemy_spawner, world);
-        leveldata.load_turrets(loaded.turrets, world);
-        println!("Level data state: Success!");
-        Self { leveldata: leveldata }
+        println!("Level data state: Success! ({} Chunks)", chunks.len());
+
+        let mut level = Self { 
+            leveldata, 
+            chunks: Rc::new(chunks), 
+            next_chunk: 0, 
+            live_chunks: Vec::new(),
+            scroll_curve: Rc::new(loaded.stage.scroll_curve.clone()),
+            stage_speed: loaded.stage.scroll_speed,
+            next_lock: 0,
+            background: Rc::new(loaded.background.clone()),
+            triggers: Rc::new(loaded.triggers.clone()),
+            trigger_states: vec![TriggerState::default(); loaded.triggers.len()],
+            speed_override: None,
+            checkpoints: Rc::new(checkpoints),
+            next_checkpoint: 0,
+        };
+        level.stream(world);
+        level
+    }
+    // Instantiates Chunks coming into Reach and retires the ones behind the View -> only those ever hold Entities
+    pub fn stream(&mut self, world: &mut World)
+    {
+        let chunk_width = CHUNK_COLUMNS as f32 * LEVEL_SCALE;
+        let chunks = self.chunks.clone();
+        while self.next_chunk < chunks.len()
+        {
+            let chunk_x = self.next_chunk as f32 * chunk_width;
+            if chunk_x > world.level_offset + STREAM_AHEAD
+            {
+                break;
+            }
+            // Skipped over (Playtest from the Middle of a Stage) -> never instantiated
+            let counts = if chunk_x + chunk_width < world.level_offset - STREAM_BEHIND
+            {
+                ChunkCounts::default()
+            }
+            else
+            {
+                self.leveldata.load_chunk(&chunks[self.next_chunk], world)
+            };
+            self.live_chunks.push(counts);
+            self.next_chunk += 1;
+        }
+
+        let first_chunk = self.next_chunk - self.live_chunks.len();
+        if !self.live_chunks.is_empty() && (first_chunk + 1) as f32 * chunk_width < world.level_offset - STREAM_BEHIND
+        {
+            let counts = self.live_chunks.remove(0);
+            self.leveldata.retire_chunk(&counts, world);
+        }
+    }
+    // Scroll Speed of the next Step -> 0 while the Camera waits on a Scroll Lock
+    pub fn update_scroll(&mut self, enemypool: &EnemyPool, world: &mut World)
+    {
+        while let Some(lock) = self.scroll_curve.locks.get(self.next_lock)
+        {
+            // Not reached yet
+            if world.level_offset < lock.get_offset()
+            {
+                break;
+            }
+            // Started behind the Lock (Playtest from the Middle) -> skipped, a reached Lock holds the Camera exactly on it
+            if world.level_offset > lock.get_offset() || self.is_lock_cleared(lock, enemypool)
+            {
+                println!("Scroll Lock at Column {} cleared", lock.column);
+                self.next_lock += 1;
+                continue;
+            }
+            world.scroll_speed = 0.0;
+            return;
+        }
+        world.scroll_speed = match self.speed_override
+        {
+            Some(speed) => speed,
+            None => self.scroll_curve.get_speed(world.level_offset, self.stage_speed),
+        };
+    }
+    pub fn update_triggers(&mut self, player_rect: Rect, enemypool: &mut EnemyPool, world: &mut World)
+    {
+        let triggers = self.triggers.clone();
+        for (i, trigger) in triggers.iter().enumerate()
+        {
+            let entered = trigger.is_entered(player_rect, world.level_offset);
+            let state = self.trigger_states[i];
+            self.trigger_states[i].inside = entered;
+            if !entered || state.inside || (trigger.once && state.fired)
+            {
+                continue;
+            }
+            self.trigger_states[i].fired = true;
+            println!("{} fired", trigger.name);
+            for action in trigger.actions.iter()
+            {
+                self.run_trigger_action(action, enemypool, world);
+            }
+        }
+    }
+    fn run_trigger_action(&mut self, action: &TriggerAction, enemypool: &mut EnemyPool, world: &mut World)
+    {
+        match action
+        {
+            TriggerAction::ActivateSpawners(group) => { self.leveldata.release_spawners(group); }
+            TriggerAction::ScrollSpeed(speed) => { self.speed_override = *speed; }
+            TriggerAction::Banner(text) => { world.events.publish(GameEvent::BannerShown { text: text.clone() }); }
+            TriggerAction::Sound(name) => { world.events.publish(GameEvent::SoundRequested { name: name.clone() }); }
+            TriggerAction::OpenWall(area) => { self.leveldata.open_walls(*area, world); }
+            TriggerAction::StartBoss(cell) => {
+                let position = (*cell + vec2(0.5, 0.5)) * LEVEL_SCALE;
+                enemypool.spawn_enemy(position, &EnemyType::Boss, world);
+            }
+        }
+    }
+    // Column of the Checkpoint the Player just passed
+    pub fn reach_checkpoint(&mut self, player_rect: Rect) -> Option<f32>
+    {
+        let column = *self.checkpoints.get(self.next_checkpoint)?;
+        if player_rect.x < column * LEVEL_SCALE
+        {
+            return None;
+        }
+        // Passed several at once (Restore or Playtest) -> only the last one counts
+        while self.checkpoints.get(self.next_checkpoint).is_some_and(|c| player_rect.x >= c * LEVEL_SCALE)
+        {
+            self.next_checkpoint += 1;
+        }
+        Some(self.checkpoints[self.next_checkpoint - 1])
+    }
+    pub fn get_progress(&self) -> LevelProgress
+    {
+        let mut cleared = self.leveldata.cleared.clone();
+        // Empty Spawners would spawn their Waves again
+        cleared.extend(self.leveldata.enemy_spawner.iter().filter(|s| s.spawner.is_exhausted()).map(|s| s.cell));
+        LevelProgress {
+            cleared,
+            fired_triggers: self.trigger_states.iter().map(|s| s.fired).collect(),
+            released_groups: self.leveldata.released_groups.clone(),
+            opened_areas: self.leveldata.opened_areas.clone(),
+            speed_override: self.speed_override,
+        }
+    }
+    // Called right after new() with the View already on the Checkpoint -> the Stage Start Chunks are streamed again without the cleared Elements
+    pub fn restore_progress(&mut self, progress: &LevelProgress, column: f32, world: &mut World)
+    {
+        // Restored Checkpoint counts as passed
+        self.next_checkpoint = self.checkpoints.iter().filter(|c| **c <= column).count();
+        while !self.live_chunks.is_empty()
+        {
+            let counts = self.live_chunks.remove(0);
+            self.leveldata.retire_chunk(&counts, world);
+        }
+        self.next_chunk = 0;
+        self.leveldata.cleared = progress.cleared.clone();
+        self.leveldata.released_groups = progress.released_groups.clone();
+        self.leveldata.opened_areas = progress.opened_areas.clone();
+        for (state, fired) in self.trigger_states.iter_mut().zip(progress.fired_triggers.iter())
+        {
+            state.fired = *fired;
+        }
+        self.speed_override = progress.speed_override;
+        self.stream(world);
+    }
+    pub fn get_level_data(&self) -> &LevelData
+    {
+        &self.leveldata
+    }
+    // Chunks with live Entities -> first ..< next
+    pub fn get_live_chunks(&self) -> std::ops::Range<usize>
+    {
+        self.next_chunk - self.live_chunks.len()..self.next_chunk
+    }
+    // Offset the Camera has to stop at
+    pub fn get_scroll_limit(&self) -> Option<f32>
+    {
+        self.scroll_curve.locks.get(self.next_lock).map(|lock| lock.get_offset())
+    }
+    // Every Spawner of the Lock is empty or destroyed and no Enemy is left
+    fn is_lock_cleared(&self, lock: &ScrollLock, enemypool: &EnemyPool) -> bool
+    {
+        let (left, right) = (lock.get_offset(), lock.get_offset() + GAME_SIZE_X);
+        let spawners_cleared = self.leveldata.enemy_spawner.iter()
+            .filter(|s| match &lock.group
+            {
+                Some(group) => s.group.as_ref() == Some(group),
+                None => s.entity.transform.position.x >= left && s.entity.transform.position.x < right,
+            })
+            .all(|s| s.is_cleared());
+        spawners_cleared && enemypool.get_alive_count() == 0
     }
     pub fn has_reached_level_end(&self, progress: f32) -> bool
     {
-        self.leveldata.end_of_level.as_ref().unwrap().reached_end(progress)
+        match &self.leveldata.end_of_level
+        {
+            Some(end_of_level) => end_of_level.reached_end(progress),
+            None => false,
+        }
     }
     pub fn get_blocking_walls(&self, level_offset: f32) -> Vec<Rect>
     {
         let mut visibles = Vec::new();
 
         // Add Blocking Walls 
-        let mut elements = self.leveldata.blockingwalls.clone();
-        elements.retain(|e| e.entity.in_view == true);
-        for element in elements.iter()
+        for element in self.leveldata.blockingwalls.iter().filter(|e| e.entity.in_view && e.entity.is_active)
         {
             if inside_windowview(element.entity.transform.rect, level_offset + element.entity.transform.get_fullsize().x)
             {
@@ -54,10 +273,21 @@ impl Level
             }
         }
         */
+        // Add Crushers, Doors & their Switches
+        for element in self.leveldata.hazards.iter().filter(|e| e.entity.in_view)
+        {
+            if element.is_blocking() && element.entity.is_active
+            {
+                visibles.push(element.entity.transform.rect);
+            }
+            match &element.switch
+            {
+                Some(switch) if switch.entity.is_active => { visibles.push(switch.entity.transform.rect); }
+                _ => {}
+            }
+        }
         // Add Destructibles
-        let mut elements = self.leveldata.destructibles.clone();
-        elements.retain(|e| e.entity.in_view == true);
-        for element in elements.iter()
+        for element in self.leveldata.destructibles.iter().filter(|e| e.entity.in_view)
         {
             if inside_windowborder(element.entity.transform.rect, level_offset + element.entity.transform.get_fullsize().x, element.entity.transform.get_fullsize().y)
             {
@@ -65,9 +295,7 @@ impl Level
             }
         }
         // Add Turrets 
-        let mut elements = self.leveldata.turrets.clone();
-        elements.retain(|e| e.entity.in_view == true);
-        for element in elements.iter()
+        for element in self.leveldata.turrets.iter().filter(|e| e.entity.in_view)
         {
             if inside_windowborder(element.entity.transform.rect, level_offset + element.entity.transform.get_fullsize().x, element.entity.transform.get_fullsize().y)
             {
@@ -78,20 +306,10 @@ impl Level
         visibles
     }
 
+    // Elements are initialised as their Chunk gets instantiated
     pub fn init(&mut self, world: &mut World)
     {
-        for trap_wall_element in self.leveldata.trapwalls.iter_mut()
-        {
-            trap_wall_element.init(world);
-        }
-        for enemyspawner_element in self.leveldata.enemy_spawner.iter_mut()
-        {
-            enemyspawner_element.init(world);
-        }
-        for turret_element in self.leveldata.turrets.iter_mut()
-        {
-            turret_element.init(world);
-        }
+        self.stream(world);
     }
     pub fn spawer_update(&mut self, enemypool: &mut EnemyPool, world: &mut World)
     {
@@ -111,6 +329,12 @@ impl Level
         {
             destructible_element.update(world);
         }
+        // One Clock for every Hazard of the Stage -> Gates & Crushers placed together stay in Sync
+        self.leveldata.hazard_time += get_tick_time();
+        for hazard_element in self.leveldata.hazards.iter_mut()
+        {
+            hazard_element.update(self.leveldata.hazard_time, world);
+        }
     }
     pub fn fixed_update(&mut self, world: &mut World)
     {
@@ -122,10 +346,15 @@ impl Level
         {
             destructible_element.fixed_update(world);
         }
+        for hazard_element in self.leveldata.hazards.iter_mut()
+        {
+            hazard_element.fixed_update(world);
+        }
     }
 
     pub fn late_update(&mut self, world: &mut World, misslepool: &mut MisslePool)
     {
+        self.leveldata.record_cleared();
         for wall_element in self.leveldata.walls.iter_mut()
         {
             wall_element.late_update(world);
@@ -146,12 +375,21 @@ impl Level
         {
             enemyspawner_element.late_update(world);
         }
+        for hazard_element in self.leveldata.hazards.iter_mut()
+        {
+            hazard_element.late_update(world);
+        }
         for turret_element in self.leveldata.turrets.iter_mut()
         {
             turret_element.shoot(misslepool, world);
             turret_element.late_update(world);
         }
     }
+    // Behind everything else -> Layers only depend on the Camera
+    pub fn draw_background(&self, render_offset: f32)
+    {
+        self.background.draw(render_offset);
+    }
     pub fn draw(& mut self)
     {
         for trap_wall_element in self.leveldata.trapwalls.iter()
@@ -171,6 +409,10 @@ impl Level
         {
             destructible_element.draw();
         }
+        for hazard_element in self.leveldata.hazards.iter()
+        {
+            hazard_element.draw();
+        }
         for enemyspawner_element in self.leveldata.enemy_spawner.iter_mut()
         {
             enemyspawner_element.draw();
@@ -184,6 +426,12 @@ impl Level
             Some (end_of_level)=> { end_of_level.draw();}
             None => {}
         }
+        // Checkpoints -> dim once passed
+        for (i, column) in self.checkpoints.iter().enumerate()
+        {
+            let color = if i < self.next_checkpoint { color_u8!(0,128,255,48) } else { color_u8!(0,128,255,160) };
+            draw_line(column * LEVEL_SCALE, 0.0, column * LEVEL_SCALE, GAME_SIZE_Y, 4.0, color);
+        }
     }
 }
 
@@ -197,8 +445,17 @@ pub struct LevelData
     pub enemy_spawner: Vec<EnemySpawnerElement>,
     pub destructibles: Vec<DestructibleElement>,
     pub turrets: Vec<TurretElement>,
+    pub hazards: Vec<HazardElement>,
+    // Seconds since the Stage started -> Paths & Gate Periods run on it
+    pub hazard_time: f32,
 
     pub end_of_level: Option<LevelEndElement>,
+
+    // Trigger Results -> also apply to Chunks streamed in later
+    released_groups: Vec<String>,
+    opened_areas: Vec<Rect>,
+    // Cells of destroyed or emptied Elements -> left out when a Chunk is streamed in again
+    cleared: Vec<Vec2>,
 }
 impl LevelData
 {
@@ -211,8 +468,55 @@ impl LevelData
             enemy_spawner: Vec::new(), 
             destructibles: Vec::new(), 
             turrets: Vec::new(), 
-            end_of_level: None 
+            hazards: Vec::new(),
+            hazard_time: 0.0,
+            end_of_level: None,
+            released_groups: Vec::new(),
+            opened_areas: Vec::new(),
+            cleared: Vec::new(),
+        }
+    }
+    // Called before the Elements handle their Death -> Health is still at 0 for one Step
+    pub fn record_cleared(&mut self)
+    {
+        let destroyed = self.destructibles.iter().filter(|e| e.entity.is_active && e.entity.entity_params.health <= 0.0).map(|e| e.cell)
+            .chain(self.enemy_spawner.iter().filter(|e| e.entity.is_active && e.entity.entity_params.health <= 0.0).map(|e| e.cell))
+            .chain(self.turrets.iter().filter(|e| e.entity.is_active && e.entity.entity_params.health <= 0.0).map(|e| e.cell))
+            .collect::<Vec<Vec2>>();
+        self.cleared.extend(destroyed);
+    }
+    pub fn release_spawners(&mut self, group: &str)
+    {
+        for spawner_element in self.enemy_spawner.iter_mut().filter(|s| s.group.as_deref() == Some(group))
+        {
+            spawner_element.spawner.set_held(false);
+        }
+        self.released_groups.push(group.to_string());
+    }
+    // Area in Cells
+    pub fn open_walls(&mut self, area: Rect, world: &mut World)
+    {
+        self.opened_areas.push(area);
+        for wall in self.blockingwalls.iter_mut()
+        {
+            LevelData::open_wall(wall, area, self.level_scale, world);
+        }
+    }
+    fn open_wall(wall: &mut BlockingWallElement, area: Rect, level_scale: f32, world: &mut World)
+    {
+        let cell = wall.entity.transform.rect.point() / level_scale;
+        if !wall.entity.is_active || !area.contains(cell + vec2(0.5, 0.5))
+        {
+            return;
         }
+        wall.entity.is_active = false;
+        world.set_entity(&mut wall.entity);
+        world.events.publish(GameEvent::EntityKilled { 
+            handle: wall.entity.handle, 
+            source: EventSource::Destructible, 
+            position: wall.entity.transform.position, 
+            color: GRAY 
+        });
     }
     pub fn load_level_end(&mut self, level_end: Vec<Vec2>, world: &mut World)
     {
@@ -235,127 +539,182 @@ impl LevelData
         world.set_entity(&mut end_element.entity);
         self.end_of_level = Some(end_element);
     }
-    pub fn load_wall_fillings(&mut self, loader_data: &LoadedLevelData, world: &mut World)
+    // Old Entities of this Chunk -> same Order as they were loaded before Streaming
+    pub fn load_chunk(&mut self, chunk: &LevelChunk, world: &mut World) -> ChunkCounts
     {
-        println!("Load Walls... ");
-
-        for i in 0..(loader_data.walls.len()  + loader_data.infected_wall_filling.len())
+        // Cleared before a Checkpoint Restore
+        let destructibles: Vec<PlacedTile> = chunk.destructibles.iter().filter(|t| !self.cleared.contains(&t.cell)).cloned().collect();
+        let spawners: Vec<PlacedSpawner> = chunk.spawners.iter().filter(|s| !self.cleared.contains(&s.tile.cell)).cloned().collect();
+        let turrets: Vec<PlacedTile> = chunk.turrets.iter().filter(|t| !self.cleared.contains(&t.cell)).cloned().collect();
+        let counts = ChunkCounts {
+            walls: chunk.walls.len() + chunk.infected_walls.len(),
+            blocking_walls: chunk.blocking_walls.len(),
+            trap_walls: chunk.trap_walls.len(),
+            destructibles: destructibles.len(),
+            spawners: spawners.len(),
+            turrets: turrets.len(),
+            hazards: chunk.hazards.len(),
+        };
+        self.load_wall_fillings(&chunk.walls, &chunk.infected_walls, world);
+        self.load_blocking_walls(&chunk.blocking_walls, world);
+        self.load_trap_walls(&chunk.trap_walls, world);
+        self.load_destructibles(&destructibles, world);
+        self.load_enemyspawner(&spawners, world);
+        self.load_turrets(&turrets, world);
+        self.load_hazards(&chunk.hazards, world);
+        counts
+    }
+    // Elements of the oldest Chunk are at the Front of every List
+    pub fn retire_chunk(&mut self, counts: &ChunkCounts, world: &mut World)
+    {
+        self.walls.drain(..counts.walls);
+        for wall in self.blockingwalls.drain(..counts.blocking_walls)
+        {
+            world.remove_entity(wall.entity.handle);
+        }
+        for trap in self.trapwalls.drain(..counts.trap_walls)
+        {
+            world.remove_entity(trap.entity.handle);
+        }
+        for destructible in self.destructibles.drain(..counts.destructibles)
         {
+            world.remove_entity(destructible.entity.handle);
+        }
+        for spawner_element in self.enemy_spawner.drain(..counts.spawners)
+        {
+            world.remove_entity(spawner_element.entity.handle);
+            world.remove_entity(spawner_element.spawner.entity.handle);
+        }
+        for turret in self.turrets.drain(..counts.turrets)
+        {
+            world.remove_entity(turret.entity.handle);
+            world.remove_entity(turret.weapon.entity.handle);
+        }
+        for hazard in self.hazards.drain(..counts.hazards)
+        {
+            world.remove_entity(hazard.entity.handle);
+            if let Some(switch) = &hazard.switch { world.remove_entity(switch.entity.handle); }
+        }
+    }
 
+    pub fn load_wall_fillings(&mut self, walls: &[PlacedWall], infected_walls: &[PlacedWall], world: &mut World)
+    {
+        for placed in walls.iter().chain(infected_walls.iter())
+        {
             let mut wall = WallElement::new(world);
             wall.transform.set_size( vec2(1.0, 1.0));
             wall.transform.set_scale( self.level_scale );
-            
-            if i < loader_data.walls.len()
-            {
-                wall.transform.set_position_not_centered(loader_data.walls[i] * self.level_scale);
-                wall.set_sprite( vec2(0.0, 0.0));
-            } else 
-            {
-                wall.transform.set_position_not_centered(loader_data.infected_wall_filling[i - loader_data.walls.len()] * self.level_scale);
-                wall.set_sprite( vec2(32.0, 0.0));
-            }
-
-            wall.transform.rotation = self.rotate_tile();
+            wall.set_sprite(placed.sprite, placed.tile_size);
+            wall.transform.set_position_not_centered(placed.tile.cell * self.level_scale);
+            wall.transform.rotation = placed.tile.rotation;
             
             self.walls.push(wall);
-            //println!("Wall: {} / {}", i, walls.len());
         }
     }
 
-    pub fn load_blocking_walls(&mut self, blockingwalls: Vec<Vec2>, world: &mut World)
+    pub fn load_blocking_walls(&mut self, blockingwalls: &[PlacedTile], world: &mut World)
     {
-        println!("Load Collider... ");
-        for i in 0..blockingwalls.len()
+        for tile in blockingwalls.iter()
         {
-            //println!("LevelData: {}", walls[i]);
             let mut wall = BlockingWallElement::new(world);
             wall.entity.transform.set_size( vec2(1.0, 1.0));
             wall.entity.transform.set_scale( self.level_scale );
-            wall.entity.transform.set_position_not_centered(blockingwalls[i] * self.level_scale);
-
-            wall.entity.transform.rotation = self.rotate_tile();
+            wall.entity.transform.set_position_not_centered(tile.cell * self.level_scale);
+            wall.entity.transform.rotation = tile.rotation;
 
             world.set_entity(&mut wall.entity);
+            for area in self.opened_areas.iter()
+            {
+                LevelData::open_wall(&mut wall, *area, self.level_scale, world);
+            }
             self.blockingwalls.push(wall);
-            //println!("Collider: {} / {}", i, blockingwalls.len());
         }
     }
 
-    pub fn load_trap_walls(&mut self, trapwalls: Vec<Vec2>, world: &mut World)
+    pub fn load_trap_walls(&mut self, trapwalls: &[PlacedTile], world: &mut World)
     {
-        println!("Load Traps... ");
-        for i in 0..trapwalls.len()
+        for tile in trapwalls.iter()
         {
-            //println!("LevelData: {}", walls[i]);
             let mut trap = TrapWallElement::new(world);
             trap.entity.transform.set_size( vec2(1.0, 1.0));
             trap.entity.transform.set_scale( self.level_scale );
-            trap.entity.transform.set_position_not_centered(trapwalls[i] * self.level_scale);
-
-            trap.entity.transform.rotation = self.rotate_tile();
+            trap.entity.transform.set_position_not_centered(tile.cell * self.level_scale);
+            trap.entity.transform.rotation = tile.rotation;
 
             world.set_entity(&mut trap.entity);
+            trap.init(world);
             self.trapwalls.push(trap);
-            //println!("trap: {} / {}", i, trapwalls.len());
         }
     }
 
-    pub fn load_destructibles(&mut self, destructibles: Vec<Vec2>, world: &mut World)
+    pub fn load_destructibles(&mut self, destructibles: &[PlacedTile], world: &mut World)
     {
-        println!("Load Destructibles... ");
-        for i in 0..destructibles.len()
+        for tile in destructibles.iter()
         {
             let mut destructible = DestructibleElement::new(world);
             destructible.entity.transform.set_size( vec2(1.0, 1.0));
             destructible.entity.transform.set_scale( self.level_scale );
-            destructible.entity.transform.set_position_not_centered(destructibles[i] * self.level_scale);
-
-            destructible.entity.transform.rotation = self.rotate_tile();
+            destructible.entity.transform.set_position_not_centered(tile.cell * self.level_scale);
+            destructible.entity.transform.rotation = tile.rotation;
+            destructible.cell = tile.cell;
 
             world.set_entity(&mut destructible.entity);
             self.destructibles.push(destructible);
-            //println!("destructible: {} / {}", i, destructibles.len());
         }
     }
-    pub fn load_enemyspawner(&mut self, e_spawner: Vec<(Vec2, usize, usize)>, world: &mut World)
+    pub fn load_enemyspawner(&mut self, e_spawner: &[PlacedSpawner], world: &mut World)
     {
-        println!("Load Spawners... ");
-        for i in 0..e_spawner.len()
+        for placed in e_spawner.iter()
         {
-            //println!("Spawner   {} / {}  ||| Count: {} | type: {}", i, e_spawner.len(), e_spawner[i].1, e_spawner[i].2);
-            let mut spawner_element = EnemySpawnerElement::new(e_spawner[i].1, e_spawner[i].2 ,world);
+            let mut spawner_element = EnemySpawnerElement::new(placed.count, placed.enemy_type ,world);
 
             // Apply Spawner Transform
             spawner_element.entity.transform.set_size( vec2(1.0, 1.0));
             spawner_element.entity.transform.set_scale( self.level_scale );
-            spawner_element.entity.transform.set_position_not_centered(e_spawner[i].0 * self.level_scale);
+            spawner_element.entity.transform.set_position_not_centered(placed.tile.cell * self.level_scale);
 
             spawner_element.spawner.set_transform(&spawner_element.entity.transform);
-            spawner_element.entity.transform.rotation = self.rotate_tile();
+            spawner_element.spawner.add_start_delay(placed.delay);
+            if let Some(definition) = &placed.definition { spawner_element.spawner.apply_definition(definition); }
+            spawner_element.entity.transform.rotation = placed.tile.rotation;
+            spawner_element.group = placed.group.clone();
+            spawner_element.cell = placed.tile.cell;
+            // Held until a Trigger releases the Group
+            let released = placed.group.as_ref().is_some_and(|g| self.released_groups.contains(g));
+            spawner_element.spawner.set_held(placed.held && !released);
              // Apply Spawner To World
             world.set_entity(&mut spawner_element.entity);
+            spawner_element.init(world);
             self.enemy_spawner.push(spawner_element);
         }
     }
-    pub fn load_turrets(&mut self, turrets: Vec<Vec2>, world: &mut World)
+    pub fn load_turrets(&mut self, turrets: &[PlacedTile], world: &mut World)
     {
-        println!("Load turrets... ");
-        for i in 0..turrets.len()
+        for tile in turrets.iter()
         {
             let mut turret = TurretElement::new(world);
             turret.entity.transform.set_size( vec2(1.0, 1.0));
             turret.entity.transform.set_scale( self.level_scale );
-            turret.entity.transform.set_position_not_centered(turrets[i] * self.level_scale);
+            turret.entity.transform.set_position_not_centered(tile.cell * self.level_scale);
+            turret.cell = tile.cell;
             world.set_entity(&mut turret.entity);
+            turret.init(world);
             self.turrets.push(turret);
-            //println!("turrets: {} / {}", i, turrets.len());
         }
     }
 
-    pub fn rotate_tile(&mut self) -> f32
+    pub fn load_hazards(&mut self, hazards: &[StageHazard], world: &mut World)
     {
-        let random_rotation = gen_range(0, 12); 
+        for hazard in hazards.iter()
+        {
+            let hazard_element = HazardElement::new(hazard, self.level_scale, world);
+            self.hazards.push(hazard_element);
+        }
+    }
+
+    pub fn rotate_tile(&mut self, rng: &mut RandomStream) -> f32
+    {
+        let random_rotation = rng.range_i32(0, 12); 
         let mut rotation = 0;
         match random_rotation
         {
@@ -381,6 +740,145 @@ impl LevelData
     }
 }
 
+// One Cell of a Chunk -> Rotation is rolled when the Stage loads, the Level Random Stream keeps its Order
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlacedTile
+{
+    pub cell: Vec2,
+    pub rotation: f32,
+}
+// Autotiled Wall -> Atlas Tile & Rotation follow its Neighbours
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PlacedWall
+{
+    pub tile: PlacedTile,
+    pub sprite: Vec2,
+    pub tile_size: f32,
+}
+#[derive(Clone, PartialEq, Debug)]
+pub struct PlacedSpawner
+{
+    pub tile: PlacedTile,
+    pub count: usize,
+    pub enemy_type: usize,
+    // Seconds before the first Enemy
+    pub delay: f32,
+    // Scroll Locks waiting on this Group
+    pub group: Option<String>,
+    // Waves & Trigger from the Stage Spawner File
+    pub definition: Option<SpawnerDefinition>,
+    // A Stage Trigger activates the Group
+    pub held: bool,
+}
+
+// Elements of CHUNK_COLUMNS Columns -> no Entities until the Level streams it in
+#[derive(Clone, Default, Debug)]
+pub struct LevelChunk
+{
+    pub walls: Vec<PlacedWall>,
+    pub infected_walls: Vec<PlacedWall>,
+    pub blocking_walls: Vec<PlacedTile>,
+    pub trap_walls: Vec<PlacedTile>,
+    pub destructibles: Vec<PlacedTile>,
+    pub spawners: Vec<PlacedSpawner>,
+    pub turrets: Vec<PlacedTile>,
+    pub hazards: Vec<StageHazard>,
+}
+impl LevelChunk
+{
+    pub fn split(loaded: &LoadedLevelData, leveldata: &mut LevelData, world: &mut World) -> Vec<LevelChunk>
+    {
+        let mut chunks: Vec<LevelChunk> = Vec::new();
+        fn chunk_at(chunks: &mut Vec<LevelChunk>, cell: Vec2) -> &mut LevelChunk
+        {
+            let index = (cell.x.max(0.0) as usize) / CHUNK_COLUMNS;
+            if index >= chunks.len()
+            {
+                chunks.resize(index + 1, LevelChunk::default());
+            }
+            &mut chunks[index]
+        }
+
+        let grid = TileGrid::new(loaded);
+        for cell in loaded.walls.iter()
+        {
+            let wall = LevelChunk::autotile(&grid, &loaded.tile_atlas, *cell, false, leveldata.rotate_tile(&mut world.random.level));
+            chunk_at(&mut chunks, *cell).walls.push(wall);
+        }
+        for cell in loaded.infected_wall_filling.iter()
+        {
+            let wall = LevelChunk::autotile(&grid, &loaded.tile_atlas, *cell, true, leveldata.rotate_tile(&mut world.random.level));
+            chunk_at(&mut chunks, *cell).infected_walls.push(wall);
+        }
+        for cell in loaded.blockingWalls.iter()
+        {
+            let tile = PlacedTile { cell: *cell, rotation: leveldata.rotate_tile(&mut world.random.level) };
+            chunk_at(&mut chunks, *cell).blocking_walls.push(tile);
+        }
+        for cell in loaded.trapWalls.iter()
+        {
+            let tile = PlacedTile { cell: *cell, rotation: leveldata.rotate_tile(&mut world.random.level) };
+            chunk_at(&mut chunks, *cell).trap_walls.push(tile);
+        }
+        for cell in loaded.destructibles.iter()
+        {
+            let tile = PlacedTile { cell: *cell, rotation: leveldata.rotate_tile(&mut world.random.level) };
+            chunk_at(&mut chunks, *cell).destructibles.push(tile);
+        }
+        for (i, (cell, count, enemy_type)) in loaded.enemy_spawner.iter().enumerate()
+        {
+            let properties = loaded.spawner_properties.get(i);
+            let (delay, group) = match properties
+            {
+                Some(properties) => (properties.delay, find_property(&properties.custom, "group").map(|g| g.to_string())),
+                None => (0.0, None),
+            };
+            let definition = loaded.spawner_definitions.iter().find(|d| d.matches(*cell, properties)).cloned();
+            let group = group.or(definition.as_ref().and_then(|d| d.group.clone()));
+            let held = group.as_ref().is_some_and(|g| loaded.triggers.iter().any(|t| t.activates_group(g)));
+            let tile = PlacedTile { cell: *cell, rotation: leveldata.rotate_tile(&mut world.random.level) };
+            chunk_at(&mut chunks, *cell).spawners.push(PlacedSpawner { tile, count: *count, enemy_type: *enemy_type, delay, group, definition, held });
+        }
+        for cell in loaded.turrets.iter()
+        {
+            chunk_at(&mut chunks, *cell).turrets.push(PlacedTile { cell: *cell, rotation: 0.0 });
+        }
+        // Streamed in with the Chunk of their Start Cell
+        for hazard in loaded.hazards.iter()
+        {
+            chunk_at(&mut chunks, hazard.area.point()).hazards.push(hazard.clone());
+        }
+        chunks
+    }
+    // Interior & single Tiles keep the random Rotation -> hides Repetition inside big Wall Fillings
+    fn autotile(grid: &TileGrid, atlas: &TileAtlas, cell: Vec2, infected: bool, random_rotation: f32) -> PlacedWall
+    {
+        let (shape, rotation) = TileShape::from_open_sides(grid.get_open_sides(cell));
+        let rotation = match rotation
+        {
+            Some(degrees) => degrees.to_radians(),
+            None => random_rotation,
+        };
+        PlacedWall {
+            tile: PlacedTile { cell, rotation },
+            sprite: atlas.get_offset(shape, infected),
+            tile_size: atlas.tile_size,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ChunkCounts
+{
+    pub walls: usize,
+    pub blocking_walls: usize,
+    pub trap_walls: usize,
+    pub destructibles: usize,
+    pub spawners: usize,
+    pub turrets: usize,
+    pub hazards: usize,
+}
+
 #[derive(Clone)]
 pub struct LevelEndElement
 {
@@ -391,6 +889,7 @@ impl LevelEndElement
 {
     pub fn new(world: &mut World) -> Self { 
         let mut entity = Entity::new("End", "End", world);
+        entity.set_collision(CollisionLayer::TRIGGER, CollisionLayer::NONE);
         entity.set_rect_color(WHITE);
         entity.in_view = true;
         Self { entity: entity, sprite: TextureAsset::new() } 
@@ -439,6 +938,7 @@ pub struct WallElement
     pub transform: Transform,
     pub sprite: TextureAsset,
     tilesheet_offset: Vec2,
+    tile_size: f32,
     in_view: bool,
 }
 impl WallElement
@@ -449,8 +949,9 @@ impl WallElement
         Self { 
             //entity: entity,
             transform: Transform::default(), 
-            sprite: world.assets.get_asset_by_name("tile_texture_atlas".to_string()).as_mut().unwrap().get_texture_asset(),
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(),
             tilesheet_offset: vec2(0.0, 0.0),
+            tile_size: 16.0,
             in_view: false,
         } 
     }
@@ -460,9 +961,10 @@ impl WallElement
         self.in_view = inside_windowview(self.transform.rect, world.level_offset);
     }
 
-    pub fn set_sprite(&mut self, tilesheet_offset: Vec2)
+    pub fn set_sprite(&mut self, tilesheet_offset: Vec2, tile_size: f32)
     {
         self.tilesheet_offset = tilesheet_offset;
+        self.tile_size = tile_size;
     }
 
     pub fn draw(&self)
@@ -480,7 +982,7 @@ impl WallElement
             );
         }else if self.in_view{
 
-            let tile_rect = Rect::new(self.tilesheet_offset.x, self.tilesheet_offset.y, 16.0, 16.0);
+            let tile_rect = Rect::new(self.tilesheet_offset.x, self.tilesheet_offset.y, self.tile_size, self.tile_size);
             let mut params = DrawTextureParams::default();
             params.source = Some(tile_rect);
             params.dest_size = Some(vec2( self.transform.rect.w + 20.0, self.transform.rect.h + 20.0));
@@ -503,11 +1005,12 @@ impl BlockingWallElement
 {
     pub fn new(world: &mut World) -> Self { 
         let mut entity = Entity::new("BlockingWall", "BlockingWall", world);
+        entity.set_collision(CollisionLayer::TERRAIN, CollisionLayer::NONE);
         entity.set_rect_color(GRAY);
 
         Self { 
             entity: entity, 
-            sprite: world.assets.get_asset_by_name("tile_texture_atlas".to_string()).as_mut().unwrap().get_texture_asset(),
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(),
         } 
     }
 
@@ -549,21 +1052,18 @@ pub struct TrapWallElement
 {
     pub entity: Entity,
     pub sprite: TextureAsset,
-
-    sfx_on_hit: SoundData,
 }
 impl TrapWallElement
 {
     pub fn new(world: &mut World) -> Self { 
         let mut entity = Entity::new("Trap", "TrapWall", world);
+        entity.set_collision(CollisionLayer::HAZARD, CollisionLayer::PLAYER_PROJECTILE);
 
         entity.set_rect_color(WHITE);
         entity.hit_feedback_timer = 0.001;
         Self { 
             entity: entity, 
-            sprite: world.assets.get_asset_by_name("tile_texture_atlas".to_string()).as_mut().unwrap().get_texture_asset(), 
-
-            sfx_on_hit: world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(), 
         } 
     }
     pub fn init(&mut self, world: &mut World)
@@ -580,10 +1080,9 @@ impl TrapWallElement
     }
     pub fn fixed_update(&mut self, world: &mut World)
     {
-        for entity in world.get_actives().iter_mut()
-        {
-            self.on_collision( entity);
-        }
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
+        world.events.publish_damage(&self.entity, health, EventSource::TrapWall);
     }
     pub fn late_update(&mut self, world: &mut World)
     {
@@ -601,12 +1100,8 @@ impl TrapWallElement
             self.entity.is_active = false;
             self.entity.entity_params.health = 1.0;
 
-            world.add_scorepoints( 5 + (3 * world.difficulty_level ) );
-
-            world.particlesystem_pool.spawn_system_at_position( 
-                self.entity.transform.position, 
-                64, 
-                destruction_settings(LIGHTGRAY, WHITE, DARKGRAY));
+            world.events.publish(GameEvent::EntityKilled { handle: self.entity.handle, source: EventSource::TrapWall, position: self.entity.transform.position, color: LIGHTGRAY });
+            world.events.publish(GameEvent::ScoreAwarded { points: 5 + (3 * world.difficulty_level ), position: self.entity.transform.position });
             self.entity.transform = Transform::zero();
             world.set_entity(&mut self.entity);
             return;
@@ -644,20 +1139,15 @@ impl TrapWallElement
 }
 impl Collision for TrapWallElement
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             return;
         }
-        let mut params = PlaySoundParams::default();
-        params.volume = 0.07;
-        match entity.tag.as_str()
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
         {
-            "Player Weapon Missle" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            _ => {}
+            self.entity.hit(&entity.entity_params);
         }
     }
 } 
@@ -667,13 +1157,14 @@ pub struct DestructibleElement
 {
     pub entity: Entity,
     pub sprite: TextureAsset,
-
-    sfx_on_hit: SoundData,
+    // Structure Cell -> Checkpoints remember it once destroyed
+    pub cell: Vec2,
 }
 impl DestructibleElement
 {
     pub fn new(world: &mut World) -> Self { 
         let mut entity = Entity::new("Destructible Wall", "Destructible", world);
+        entity.set_collision(CollisionLayer::STRUCTURE, CollisionLayer::PLAYER_PROJECTILE);
         entity.entity_params = EntitySettings::destructible_settings(world);
 
         entity.set_rect_color(WHITE);
@@ -681,9 +1172,8 @@ impl DestructibleElement
 
         Self { 
             entity: entity, 
-            sprite: world.assets.get_asset_by_name("tile_texture_atlas".to_string()).as_mut().unwrap().get_texture_asset(),
-
-            sfx_on_hit: world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(),
+            cell: vec2(0.0, 0.0),
         } 
     }
     pub fn update(&mut self, world: &mut World)
@@ -694,10 +1184,9 @@ impl DestructibleElement
     }
     pub fn fixed_update(&mut self, world: &mut World)
     {
-        for entity in world.get_actives().iter_mut()
-        {
-            self.on_collision( entity);
-        }
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
+        world.events.publish_damage(&self.entity, health, EventSource::Destructible);
     }
     pub fn late_update(&mut self, world: &mut World) {
         
@@ -714,12 +1203,8 @@ impl DestructibleElement
             self.entity.is_active = false;
             self.entity.entity_params.health = 1.0;
 
-            world.add_scorepoints( 3 + (2 * world.difficulty_level ) );
-
-            world.particlesystem_pool.spawn_system_at_position( 
-                self.entity.transform.position, 
-                64, 
-                destruction_settings(LIGHTGRAY, WHITE, DARKGRAY));
+            world.events.publish(GameEvent::EntityKilled { handle: self.entity.handle, source: EventSource::Destructible, position: self.entity.transform.position, color: LIGHTGRAY });
+            world.events.publish(GameEvent::ScoreAwarded { points: 3 + (2 * world.difficulty_level ), position: self.entity.transform.position });
             self.entity.transform = Transform::zero();
             world.set_entity(&mut self.entity);
             return;
@@ -759,20 +1244,15 @@ impl DestructibleElement
 }
 impl Collision for DestructibleElement
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             return;
         }
-        let mut params = PlaySoundParams::default();
-        params.volume = 0.07;
-        match entity.tag.as_str()
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
         {
-            "Player Weapon Missle" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            _ => {}
+            self.entity.hit(&entity.entity_params);
         }
     }
 } 
@@ -784,13 +1264,14 @@ pub struct EnemySpawnerElement
     pub sprite: TextureAsset,
     color: Color,
     spawner: EnemySpawner,
-
-    sfx_on_hit: SoundData,
+    pub group: Option<String>,
+    pub cell: Vec2,
 }
 impl EnemySpawnerElement
 {
     pub fn new(count: usize, spawner_type: usize,world: &mut World) -> Self { 
         let mut entity = Entity::new("EnemySpawner", "EnemySpawner", world);
+        entity.set_collision(CollisionLayer::STRUCTURE, CollisionLayer::PLAYER_PROJECTILE);
         entity.entity_params = EntitySettings::spawner_settings(world);
 
         entity.hit_feedback_timer = 0.001;
@@ -799,12 +1280,18 @@ impl EnemySpawnerElement
         //println!("Spawner Element");
         Self { 
             entity: entity, 
-            sprite: world.assets.get_asset_by_name("spawner_sheet".to_string()).as_mut().unwrap().get_texture_asset(),
+            sprite: world.assets.get_asset("spawner_sheet").get_texture_asset(),
             color: RED,
             spawner: spawner,
-            sfx_on_hit: world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
+            group: None,
+            cell: vec2(0.0, 0.0),
         } 
     }
+    // Spawned every Enemy or got destroyed -> late_update deactivates a destroyed Spawner and resets its Health
+    pub fn is_cleared(&self) -> bool
+    {
+        self.spawner.is_exhausted() || !self.entity.is_active || self.entity.entity_params.health <= 0.0
+    }
     pub fn init(&mut self, world: &mut World)
     {
         self.sprite.setup_sheet(4, 2);
@@ -846,13 +1333,8 @@ impl EnemySpawnerElement
             self.entity.is_active = false;
             self.entity.entity_params.health = 1.0;
 
-            world.add_scorepoints( 25 + (6 * world.difficulty_level ) );
-
-            let mut params = PlaySoundParams::default();
-            params.volume = 0.5;
-            play_sound(world.assets.get_asset_by_name("explosion_3".to_string()).unwrap().get_sound_data().sound.unwrap(), params );
-
-            world.particlesystem_pool.spawn_system_at_position( self.entity.transform.position, 64, explosion_settings(MAGENTA, RED, color_u8!(255,0,255,0)));
+            world.events.publish(GameEvent::EntityKilled { handle: self.entity.handle, source: EventSource::EnemySpawner, position: self.entity.transform.position, color: MAGENTA });
+            world.events.publish(GameEvent::ScoreAwarded { points: 25 + (6 * world.difficulty_level ), position: self.entity.transform.position });
             self.entity.transform = Transform::zero();
             world.set_entity(&mut self.entity);
             return;
@@ -872,10 +1354,9 @@ impl EnemySpawnerElement
         {
             self.sprite.animation_controller.update();
         }
-        for entity in world.get_actives().iter_mut()
-        {
-            self.on_collision( entity);
-        }
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
+        world.events.publish_damage(&self.entity, health, EventSource::EnemySpawner);
     }
     pub fn draw(&mut self)
     {
@@ -908,20 +1389,15 @@ impl EnemySpawnerElement
 
 impl Collision for EnemySpawnerElement
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             return;
         }
-        let mut params = PlaySoundParams::default();
-        params.volume = 0.15;
-        match entity.tag.as_str()
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
         {
-            "Player Weapon Missle" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            _ => {}
+            self.entity.hit(&entity.entity_params);
         }
     }
 } 
@@ -932,11 +1408,13 @@ pub struct TurretElement
     pub entity: Entity,
     pub sprite: TextureAsset,
     pub weapon: Weapon,
+    pub cell: Vec2,
 }
 impl TurretElement
 {
     pub fn new(world: &mut World) -> Self { 
         let mut entity = Entity::new("Turret", "Turret", world);
+        entity.set_collision(CollisionLayer::STRUCTURE, CollisionLayer::PLAYER_PROJECTILE);
         entity.entity_params = EntitySettings::turret_settings(world);
 
         entity.set_rect_color(BLUE);
@@ -945,7 +1423,7 @@ impl TurretElement
         let mut weapon = Weapon::new("Turret", "Enemy Weapon", world);
         weapon.entity.entity_params = entity.entity_params;
 
-        Self { entity: entity, sprite: TextureAsset::new(), weapon: weapon} 
+        Self { entity, sprite: TextureAsset::new(), weapon, cell: vec2(0.0, 0.0) } 
     }
 
     pub fn shoot(&mut self, misslepool: &mut MisslePool, world: &mut World)
@@ -970,7 +1448,7 @@ impl TurretElement
         {
             self.entity.is_active = false;
             self.entity.entity_params.health = 1.0;
-            world.particlesystem_pool.spawn_system_at_position( self.entity.transform.position, 64, explosion_settings(YELLOW, RED, color_u8!(255,255,0,0)));
+            world.events.publish(GameEvent::EntityKilled { handle: self.entity.handle, source: EventSource::Turret, position: self.entity.transform.position, color: YELLOW });
             self.entity.transform = Transform::zero();
             world.set_entity(&mut self.entity);
             return;
@@ -980,10 +1458,9 @@ impl TurretElement
         
         self.entity.in_view = inside_windowview(self.entity.transform.rect, world.level_offset);
 
-        for entity in world.entities.iter_mut()
-        {
-            self.on_collision( entity);
-        }
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
+        world.events.publish_damage(&self.entity, health, EventSource::Turret);
         
         
         self.weapon.set_parent(Some(self.entity.clone()));
@@ -1020,24 +1497,252 @@ impl TurretElement
 
 impl Collision for TurretElement
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             return;
         }
-        //let mut params = PlaySoundParams::default();
-        //params.volume = 0.15;
-        match entity.tag.as_str()
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
         {
-            "Player Weapon Missle" => {
-                self.entity.hit(&entity.entity_params);
-                //play_sound(self.sfx_on_hit.sound.unwrap(), params);
+            self.entity.hit(&entity.entity_params);
+        }
+    }
+} 
+#[derive(Clone)]
+pub struct HazardElement
+{
+    pub entity: Entity,
+    pub sprite: TextureAsset,
+    hazard: StageHazard,
+    // World Position of the Area -> Path Offsets start here
+    origin: Vec2,
+    // Doors -> 0 closed, 1 open
+    opened: f32,
+    pub switch: Option<HazardSwitch>,
+}
+impl HazardElement
+{
+    pub fn new(hazard: &StageHazard, level_scale: f32, world: &mut World) -> Self { 
+        let mut entity = Entity::new(&hazard.name, "Hazard", world);
+        // Doors only block (and stop Player Missles until their Switch is shot), everything else hurts on Contact
+        let layer = if hazard.kind == HazardKind::Door { CollisionLayer::STRUCTURE } else { CollisionLayer::HAZARD };
+        entity.set_collision(layer, CollisionLayer::NONE);
+        entity.entity_params = EntitySettings::hazard_settings(world);
+        if let Some(damage) = hazard.damage { entity.entity_params.damage = damage; }
+        entity.set_rect_color(if hazard.kind == HazardKind::LaserGate { RED } else { ORANGE });
+
+        let origin = hazard.area.point() * level_scale;
+        entity.transform.set_size(hazard.area.size());
+        entity.transform.set_scale(level_scale);
+        entity.transform.set_position_not_centered(origin);
+        entity.transform.store_previous();
+        world.set_entity(&mut entity);
+
+        let switch = hazard.switch.map(|cell| HazardSwitch::new(cell * level_scale, level_scale, world));
+        Self { 
+            entity, 
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(),
+            hazard: hazard.clone(),
+            origin,
+            opened: 0.0,
+            switch,
+        } 
+    }
+    pub fn is_blocking(&self) -> bool
+    {
+        self.hazard.is_blocking()
+    }
+    pub fn update(&mut self, time: f32, world: &mut World)
+    {
+        self.entity.transform.store_previous();
+        if !self.entity.is_active {return;}
+        let scale = self.entity.transform.scale;
+        match self.hazard.kind
+        {
+            HazardKind::Crusher => {
+                self.entity.transform.set_position_not_centered(self.origin + self.hazard.get_path_offset(time) * scale);
+            }
+            HazardKind::Blade => {
+                self.entity.transform.set_position_not_centered(self.origin + self.hazard.get_path_offset(time) * scale);
+                self.entity.transform.rotation = (time * self.hazard.spin * std::f32::consts::TAU).rem_euclid(std::f32::consts::TAU);
+            }
+            HazardKind::LaserGate => {
+                self.entity.collision_is_enabled = self.hazard.is_on(time);
+            }
+            HazardKind::Door => {
+                let switch_destroyed = self.switch.as_ref().is_none_or(|s| !s.entity.is_active);
+                if switch_destroyed
+                {
+                    // Slides up into the Ceiling
+                    self.opened = (self.opened + self.hazard.speed * get_tick_time() / self.hazard.area.h).min(1.0);
+                    self.entity.transform.set_position_not_centered(self.origin - vec2(0.0, self.opened * self.entity.transform.rect.h));
+                    if self.opened >= 1.0
+                    {
+                        self.entity.is_active = false;
+                        println!("{} opened", self.hazard.name);
+                    }
+                }
             }
-            "Enemy Weapon Missle" => {
-                //self.entity.hit(&entity.entity_params);
-                //play_sound(self.sfx_on_hit.sound.unwrap(), params);
+        }
+        world.set_entity(&mut self.entity);
+        if let Some(switch) = self.switch.as_mut() { switch.update(); }
+    }
+    pub fn fixed_update(&mut self, world: &mut World)
+    {
+        if let Some(switch) = self.switch.as_mut() { switch.fixed_update(world); }
+    }
+    pub fn late_update(&mut self, world: &mut World)
+    {
+        self.entity.in_view = inside_windowview(self.entity.transform.rect, world.level_offset);
+        if let Some(switch) = self.switch.as_mut() { switch.late_update(world); }
+    }
+    pub fn draw(&self)
+    {
+        if let Some(switch) = &self.switch { switch.draw(); }
+        if !self.entity.is_active { return; }
+        if SHOW_COLLISION 
+        {
+            draw_rectangle_lines(
+                self.entity.transform.rect.x, 
+                self.entity.transform.rect.y, 
+                self.entity.transform.rect.w, 
+                self.entity.transform.rect.h, 
+                2.0,
+                self.entity.get_rect_color()
+            );
+        }else if self.entity.in_view{
+            let rect = self.entity.transform.get_render_rect();
+            let color = self.entity.get_rect_color();
+            match self.hazard.kind
+            {
+                HazardKind::Crusher | HazardKind::Door => {
+                    // One Blocking Wall Tile per Cell
+                    let cell_size = self.entity.transform.scale;
+                    let params = DrawTextureParams { source: Some(Rect::new(17.0, 0.0, 16.0, 16.0)), dest_size: Some(vec2(cell_size, cell_size)), ..Default::default() };
+                    for x in 0..self.hazard.area.w as i32
+                    {
+                        for y in 0..self.hazard.area.h as i32
+                        {
+                            draw_texture_ex(self.sprite.texture_data, rect.x + x as f32 * cell_size, rect.y + y as f32 * cell_size, WHITE, params.clone());
+                        }
+                    }
+                    draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 3.0, color);
+                }
+                HazardKind::Blade => {
+                    let center = rect.center();
+                    let radius = rect.w.min(rect.h) * 0.5;
+                    let rotation = self.entity.transform.rotation.to_degrees();
+                    draw_poly(center.x, center.y, 3, radius, rotation, color);
+                    draw_poly(center.x, center.y, 3, radius, rotation + 60.0, color);
+                    draw_circle(center.x, center.y, radius * 0.25, DARKGRAY);
+                }
+                HazardKind::LaserGate => {
+                    // Emitters at both Ends, Beam only while on
+                    let vertical = rect.h >= rect.w;
+                    let emitter = if vertical { vec2(rect.w, 8.0) } else { vec2(8.0, rect.h) };
+                    draw_rectangle(rect.x, rect.y, emitter.x, emitter.y, GRAY);
+                    draw_rectangle(rect.x + rect.w - emitter.x, rect.y + rect.h - emitter.y, emitter.x, emitter.y, GRAY);
+                    if self.entity.collision_is_enabled
+                    {
+                        draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(color.r, color.g, color.b, 0.6));
+                    }
+                    else
+                    {
+                        let center = rect.center();
+                        if vertical { draw_line(center.x, rect.y, center.x, rect.y + rect.h, 1.0, Color::new(color.r, color.g, color.b, 0.3)); }
+                        else { draw_line(rect.x, center.y, rect.x + rect.w, center.y, 1.0, Color::new(color.r, color.g, color.b, 0.3)); }
+                    }
+                }
             }
-            _ => {}
         }
     }
-} 
\ No newline at end of file
+}
+
+// Destructible Cell that opens its Door
+#[derive(Clone)]
+pub struct HazardSwitch
+{
+    pub entity: Entity,
+    pub sprite: TextureAsset,
+}
+impl HazardSwitch
+{
+    pub fn new(position: Vec2, level_scale: f32, world: &mut World) -> Self { 
+        let mut entity = Entity::new("Door Switch", "Destructible", world);
+        entity.set_collision(CollisionLayer::STRUCTURE, CollisionLayer::PLAYER_PROJECTILE);
+        entity.entity_params = EntitySettings::destructible_settings(world);
+        entity.set_rect_color(YELLOW);
+        entity.hit_feedback_timer = 0.001;
+
+        entity.transform.set_size( vec2(1.0, 1.0));
+        entity.transform.set_scale( level_scale );
+        entity.transform.set_position_not_centered(position);
+        world.set_entity(&mut entity);
+        Self { 
+            entity, 
+            sprite: world.assets.get_asset("tile_texture_atlas").get_texture_asset(),
+        } 
+    }
+    pub fn update(&mut self)
+    {
+        if !self.entity.is_active {return;}
+        self.entity.hit_cooldown();
+    }
+    pub fn fixed_update(&mut self, world: &mut World)
+    {
+        if !self.entity.is_active {return;}
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
+        world.events.publish_damage(&self.entity, health, EventSource::Destructible);
+    }
+    pub fn late_update(&mut self, world: &mut World)
+    {
+        if !self.entity.is_active {return;}
+        if self.entity.entity_params.health <= 0.0
+        {
+            self.entity.is_active = false;
+            world.events.publish(GameEvent::EntityKilled { handle: self.entity.handle, source: EventSource::Destructible, position: self.entity.transform.position, color: YELLOW });
+            world.events.publish(GameEvent::ScoreAwarded { points: 3 + (2 * world.difficulty_level ), position: self.entity.transform.position });
+            world.set_entity(&mut self.entity);
+            return;
+        }
+        self.entity.in_view = inside_windowview(self.entity.transform.rect, world.level_offset);
+    }
+    pub fn draw(&self)
+    {
+        if !self.entity.is_active { return; }
+        if SHOW_COLLISION 
+        {
+            draw_rectangle_lines(
+                self.entity.transform.rect.x, 
+                self.entity.transform.rect.y, 
+                self.entity.transform.rect.w, 
+                self.entity.transform.rect.h, 
+                2.0,
+                self.entity.get_rect_color()
+            );
+        }else if self.entity.in_view{
+            let tile_rect = Rect::new(51.0, 0.0, 16.0, 16.0);
+            let params = DrawTextureParams { source: Some(tile_rect), dest_size: Some(vec2(self.entity.transform.rect.w, self.entity.transform.rect.h)), ..Default::default() };
+            draw_texture_ex(self.sprite.texture_data, 
+                self.entity.transform.rect.x, 
+                self.entity.transform.rect.y, 
+                self.entity.get_rect_color(), params);
+        }
+    }
+}
+impl Collision for HazardSwitch
+{
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
+    fn on_collision(&mut self, entity: &mut Entity) {
+        if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
+        {
+            return;
+        }
+        if entity.collision_layer.intersects(CollisionLayer::PLAYER_PROJECTILE)
+        {
+            self.entity.hit(&entity.entity_params);
+        }
+    }
+}
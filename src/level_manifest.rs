@@ -0,0 +1,215 @@
+use super::*;
+
+pub const DEFAULT_LEVEL_PACK: &str = "resources/levels/Level_1/";
+pub const MANIFEST_FILE: &str = "manifest.cfg";
+
+// One Stage of a Level Pack -> File Names are relative to the Pack Folder
+#[derive(Clone, PartialEq, Debug)]
+pub struct StageEntry
+{
+    pub name: String,
+    pub structure: String,
+    pub spawnmap: Option<String>,
+    pub scroll_speed: f32,
+    // Speed Keys & Scroll Locks over the Stage -> empty keeps scroll_speed
+    pub scroll_curve: ScrollCurve,
+    pub music: Option<String>,
+    // Overrides the Palette of the Pack
+    pub palette: Option<String>,
+    // Overrides the Background of the Pack
+    pub background: Option<String>,
+    // Spawner Definitions -> Waves, Triggers & Formations on top of the Spawnmap
+    pub spawners: Option<String>,
+    // Trigger Areas & their Actions -> on top of the Trigger Objects of a Tiled Map
+    pub triggers: Option<String>,
+    // Moving & timed Hazards -> on top of the Hazard Objects of a Tiled Map
+    pub hazards: Option<String>,
+    // Checkpoint Columns -> on top of the Checkpoint Markers of the Structure
+    pub checkpoints: Vec<f32>,
+}
+impl StageEntry
+{
+    pub fn new(name: &str) -> Self
+    {
+        Self { name: name.to_string(), structure: String::new(), spawnmap: None, scroll_speed: LEVEL_SPEED, scroll_curve: ScrollCurve::new(), music: None, palette: None, background: None, spawners: None, triggers: None, hazards: None, checkpoints: Vec::new() }
+    }
+}
+
+// Describes a Level Pack -> Designers add Stages here instead of in Code
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelManifest
+{
+    pub name: String,
+    // Stage Index the Run continues at once the last Stage is done
+    pub loop_stage: usize,
+    // After the last Stage -> generated Stages (true) or back to loop_stage (false)
+    pub generated_stages: bool,
+    // Palette File for every Stage Image -> None uses the Default Colors
+    pub palette: Option<String>,
+    // Background Layer Set for every Stage -> None keeps the black Background
+    pub background: Option<String>,
+    // Atlas Layout File for Wall Autotiling -> None uses the Default Layout
+    pub tile_atlas: Option<String>,
+    pub stages: Vec<StageEntry>,
+}
+impl Default for LevelManifest
+{
+    fn default() -> Self { Self::new() }
+}
+impl LevelManifest
+{
+    pub fn new() -> Self
+    {
+        Self { name: String::new(), loop_stage: 0, generated_stages: true, palette: None, background: None, tile_atlas: None, stages: Vec::new() }
+    }
+
+    // Format -> Pack Keys first, then one "[stage]" Block per Stage with "key = value" Lines  |  "#" starts a Comment
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        let mut manifest = LevelManifest::new();
+        for (line_number, line) in config_lines(text)
+        {
+            if line == "[stage]"
+            {
+                let name = format!("Stage {}", manifest.stages.len() + 1);
+                manifest.stages.push(StageEntry::new(&name));
+                continue;
+            }
+            let (key, value) = config_entry(line_number, line)?;
+
+            match manifest.stages.last_mut()
+            {
+                // Pack Keys
+                None => match key
+                {
+                    "name" => { manifest.name = value.to_string(); }
+                    "palette" => { manifest.palette = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "background" => { manifest.background = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "tile_atlas" => { manifest.tile_atlas = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    // 1 = first Stage -> same Numbers as in the Game
+                    "loop_stage" => {
+                        let stage = value.parse::<usize>().map_err(|_| format!("Line {}: loop_stage is not a Number", line_number))?;
+                        manifest.loop_stage = stage.saturating_sub(1);
+                    }
+                    "generated_stages" => {
+                        manifest.generated_stages = value.parse::<bool>().map_err(|_| format!("Line {}: generated_stages has to be true or false", line_number))?;
+                    }
+                    _ => { println!("Manifest Line {}: unknown Key {}", line_number, key); }
+                },
+                // Stage Keys
+                Some(stage) => match key
+                {
+                    "name" => { stage.name = value.to_string(); }
+                    "structure" => { stage.structure = value.to_string(); }
+                    "spawnmap" => { stage.spawnmap = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "palette" => { stage.palette = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "background" => { stage.background = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "triggers" => { stage.triggers = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "hazards" => { stage.hazards = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "spawners" => { stage.spawners = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "music" => { stage.music = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "scroll_speed" => {
+                        stage.scroll_speed = value.parse::<f32>().map_err(|_| format!("Line {}: scroll_speed is not a Number", line_number))?;
+                    }
+                    // One Line per Key / Lock
+                    "scroll_key" => {
+                        let key = ScrollCurve::parse_key(value).map_err(|error| format!("Line {}: {}", line_number, error))?;
+                        stage.scroll_curve.add_key(key);
+                    }
+                    "scroll_lock" => {
+                        let lock = ScrollCurve::parse_lock(value).map_err(|error| format!("Line {}: {}", line_number, error))?;
+                        stage.scroll_curve.add_lock(lock);
+                    }
+                    "checkpoint" => {
+                        let column = value.parse::<f32>().map_err(|_| format!("Line {}: checkpoint is not a Column", line_number))?;
+                        stage.checkpoints.push(column);
+                    }
+                    _ => { println!("Manifest Line {}: unknown Key {}", line_number, key); }
+                },
+            }
+        }
+
+        for stage in manifest.stages.iter()
+        {
+            if stage.structure.is_empty()
+            {
+                return Err(format!("{} has no structure File", stage.name));
+            }
+        }
+        if manifest.stages.is_empty()
+        {
+            return Err("Manifest has no Stages".to_string());
+        }
+        if manifest.loop_stage >= manifest.stages.len()
+        {
+            manifest.loop_stage = manifest.stages.len() - 1;
+        }
+        Ok(manifest)
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_pack_and_stage_keys()
+    {
+        let text = "name = Pack # Comment\nloop_stage = 2\ngenerated_stages = false\n\n[stage]\nstructure = a.png\nscroll_key = 10, 200\nscroll_lock = 20, boss\ncheckpoint = 64\n\n[stage]\nname = Second\nstructure = b.png\nspawnmap =\n";
+        let manifest = LevelManifest::parse(text).unwrap();
+        assert_eq!(manifest.name, "Pack");
+        assert_eq!(manifest.loop_stage, 1);
+        assert!(!manifest.generated_stages);
+        assert_eq!(manifest.stages.len(), 2);
+        assert_eq!(manifest.stages[0].name, "Stage 1");
+        assert_eq!(manifest.stages[0].scroll_curve.keys, vec![ScrollKey { column: 10.0, speed: 200.0 }]);
+        assert_eq!(manifest.stages[0].scroll_curve.locks[0].group, Some("boss".to_string()));
+        assert_eq!(manifest.stages[0].checkpoints, vec![64.0]);
+        assert_eq!(manifest.stages[1].name, "Second");
+        assert_eq!(manifest.stages[1].spawnmap, None);
+    }
+    #[test]
+    fn loads_the_stages_it_lists()
+    {
+        let manifest = "name = Test\nloop_stage = 3\n[stage]\nname = Intro\nstructure = stage.png\nscroll_speed = 150\n[stage]\nname = Lost\nstructure = missing.png\n[stage]\nname = Outro\nstructure = stage.png\n";
+        let folder = write_test_pack("manifest", &[(MANIFEST_FILE, manifest)]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+
+        // Missing Structure -> left out & reported, the Rest of the Pack still plays
+        let names: Vec<&str> = loader.levels.iter().map(|level| level.stage.name.as_str()).collect();
+        assert_eq!(names, vec!["Intro", "Outro"]);
+        assert_eq!(loader.levels[0].stage.scroll_speed, 150.0);
+        assert_eq!(loader.levels[1].stage.scroll_speed, LEVEL_SPEED);
+        assert_eq!(loader.errors.len(), 1);
+        assert!(loader.errors[0].file.ends_with("missing.png"));
+        // Loop Stage 3 is gone with the left out Stage
+        assert_eq!(loader.manifest.loop_stage, 1);
+    }
+    #[test]
+    fn broken_manifests_load_no_stages()
+    {
+        let folder = write_test_pack("broken_manifest", &[(MANIFEST_FILE, "name = Empty\n")]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+        assert!(loader.levels.is_empty());
+        assert_eq!(loader.errors.len(), 1);
+        assert_eq!(loader.errors[0].kind, LoadErrorKind::Parse);
+
+        assert!(LevelManifest::parse("[stage]\nname = No Structure\n").is_err());
+        assert!(LevelManifest::parse("[stage]\nstructure = a.png\nscroll_speed = fast\n").is_err());
+    }
+    #[test]
+    fn shipped_pack_loads_every_stage()
+    {
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(DEFAULT_LEVEL_PACK);
+        assert!(loader.errors.is_empty());
+        assert_eq!(loader.levels.len(), loader.manifest.stages.len());
+        for (level, stage) in loader.levels.iter().zip(loader.manifest.stages.iter())
+        {
+            assert_eq!(level.stage.name, stage.name);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use super::*;
+
+// Max Distance per Channel (0 - 255) for a Pixel to count as a Palette Color
+pub const LEVEL_COLOR_THRESHOLD: f32 = 20.0;
+pub const DEFAULT_PALETTE_NAME: &str = "Default";
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PaletteEntry
+{
+    // Built-in LevelElementKind Name, or any other Name -> ends up as a LevelMarker
+    pub kind: String,
+    pub color: Color,
+}
+
+// Maps Structure Image Colors to Element Kinds -> loaded per Level Pack or Stage
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelPalette
+{
+    pub name: String,
+    pub threshold: f32,
+    pub entries: Vec<PaletteEntry>,
+}
+impl Default for LevelPalette
+{
+    // Colors every Stage was painted with before Palettes existed
+    fn default() -> Self
+    {
+        let mut palette = LevelPalette::new(DEFAULT_PALETTE_NAME);
+        // Filling
+        palette.register("wall",            color_u8!(255,255,255,255));
+        palette.register("infected_wall",   color_u8!(128,1,128,255));
+
+        // Entities
+        palette.register("blocking_wall",   color_u8!(180,180,180,255));
+        palette.register("trap_wall",       color_u8!(255,1,128,255));
+        palette.register("destructible",    color_u8!(0,0,255,255));
+        palette.register("enemy_spawner",   color_u8!(255,0,0,255));
+        palette.register("turret",          color_u8!(255,255,0,255));
+        palette.register("level_end",       color_u8!(0,255,0,255));
+
+        // Markers
+        palette.register("pickup",          color_u8!(255,128,0,255));
+        palette.register("checkpoint",      color_u8!(0,128,255,255));
+        palette.register("trigger",         color_u8!(128,255,128,255));
+        palette.register(HAZARD_MARKER_KIND, color_u8!(255,0,255,255));
+        palette
+    }
+}
+impl LevelPalette
+{
+    pub fn new(name: &str) -> Self
+    {
+        Self { name: name.to_string(), threshold: LEVEL_COLOR_THRESHOLD, entries: Vec::new() }
+    }
+    // Same Kind again -> replaces its Color
+    pub fn register(&mut self, kind: &str, color: Color)
+    {
+        match self.entries.iter_mut().find(|e| e.kind == kind)
+        {
+            Some(entry) => { entry.color = color; }
+            None => { self.entries.push(PaletteEntry { kind: kind.to_string(), color }); }
+        }
+    }
+
+    // Every Entry within the Threshold -> more than one means the Palette is ambiguous for this Pixel
+    pub fn matches(&self, pixel: Color) -> Vec<&PaletteEntry>
+    {
+        self.entries.iter().filter(|e| compare_color_in_range(pixel, e.color, self.threshold)).collect()
+    }
+
+    // Format -> "name" and "threshold", then one "kind = r, g, b" (optional ", a") per Line  |  "#" starts a Comment
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        let mut palette = LevelPalette::new(DEFAULT_PALETTE_NAME);
+        for (line_number, line) in config_lines(text)
+        {
+            let (key, value) = config_entry(line_number, line)?;
+            match key
+            {
+                "name" => { palette.name = value.to_string(); }
+                "threshold" => {
+                    palette.threshold = value.parse::<f32>().map_err(|_| format!("Line {}: threshold is not a Number", line_number))?;
+                }
+                _ => {
+                    let color = parse_color(value).map_err(|error| format!("Line {}: {} -> {}", line_number, key, error))?;
+                    palette.register(key, color);
+                }
+            }
+        }
+        if palette.entries.is_empty()
+        {
+            return Err("Palette has no Colors".to_string());
+        }
+        Ok(palette)
+    }
+}
+
+pub fn compare_color_in_range(col_1: Color, col_2: Color, range: f32) -> bool
+{
+    let color_1 = col_1.to_vec() * 255.0;
+    let color_2 = col_2.to_vec() * 255.0;
+    if color_1.x >= (color_2.x - range) && color_1.x <= (color_2.x + range) &&
+    color_1.y >= (color_2.y - range) && color_1.y <= (color_2.y + range) &&
+    color_1.z >= (color_2.z - range) && color_1.z <= (color_2.z + range) &&
+    color_1.w >= (color_2.w - range) && color_1.w <= (color_2.w + range) {
+        return true;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_colors_and_replaces_kinds()
+    {
+        let palette = LevelPalette::parse("name = Test\nthreshold = 5\nwall = 1, 2, 3\nwall = 4, 5, 6, 7\nturret = 255, 255, 0\n").unwrap();
+        assert_eq!(palette.name, "Test");
+        assert_eq!(palette.threshold, 5.0);
+        assert_eq!(palette.entries.len(), 2);
+        assert_eq!(palette.entries[0].color, color_u8!(4, 5, 6, 7));
+        assert_eq!(palette.matches(color_u8!(253, 255, 2, 255)).len(), 1);
+    }
+    #[test]
+    fn stage_palettes_bind_colors_to_kinds()
+    {
+        let manifest = "name = Test\n[stage]\nname = Default\nstructure = stage.png\n[stage]\nname = Swapped\nstructure = stage.png\npalette = swapped.cfg\n[stage]\nname = Broken\nstructure = stage.png\npalette = broken.cfg\n[stage]\nname = Broken Again\nstructure = stage.png\npalette = broken.cfg\n";
+        let swapped = "name = Swapped\nwall = 180, 180, 180\nblocking_wall = 255, 255, 255\nenemy_spawner = 255, 0, 0\nlevel_end = 0, 255, 0\nbeacon = 128, 1, 128\n";
+        let folder = write_test_pack("palette", &[(MANIFEST_FILE, manifest), ("swapped.cfg", swapped), ("broken.cfg", "name = No Colors\n")]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+        assert_eq!(loader.levels.len(), 4);
+
+        let default = &loader.levels[0];
+        assert_eq!(default.palette, DEFAULT_PALETTE_NAME);
+        assert_eq!((default.walls.len(), default.blockingWalls.len(), default.infected_wall_filling.len()), (213, 166, 19));
+
+        // Wall & Blocking Wall Colors swapped -> the same Pixels build the other Kind
+        let swapped = &loader.levels[1];
+        assert_eq!(swapped.palette, "Swapped");
+        assert_eq!((swapped.walls.len(), swapped.blockingWalls.len(), swapped.infected_wall_filling.len()), (166, 213, 0));
+        assert_eq!(swapped.enemy_spawner.len(), default.enemy_spawner.len());
+        // Unknown Kind -> Marker the Stage can look up by Name
+        assert_eq!(swapped.markers.iter().filter(|marker| marker.kind == "beacon").count(), 19);
+
+        // Broken Pack Palette -> Default Colors & one Error, not one per Stage
+        let broken = &loader.levels[2];
+        assert_eq!(broken.palette, DEFAULT_PALETTE_NAME);
+        assert_eq!(broken.walls.len(), 213);
+        assert_eq!(loader.levels[3].palette, DEFAULT_PALETTE_NAME);
+        assert_eq!(loader.errors.len(), 1);
+        assert!(loader.errors[0].file.ends_with("broken.cfg"));
+    }
+    #[test]
+    fn rejects_broken_palettes()
+    {
+        assert!(LevelPalette::parse("name = No Colors\n").is_err());
+        assert!(LevelPalette::parse("wall = 1, 2\n").is_err());
+        assert!(LevelPalette::parse("wall = 300, 0, 0\n").is_err());
+    }
+}
@@ -0,0 +1,325 @@
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum IssueSeverity
+{
+    // Stage loads, but plays differently than the Designer meant
+    Warning,
+    // Stage fails to load or can not be finished
+    Error,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelIssue
+{
+    pub severity: IssueSeverity,
+    pub stage: String,
+    pub file: String,
+    // Pixel (Images) or Cell (Tiled Maps)
+    pub position: Option<(u32, u32)>,
+    pub message: String,
+}
+impl std::fmt::Display for LevelIssue
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        let severity = match self.severity
+        {
+            IssueSeverity::Warning => "warning",
+            IssueSeverity::Error => "error",
+        };
+        match self.position
+        {
+            Some((x, y)) => write!(f, "{}: {} [{}] ({}, {}): {}", severity, self.file, self.stage, x, y, self.message),
+            None => write!(f, "{}: {} [{}]: {}", severity, self.file, self.stage, self.message),
+        }
+    }
+}
+
+// Loads every Stage of a Pack from Disk and collects Problems instead of failing on the first one
+pub struct LevelValidator
+{
+    folder: String,
+    stage: String,
+    pack_palette: Option<String>,
+    pub issues: Vec<LevelIssue>,
+    // Stage Name -> Name of the Palette it was checked against
+    pub palettes: Vec<(String, String)>,
+}
+impl LevelValidator
+{
+    pub fn new(folder: &str) -> Self
+    {
+        Self { folder: folder.to_string(), stage: String::new(), pack_palette: None, issues: Vec::new(), palettes: Vec::new() }
+    }
+    pub fn error_count(&self) -> usize { self.issues.iter().filter(|i| i.severity == IssueSeverity::Error).count() }
+    pub fn warning_count(&self) -> usize { self.issues.iter().filter(|i| i.severity == IssueSeverity::Warning).count() }
+
+    fn report(&mut self, severity: IssueSeverity, file: &str, position: Option<(u32, u32)>, message: String)
+    {
+        self.issues.push(LevelIssue { severity, stage: self.stage.clone(), file: file.to_string(), position, message });
+    }
+
+    pub fn validate_pack(&mut self)
+    {
+        let manifest_path = format!("{}{}", self.folder, MANIFEST_FILE);
+        self.stage = "Pack".to_string();
+        let text = match std::fs::read_to_string(&manifest_path)
+        {
+            Ok(text) => text,
+            Err(error) => { self.report(IssueSeverity::Error, &manifest_path, None, format!("could not read Manifest: {}", error)); return; }
+        };
+        let manifest = match LevelManifest::parse(&text)
+        {
+            Ok(manifest) => manifest,
+            Err(error) => { self.report(IssueSeverity::Error, &manifest_path, None, error); return; }
+        };
+        self.pack_palette = manifest.palette.clone();
+        for stage in manifest.stages.iter()
+        {
+            self.validate_stage(stage);
+        }
+    }
+
+    pub fn validate_stage(&mut self, stage: &StageEntry)
+    {
+        self.stage = stage.name.clone();
+        let structure_path = format!("{}{}", self.folder, stage.structure);
+        if stage.scroll_speed <= 0.0
+        {
+            self.report(IssueSeverity::Error, &structure_path, None, format!("scroll_speed {} never reaches the Level End", stage.scroll_speed));
+        }
+
+        let level_data = if is_tiled_map(&structure_path)
+        {
+            let text = match std::fs::read_to_string(&structure_path)
+            {
+                Ok(text) => text,
+                Err(error) => { self.report(IssueSeverity::Error, &structure_path, None, format!("could not read: {}", error)); return; }
+            };
+            match import_tiled_map(&structure_path, &text)
+            {
+                Ok(level_data) => level_data,
+                Err(error) => { self.report(IssueSeverity::Error, &structure_path, None, error); return; }
+            }
+        }
+        else
+        {
+            let image = match self.read_image(&structure_path)
+            {
+                Some(image) => image,
+                None => { return; }
+            };
+            let palette = self.read_palette(stage);
+            self.palettes.push((stage.name.clone(), palette.name.clone()));
+            self.check_structure_colors(&structure_path, &image, &palette);
+
+            let mut loader = LevelLoader::new();
+            let mut level_data = loader.convert_image_to_level(&image, &palette);
+            match &stage.spawnmap
+            {
+                Some(spawnmap) => {
+                    let spawnmap_path = format!("{}{}", self.folder, spawnmap);
+                    if let Some(spawnmap) = self.read_image(&spawnmap_path)
+                    {
+                        self.check_spawnmap(&spawnmap_path, &spawnmap, &level_data);
+                        LevelLoader::apply_spawnmap(&mut level_data, &spawnmap);
+                    }
+                }
+                None => {
+                    if !level_data.enemy_spawner.is_empty()
+                    {
+                        self.report(IssueSeverity::Warning, &structure_path, None, format!("{} Spawners but no Spawnmap -> they never spawn", level_data.enemy_spawner.len()));
+                    }
+                }
+            }
+            level_data
+        };
+
+        self.check_level_end(&structure_path, &level_data);
+        self.check_spawners(&structure_path, &level_data);
+    }
+
+    // Broken Palettes are Errors -> the Stage is still checked against the Default Colors
+    fn read_palette(&mut self, stage: &StageEntry) -> LevelPalette
+    {
+        let file = match stage.palette.clone().or(self.pack_palette.clone())
+        {
+            Some(file) => file,
+            None => { return LevelPalette::default(); }
+        };
+        let path = format!("{}{}", self.folder, file);
+        let palette = std::fs::read_to_string(&path)
+            .map_err(|error| format!("could not read Palette: {}", error))
+            .and_then(|text| LevelPalette::parse(&text));
+        match palette
+        {
+            Ok(palette) => {
+                self.check_palette(&path, &palette);
+                palette
+            }
+            Err(error) => {
+                self.report(IssueSeverity::Error, &path, None, error);
+                LevelPalette::default()
+            }
+        }
+    }
+
+    fn read_image(&mut self, path: &str) -> Option<Image>
+    {
+        let bytes = match std::fs::read(path)
+        {
+            Ok(bytes) => bytes,
+            Err(error) => { self.report(IssueSeverity::Error, path, None, format!("could not read: {}", error)); return None; }
+        };
+        match LevelLoader::decode_png(&bytes)
+        {
+            Ok(image) => Some(image),
+            Err(error) => { self.report(IssueSeverity::Error, path, None, error); None }
+        }
+    }
+
+    // ---------------------------------
+    // Checks
+
+    // Every visible Pixel has to match exactly one Palette Entry
+    fn check_structure_colors(&mut self, path: &str, image: &Image, palette: &LevelPalette)
+    {
+        for y in 0..image.height() as u32
+        {
+            for x in 0..image.width() as u32
+            {
+                let pixel = image.get_pixel(x, y);
+                if is_empty_pixel(pixel)
+                {
+                    continue;
+                }
+                let matches: Vec<String> = palette.matches(pixel).iter().map(|entry| entry.kind.clone()).collect();
+                match matches.len()
+                {
+                    0 => { self.report(IssueSeverity::Warning, path, Some((x, y)), format!("unknown Color {} is ignored", color_to_string(pixel))); }
+                    1 => {}
+                    _ => { self.report(IssueSeverity::Error, path, Some((x, y)), format!("Color {} matches {} at once", color_to_string(pixel), matches.join(" and "))); }
+                }
+            }
+        }
+    }
+
+    // Two Entries closer than twice the Threshold -> some Colors between them match both
+    fn check_palette(&mut self, path: &str, palette: &LevelPalette)
+    {
+        for (i, first) in palette.entries.iter().enumerate()
+        {
+            for second in palette.entries[i + 1..].iter()
+            {
+                if compare_color_in_range(first.color, second.color, palette.threshold * 2.0)
+                {
+                    self.report(IssueSeverity::Warning, path, None, format!("{} and {} are too close for a Threshold of {}", first.kind, second.kind, palette.threshold));
+                }
+            }
+        }
+    }
+
+    // Spawnmap Pixels are matched to Spawners in Reading Order -> both Counts have to agree
+    fn check_spawnmap(&mut self, path: &str, spawnmap: &Image, level_data: &LoadedLevelData)
+    {
+        let mut found_spawner = 0;
+        for y in 0..spawnmap.height() as u32
+        {
+            for x in 0..spawnmap.width() as u32
+            {
+                let pixel = spawnmap.get_pixel(x, y);
+                if pixel.a != 1.0
+                {
+                    continue;
+                }
+                if found_spawner >= level_data.enemy_spawner.len()
+                {
+                    self.report(IssueSeverity::Error, path, Some((x, y)), "Spawnmap Pixel without a Spawner -> ignored".to_string());
+                }
+                else
+                {
+                    let spawner = level_data.enemy_spawner[found_spawner].0;
+                    if spawner.x as u32 != x || spawner.y as u32 != y
+                    {
+                        self.report(IssueSeverity::Warning, path, Some((x, y)), format!("Spawnmap Pixel is used by the Spawner at ({}, {})", spawner.x, spawner.y));
+                    }
+                }
+                found_spawner += 1;
+            }
+        }
+        if found_spawner < level_data.enemy_spawner.len()
+        {
+            for spawner in level_data.enemy_spawner[found_spawner..].iter()
+            {
+                self.report(IssueSeverity::Error, path, Some((spawner.0.x as u32, spawner.0.y as u32)), "Spawner has no Spawnmap Pixel -> never spawns".to_string());
+            }
+        }
+    }
+
+    // Level End has to be one Block -> neighbouring Columns, each without Gaps
+    fn check_level_end(&mut self, path: &str, level_data: &LoadedLevelData)
+    {
+        if level_data.level_end.is_empty()
+        {
+            self.report(IssueSeverity::Error, path, None, "no Level End -> the Stage never ends".to_string());
+            return;
+        }
+        let mut columns: Vec<(u32, Vec<u32>)> = Vec::new();
+        for end in level_data.level_end.iter()
+        {
+            let (x, y) = (end.x as u32, end.y as u32);
+            match columns.iter_mut().find(|(column, _)| *column == x)
+            {
+                Some((_, rows)) => { rows.push(y); }
+                None => { columns.push((x, vec![y])); }
+            }
+        }
+        columns.sort_by_key(|(column, _)| *column);
+
+        for pair in columns.windows(2)
+        {
+            if pair[1].0 > pair[0].0 + 1
+            {
+                self.report(IssueSeverity::Error, path, Some((pair[1].0, pair[1].1[0])), format!("Level End Columns {} and {} are not next to each other", pair[0].0, pair[1].0));
+            }
+        }
+        for (column, rows) in columns.iter_mut()
+        {
+            rows.sort();
+            for pair in rows.windows(2)
+            {
+                if pair[1] > pair[0] + 1
+                {
+                    self.report(IssueSeverity::Error, path, Some((*column, pair[0] + 1)), format!("Level End Column {} has a Gap from Row {} to {}", column, pair[0] + 1, pair[1] - 1));
+                }
+            }
+        }
+    }
+
+    fn check_spawners(&mut self, path: &str, level_data: &LoadedLevelData)
+    {
+        for (position, count, enemy_type) in level_data.enemy_spawner.iter()
+        {
+            let position = Some((position.x as u32, position.y as u32));
+            if EnemySpawner::enemy_type_from_id(*enemy_type).is_none()
+            {
+                self.report(IssueSeverity::Warning, path, position, format!("Enemy Type {} does not exist -> spawns Default", enemy_type));
+            }
+            if *count == 0
+            {
+                self.report(IssueSeverity::Warning, path, position, "Spawner with a Count of 0".to_string());
+            }
+        }
+    }
+}
+
+// Background -> black or fully transparent
+fn is_empty_pixel(pixel: Color) -> bool
+{
+    pixel.a == 0.0 || (pixel.r == 0.0 && pixel.g == 0.0 && pixel.b == 0.0)
+}
+fn color_to_string(color: Color) -> String
+{
+    format!("({}, {}, {}, {})", (color.r * 255.0) as u8, (color.g * 255.0) as u8, (color.b * 255.0) as u8, (color.a * 255.0) as u8)
+}
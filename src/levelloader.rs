@@ -1,5 +1,66 @@
+use macroquad::audio::load_sound;
 use super::*;
 
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LevelElementKind
+{
+    // Only Visual
+    Wall, InfectedWall,
+    // Game Blocks
+    BlockingWall, TrapWall, Destructible, EnemySpawner, Turret, LevelEnd,
+}
+impl LevelElementKind
+{
+    pub fn all() -> [LevelElementKind; 8]
+    {
+        [
+            LevelElementKind::Wall, LevelElementKind::InfectedWall, LevelElementKind::BlockingWall, LevelElementKind::TrapWall,
+            LevelElementKind::Destructible, LevelElementKind::EnemySpawner, LevelElementKind::Turret, LevelElementKind::LevelEnd,
+        ]
+    }
+    // Names used in Palettes and Tiled Maps
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            LevelElementKind::Wall => "wall",
+            LevelElementKind::InfectedWall => "infected_wall",
+            LevelElementKind::BlockingWall => "blocking_wall",
+            LevelElementKind::TrapWall => "trap_wall",
+            LevelElementKind::Destructible => "destructible",
+            LevelElementKind::EnemySpawner => "enemy_spawner",
+            LevelElementKind::Turret => "turret",
+            LevelElementKind::LevelEnd => "level_end",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<LevelElementKind>
+    {
+        LevelElementKind::all().iter().find(|kind| kind.get_name() == name).copied()
+    }
+}
+
+// Element without a built-in Kind (Pickups, Checkpoints, Triggers, ...) -> Systems look them up by Kind Name
+#[derive(Clone, PartialEq, Debug)]
+pub struct LevelMarker
+{
+    pub kind: String,
+    pub position: Vec2,
+    // In Cells -> 1 x 1 for Image Stages
+    pub size: Vec2,
+    // Tiled Object Properties -> empty for Image Stages
+    pub properties: TiledProperties,
+}
+
+// Extra Settings of a Spawner (same Index as enemy_spawner) -> only Tiled Maps carry them
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnerProperties
+{
+    // Seconds before the first Enemy
+    pub delay: f32,
+    // Every Property of the Tiled Object, including the ones the Game does not know
+    pub custom: TiledProperties,
+}
+
 #[derive(Clone)]
 pub struct LoadedLevelData
 {
@@ -12,9 +73,26 @@ pub struct LoadedLevelData
     pub trapWalls: Vec<Vec2>,
 
     pub enemy_spawner: Vec<(Vec2, usize, usize)>,
+    pub spawner_properties: Vec<SpawnerProperties>,
+    // Bound to Spawners by Cell or by the Tiled Property "definition"
+    pub spawner_definitions: Vec<SpawnerDefinition>,
+    pub triggers: Vec<StageTrigger>,
+    pub hazards: Vec<StageHazard>,
+    pub markers: Vec<LevelMarker>,
     pub destructibles: Vec<Vec2>,
     pub turrets: Vec<Vec2>,
-    pub level_end: Vec<Vec2>
+    pub level_end: Vec<Vec2>,
+    // Width & Height of the Structure in Cells
+    pub size: (u32, u32),
+
+    // Name, Scroll Speed & Music from the Manifest
+    pub stage: StageEntry,
+    // Name of the Palette the Structure Image was read with
+    pub palette: String,
+    // Atlas Layout the Walls are autotiled with
+    pub tile_atlas: TileAtlas,
+    pub background: Background,
+    pub music: SoundData,
 }
 impl LoadedLevelData
 {
@@ -26,238 +104,527 @@ impl LoadedLevelData
             blockingWalls: Vec::new(), 
             trapWalls: Vec::new(), 
             enemy_spawner: Vec::new(), 
+            spawner_properties: Vec::new(),
+            spawner_definitions: Vec::new(),
+            triggers: Vec::new(),
+            hazards: Vec::new(),
+            markers: Vec::new(),
             destructibles: Vec::new(), 
             turrets: Vec::new(),
             level_end: Vec::new(),
+            size: (0, 0),
+
+            stage: StageEntry::new(""),
+            palette: DEFAULT_PALETTE_NAME.to_string(),
+            tile_atlas: TileAtlas::default(),
+            background: Background::default(),
+            music: SoundData::empty(),
+        }
+    }
+    // Unknown Kinds become Markers -> new Kinds only need a Palette Entry
+    pub fn add_named_element(&mut self, kind: &str, position: Vec2)
+    {
+        match LevelElementKind::from_name(kind)
+        {
+            Some(kind) => { self.add_element(kind, position); }
+            None => { self.markers.push(LevelMarker { kind: kind.to_string(), position, size: vec2(1.0, 1.0), properties: TiledProperties::new() }); }
+        }
+    }
+    // Spawners start empty -> Count and Type come from the Spawnmap
+    pub fn add_element(&mut self, kind: LevelElementKind, position: Vec2)
+    {
+        match kind
+        {
+            LevelElementKind::Wall => { self.walls.push(position); }
+            LevelElementKind::InfectedWall => { self.infected_wall_filling.push(position); }
+            LevelElementKind::BlockingWall => { self.blockingWalls.push(position); }
+            LevelElementKind::TrapWall => { self.trapWalls.push(position); }
+            LevelElementKind::Destructible => { self.destructibles.push(position); }
+            LevelElementKind::EnemySpawner => { self.enemy_spawner.push((position, 0, 0)); }
+            LevelElementKind::Turret => { self.turrets.push(position); }
+            LevelElementKind::LevelEnd => { self.level_end.push(position); }
         }
     }
 }
 
 pub struct LevelLoader
 {
+    // Pack Folder the Manifest was read from -> the Stage Editor exports back into it
+    pub folder: String,
+    pub manifest: LevelManifest,
     pub levels: Vec<LoadedLevelData>,
+    // Palette File -> parsed Palette, shared by every Stage using it
+    palettes: Vec<(String, LevelPalette)>,
+    // Wall Atlas Layout of the Pack -> handed to every Stage
+    pub tile_atlas: TileAtlas,
+    // Background File -> parsed Layer Set, read once after the Manifest
+    backgrounds: Vec<(String, Background)>,
+    // Spawner File -> parsed Definitions, read once after the Manifest
+    spawner_files: Vec<(String, Vec<SpawnerDefinition>)>,
+    // Trigger File -> parsed Triggers, read once after the Manifest
+    trigger_files: Vec<(String, Vec<StageTrigger>)>,
+    // Hazard File -> parsed Hazards, read once after the Manifest
+    hazard_files: Vec<(String, Vec<StageHazard>)>,
+    // Everything that failed to load -> broken Stages are left out, the rest of the Pack still plays
+    pub errors: Vec<LoadError>,
 }
 impl LevelLoader 
 {
     pub fn new() -> Self {
-        Self { levels: Vec::new() }
+        Self { folder: String::new(), manifest: LevelManifest::new(), levels: Vec::new(), palettes: Vec::new(), tile_atlas: TileAtlas::default(), backgrounds: Vec::new(), spawner_files: Vec::new(), trigger_files: Vec::new(), hazard_files: Vec::new(), errors: Vec::new() }
     }
     pub async fn level_loader_init(&mut self)
     {
-        self.init_load_for_wasm("resources/levels/Level_1/".to_string()).await;
+        self.load_pack(DEFAULT_LEVEL_PACK, PackSource::Window).await;
     }
-
-    pub async fn init_load_for_wasm(&mut self, folder_path: String)
+    // No Window -> same Load Path, the Files come straight from Disk
+    pub fn level_loader_init_headless(&mut self, folder_path: &str)
     {
-        self.level_structure_files(&folder_path).await;
+        run_ready(self.load_pack(folder_path, PackSource::Disk));
     }
 
-    async fn level_structure_files(&mut self, folder_path: &String)
+    // One Load Path for every Build -> only the Source of the Files differs
+    async fn load_pack(&mut self, folder_path: &str, source: PackSource)
     {
-        let path_dir = folder_path.as_str();
-
-        let level_files = vec![
-            //"test_level.png",
-            //"test_level_2.png",
-            //"test_level_3.png",
-            //"Level_1.png",
-            //"Level_2.png",
-            //"Level_3.png",
-            //"Level_4.png",
-
-            "Level_1_Stage_1.png",
-            "Level_1_Stage_2.png",
-            "Level_1_Stage_3.png",
-            "Level_1_Stage_4.png",
-            "Level_1_Stage_5.png",
-
-            "Level_1_Stage_6.png",
-            "Level_1_Stage_7.png",
-            "Level_1_Stage_8.png",
-            "Level_1_Stage_9.png",
-            "Level_1_Stage_10.png",
-        ];
-
-        let spawnmap_files = vec![
-            //"test_level_3_SpawnMap.png",
-            //"Level_1_SpawnMap.png",
-            //"Level_2_SpawnMap.png",
-            //"Level_3_SpawnMap.png",
-            //"Level_4_SpawnMap.png",
-
-            "Level_1_Stage_1_Spawnmap.png",
-            "Level_1_Stage_2_Spawnmap.png",
-            "Level_1_Stage_3_Spawnmap.png",
-            "Level_1_Stage_4_Spawnmap.png",
-            "Level_1_Stage_5_Spawnmap.png",
+        self.folder = folder_path.to_string();
+        let manifest_path = format!("{}{}", folder_path, MANIFEST_FILE);
+        let text = match source.read_string(&manifest_path).await
+        {
+            Ok(text) => text,
+            Err(error) => { self.report(error); return; }
+        };
+        if !self.apply_manifest(&text, &manifest_path)
+        {
+            return;
+        }
+        if let Some(file) = self.manifest.tile_atlas.clone()
+        {
+            let path = format!("{}{}", folder_path, file);
+            let text = source.read_string(&path).await;
+            self.apply_tile_atlas(&path, text);
+        }
 
-            "Level_1_Stage_6_Spawnmap.png",
-            "Level_1_Stage_7_Spawnmap.png",
-            "Level_1_Stage_8_Spawnmap.png",
-            "Level_1_Stage_9_Spawnmap.png",
-            "Level_1_Stage_10_Spawnmap.png",
-        ];
+        // Broken or missing Side Files are left out -> Backgrounds stay black, Spawnmap Spawners stay as they are
+        let files = self.side_file_names(self.manifest.background.clone(), |stage| stage.background.clone());
+        self.backgrounds = self.load_side_files(source, files, Background::parse, |background| format!("{} Layers", background.layers.len())).await;
+        let files = self.side_file_names(None, |stage| stage.spawners.clone());
+        self.spawner_files = self.load_side_files(source, files, SpawnerDefinition::parse_all, |definitions| format!("{} Spawners", definitions.len())).await;
+        let files = self.side_file_names(None, |stage| stage.triggers.clone());
+        self.trigger_files = self.load_side_files(source, files, StageTrigger::parse_all, |triggers| format!("{} Triggers", triggers.len())).await;
+        let files = self.side_file_names(None, |stage| stage.hazards.clone());
+        self.hazard_files = self.load_side_files(source, files, StageHazard::parse_all, |hazards| format!("{} Hazards", hazards.len())).await;
 
-        let level_paths = self.build_filepath(level_files, path_dir);
-        let levelspawn_paths = self.build_filepath(spawnmap_files, path_dir);
+        for stage in self.manifest.stages.clone().iter()
+        {
+            self.load_stage(stage, source).await;
+        }
+        self.clamp_loop_stage();
+    }
+    async fn load_stage(&mut self, stage: &StageEntry, source: PackSource)
+    {
+        let path = format!("{}{}", self.folder, stage.structure);
+        if is_tiled_map(&path)
+        {
+            match source.read_string(&path).await
+            {
+                Ok(text) => { self.push_tiled_map(&path, &text, stage); }
+                Err(error) => { self.report(error); }
+            }
+            return;
+        }
+        let level_image = match source.read_image(&path).await
+        {
+            Ok(image) => image,
+            Err(error) => { self.report(error); return; }
+        };
+        let palette = match self.palette_file(stage)
+        {
+            Some(file) => {
+                let palette_path = format!("{}{}", self.folder, file);
+                let text = source.read_string(&palette_path).await;
+                self.get_palette(&palette_path, text)
+            }
+            None => LevelPalette::default(),
+        };
+        let mut level_data = self.convert_image_to_level(&level_image, &palette);
+        level_data.stage = stage.clone();
 
-        for i in 0..level_paths.0.len()
+        if let Some(spawnmap) = &stage.spawnmap
         {
-            let path = level_paths.0[i].clone();
-            let name = level_paths.1[i].clone();
-            let level_image = self.load_file(path.as_str(), name.as_str()).await;
-            let mut level_data = self.convert_image_to_level(&level_image);
-            
-            self.load_spawnmap(&mut level_data, &levelspawn_paths.0[i], &levelspawn_paths.1[i]).await;
+            match source.read_image(&format!("{}{}", self.folder, spawnmap)).await
+            {
+                Ok(spawnmap) => { LevelLoader::apply_spawnmap(&mut level_data, &spawnmap); }
+                Err(error) => { self.report(error); }
+            }
+        }
+        // Sounds need the Audio of a Window
+        if let (Some(music), PackSource::Window) = (&stage.music, source)
+        {
+            let music_path = format!("{}{}", self.folder, music);
+            match load_sound(&music_path).await
+            {
+                Ok(sound) => { level_data.music = SoundData::new(sound); }
+                Err(error) => { self.report(LoadError::new(&music_path, LoadErrorKind::Missing, &error.to_string())); }
+            }
+        }
 
-            self.levels.push(level_data);
+        println!("{} built with Palette {}", stage.name, level_data.palette);
+        self.push_level(&path, level_data);
+    }
+    fn report(&mut self, error: LoadError)
+    {
+        println!("Could not load {}", error);
+        self.errors.push(error);
+    }
+    // Stages without a Level End would panic once started -> left out of the Run
+    fn push_level(&mut self, path: &str, mut level_data: LoadedLevelData)
+    {
+        if level_data.level_end.is_empty()
+        {
+            self.report(LoadError::new(path, LoadErrorKind::Invalid, format!("{} has no Level End", level_data.stage.name).as_str()));
+            return;
         }
+        level_data.tile_atlas = self.tile_atlas.clone();
+        level_data.background = self.find_background(&level_data.stage);
+        level_data.spawner_definitions = self.bind_spawner_definitions(path, &level_data);
+        level_data.triggers = self.collect_triggers(path, &level_data);
+        level_data.hazards = self.collect_hazards(path, &level_data);
+        self.levels.push(level_data);
     }
-    fn build_filepath(&self ,files: Vec<&str>, path: &str) -> (Vec<String>,Vec<String>)
+    // Left out Stages shorten the Run -> the Loop has to stay inside it
+    fn clamp_loop_stage(&mut self)
     {
-        let mut file_paths : (Vec<String>,Vec<String>) = (Vec::new(), Vec::new());
-        for file in files.iter()
+        if self.manifest.loop_stage >= self.levels.len()
         {
-            let mut full_path = "".to_string();
-            full_path.push_str(path);
-            full_path.push_str(file);
-            file_paths.0.push(full_path);
-
-            let mut name = file.to_string();
-            let s = file.to_string();
-            let offset = s.find(".").unwrap();
-            name.drain(offset..);
-            file_paths.1.push(name);
+            self.manifest.loop_stage = self.levels.len().saturating_sub(1);
+        }
+    }
+    // macroquad panics on broken Images -> decoded with the image Crate, which reports them instead
+    pub fn decode_png(bytes: &[u8]) -> Result<Image, String>
+    {
+        let decoded = ::image::load_from_memory_with_format(bytes, ::image::ImageFormat::Png)
+            .map_err(|error| format!("not a valid PNG ({})", error))?
+            .to_rgba8();
+        if decoded.width() > u16::MAX as u32 || decoded.height() > u16::MAX as u32
+        {
+            return Err(format!("{} x {} Pixel is too large", decoded.width(), decoded.height()));
         }
-        return file_paths;
+        Ok(Image { width: decoded.width() as u16, height: decoded.height() as u16, bytes: decoded.into_raw() })
     }
 
-    async fn load_spawnmap(&mut self, level_data: &mut LoadedLevelData, path: &str, name: &str)
+    fn apply_manifest(&mut self, text: &str, manifest_path: &str) -> bool
     {
-        path.to_string().push_str(name);
-        let spawnmap_result = load_image(path.to_string().as_str()).await;
-        match spawnmap_result
+        match LevelManifest::parse(text)
         {
-            Ok(spawnmap) => {
+            Ok(manifest) => {
+                println!("Level Pack {}: {} Stages", manifest.name, manifest.stages.len());
+                self.manifest = manifest;
+                true
+            }
+            Err(error) => { self.report(LoadError::new(manifest_path, LoadErrorKind::Parse, &error)); false }
+        }
+    }
 
-                let mut found_spawner = 0;
-                for y in 0..spawnmap.height()
-                {
-                    for x in 0..spawnmap.width()
-                    {
-                        if spawnmap.get_pixel(x as u32, y as u32).a == 1.0
-                        {
-                            
-                            if found_spawner < level_data.enemy_spawner.len() {
-                                // 1 = Count of Enemies
-                                level_data.enemy_spawner[found_spawner] .1 = (spawnmap.get_pixel(x as u32, y as u32).g * 255.0) as usize;
-                                // 2 = Type of Enemies
-                                level_data.enemy_spawner[found_spawner] .2 = (spawnmap.get_pixel(x as u32, y as u32).b * 255.0) as usize;
-                                //println!("Spawner Count: {}", level_data.enemy_spawner[found_spawner] .1);
-                                found_spawner += 1;
-                            }
-                        }
-                    }
-                } 
+    // Broken or missing Layouts keep the Default Layout
+    fn apply_tile_atlas(&mut self, path: &str, text: Result<String, LoadError>)
+    {
+        match text.and_then(|text| TileAtlas::parse(&text).map_err(|error| LoadError::new(path, LoadErrorKind::Parse, &error)))
+        {
+            Ok(atlas) => {
+                println!("Tile Atlas Layout {} loaded", atlas.name);
+                self.tile_atlas = atlas;
+            }
+            Err(error) => {
+                self.report(error);
+                println!("{} -> Default Tile Atlas Layout", path);
+            }
+        }
+    }
 
+    // Every Side File of one Kind the Pack & its Stages name, each once
+    fn side_file_names(&self, pack_file: Option<String>, stage_file: fn(&StageEntry) -> Option<String>) -> Vec<String>
+    {
+        let mut files: Vec<String> = Vec::new();
+        for file in pack_file.into_iter().chain(self.manifest.stages.iter().filter_map(stage_file))
+        {
+            if !files.contains(&file)
+            {
+                files.push(file);
+            }
+        }
+        files
+    }
+    // Reads & parses every File once -> broken or missing Files are reported and left out
+    async fn load_side_files<T>(&mut self, source: PackSource, files: Vec<String>, parse: fn(&str) -> Result<T, String>, summary: fn(&T) -> String) -> Vec<(String, T)>
+    {
+        let mut loaded = Vec::new();
+        for file in files
+        {
+            let path = format!("{}{}", self.folder, file);
+            let text = source.read_string(&path).await;
+            match text.and_then(|text| parse(&text).map_err(|error| LoadError::new(&path, LoadErrorKind::Parse, &error)))
+            {
+                Ok(content) => {
+                    println!("{} loaded ({})", file, summary(&content));
+                    loaded.push((file, content));
+                }
+                Err(error) => { self.report(error); }
+            }
+        }
+        loaded
+    }
+    // Markers of one Kind with Properties (Tiled Objects) -> built the same Way as the Blocks of a Side File
+    fn build_marker_objects<T>(&mut self, path: &str, level_data: &LoadedLevelData, kind: &str, label: &str, from_properties: fn(&str, Rect, &TiledProperties) -> Result<T, String>) -> Vec<T>
+    {
+        let mut objects = Vec::new();
+        let markers = level_data.markers.iter().filter(|m| m.kind == kind && !m.properties.is_empty());
+        for (i, marker) in markers.enumerate()
+        {
+            let area = Rect::new(marker.position.x, marker.position.y, marker.size.x, marker.size.y);
+            match from_properties(&format!("{} Object {}", label, i + 1), area, &marker.properties)
+            {
+                Ok(object) => { objects.push(object); }
+                Err(error) => { self.report(LoadError::new(path, LoadErrorKind::Invalid, &error)); }
+            }
+        }
+        objects
+    }
+    // Definitions whose Cell holds no Spawner are reported -> the Stage still plays without them
+    fn bind_spawner_definitions(&mut self, path: &str, level_data: &LoadedLevelData) -> Vec<SpawnerDefinition>
+    {
+        let definitions = match find_side_file(&self.spawner_files, &level_data.stage.spawners)
+        {
+            Some(definitions) => definitions.clone(),
+            None => { return Vec::new(); }
+        };
+        for definition in definitions.iter()
+        {
+            match definition.cell
+            {
+                Some(cell) if !level_data.enemy_spawner.iter().any(|s| s.0 == cell) => {
+                    let reason = format!("{}: no Spawner at Cell {}, {}", definition.name, cell.x, cell.y);
+                    self.report(LoadError::new(path, LoadErrorKind::Invalid, &reason));
+                }
+                _ => {}
+            }
+        }
+        definitions
+    }
+    // Trigger Markers with Properties (Tiled Objects) & the Triggers of the Stage File
+    fn collect_triggers(&mut self, path: &str, level_data: &LoadedLevelData) -> Vec<StageTrigger>
+    {
+        let mut triggers = self.build_marker_objects(path, level_data, TRIGGER_MARKER_KIND, "Trigger", StageTrigger::from_properties);
+        triggers.extend(find_side_file(&self.trigger_files, &level_data.stage.triggers).cloned().unwrap_or_default());
+        triggers
+    }
+    // Hazard Markers (Tiled Objects) & the Hazards of the Stage File
+    // Image Markers carry no Properties -> the File Block whose area starts at the Marker Cell describes them
+    fn collect_hazards(&mut self, path: &str, level_data: &LoadedLevelData) -> Vec<StageHazard>
+    {
+        let file_hazards = find_side_file(&self.hazard_files, &level_data.stage.hazards).cloned().unwrap_or_default();
+        for marker in level_data.markers.iter().filter(|m| m.kind == HAZARD_MARKER_KIND && m.properties.is_empty())
+        {
+            if !file_hazards.iter().any(|h| h.area.x == marker.position.x && h.area.y == marker.position.y)
+            {
+                let error = format!("Hazard Marker at {}, {} needs a [hazard] Block in the hazards File with its area starting at that Cell", marker.position.x, marker.position.y);
+                self.report(LoadError::new(path, LoadErrorKind::Invalid, &error));
             }
-            Err(error) => {}
         }
+        let mut hazards = self.build_marker_objects(path, level_data, HAZARD_MARKER_KIND, "Hazard", StageHazard::from_properties);
+        hazards.extend(file_hazards);
+        hazards
+    }
+    // Stage Background overrides the one of the Pack
+    pub fn find_background(&self, stage: &StageEntry) -> Background
+    {
+        let file = stage.background.clone().or(self.manifest.background.clone());
+        find_side_file(&self.backgrounds, &file).cloned().unwrap_or_default()
+    }
 
-        
+    // Stage Palette overrides the one of the Pack
+    pub fn palette_file(&self, stage: &StageEntry) -> Option<String>
+    {
+        stage.palette.clone().or(self.manifest.palette.clone())
+    }
+    // Palette a Stage was built with -> Default if it was never loaded
+    pub fn find_palette(&self, name: &str) -> LevelPalette
+    {
+        match self.palettes.iter().find(|(_, palette)| palette.name == name)
+        {
+            Some((_, palette)) => palette.clone(),
+            None => LevelPalette::default(),
+        }
+    }
+    // Broken or missing Palettes fall back to the Default Colors
+    fn get_palette(&mut self, path: &str, text: Result<String, LoadError>) -> LevelPalette
+    {
+        if let Some((_, palette)) = self.palettes.iter().find(|(file, _)| file == path) { return palette.clone(); }
+        let palette = match text.and_then(|text| LevelPalette::parse(&text).map_err(|error| LoadError::new(path, LoadErrorKind::Parse, &error)))
+        {
+            Ok(palette) => palette,
+            Err(error) => {
+                self.report(error);
+                println!("{} -> Default Palette", path);
+                LevelPalette::default()
+            }
+        };
+        self.palettes.push((path.to_string(), palette.clone()));
+        palette
     }
 
+    // Tiled Stages carry their Spawner Settings as Object Properties -> no Spawnmap
+    fn push_tiled_map(&mut self, path: &str, text: &str, stage: &StageEntry)
+    {
+        match import_tiled_map(path, text)
+        {
+            Ok(mut level_data) => {
+                level_data.stage = stage.clone();
+                level_data.palette = "Tiled".to_string();
+                self.push_level(path, level_data);
+            }
+            Err(error) => { self.report(LoadError::new(path, LoadErrorKind::Parse, &error)); }
+        }
+    }
 
-    async fn load_file(&mut self, path: &str, filename: &str) -> Image
+    pub fn apply_spawnmap(level_data: &mut LoadedLevelData, spawnmap: &Image)
     {
-        path.to_string().push_str(filename);
-        let image = load_image(path.to_string().as_str()).await.unwrap();
-        image
+        let mut found_spawner = 0;
+        for y in 0..spawnmap.height()
+        {
+            for x in 0..spawnmap.width()
+            {
+                if spawnmap.get_pixel(x as u32, y as u32).a == 1.0 && found_spawner < level_data.enemy_spawner.len()
+                {
+                    // 1 = Count of Enemies
+                    level_data.enemy_spawner[found_spawner] .1 = (spawnmap.get_pixel(x as u32, y as u32).g * 255.0) as usize;
+                    // 2 = Type of Enemies
+                    level_data.enemy_spawner[found_spawner] .2 = (spawnmap.get_pixel(x as u32, y as u32).b * 255.0) as usize;
+                    //println!("Spawner Count: {}", level_data.enemy_spawner[found_spawner] .1);
+                    found_spawner += 1;
+                }
+            }
+        } 
     }
 
-    fn convert_image_to_level(&mut self, level_image: &Image) -> LoadedLevelData
+    pub fn convert_image_to_level(&mut self, level_image: &Image, palette: &LevelPalette) -> LoadedLevelData
     {
         let mut new_level = LoadedLevelData::new();
-
-        let threshhold =20.0;
-
-        // Filling
-        let _wall = color_u8!(255,255,255,255);
-        let _infected_wall_filling = color_u8!(128,1,128,255);
-
-        // Entitoes
-        let _blocking_wall = color_u8!(180,180,180,255);
-        let _trap_wall = color_u8!(255,1,128,255);
-        let _destructible = color_u8!(0,0,255,255);
-        let _enemyspawner = color_u8!(255,0,0,255);
-        let _turret = color_u8!(255,255,0,255);
-        let _level_end = color_u8!(0,255,0,255);
+        new_level.palette = palette.name.clone();
+        new_level.size = (level_image.width() as u32, level_image.height() as u32);
 
         for y in 0..level_image.height()
         {
             for x in 0..level_image.width()
             {
                 let position = vec2(x as f32, y as f32);
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _wall, threshhold)
-                {
-                    new_level.walls.push( position );
-                    
-                }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _infected_wall_filling, threshhold)
-                {
-                    new_level.infected_wall_filling.push( position );
-                    
-                }
-                
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _blocking_wall, threshhold)
-                {
-                    new_level.blockingWalls.push( position );
-                    
-                }
-                
-                //println!("{:?}", level_image.get_pixel(x as u32, y as u32));
-                //println!("{:?}", _trap_wall);
-                // Color { r: 1.0, g: 0.0, b: 0.5019608, a: 1.0 }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _trap_wall, threshhold)
-                {
-                    new_level.trapWalls.push( position );
-
-                }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _destructible, threshhold)
-                {
-                    new_level.destructibles.push( position );
-                }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _enemyspawner, threshhold)
-                {
-                    let spawner = (position, 0,0);
-                    new_level.enemy_spawner.push( spawner );
-                }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _turret, threshhold)
-                {
-                    new_level.turrets.push( position );
-                }
-                if self.compare_color_in_range( level_image.get_pixel(x as u32, y as u32) , _level_end, threshhold)
+                for entry in palette.matches(level_image.get_pixel(x as u32, y as u32))
                 {
-                    new_level.level_end.push( position );
-
+                    new_level.add_named_element(&entry.kind, position);
                 }
             }
         } 
         new_level
     }
     
+}
 
-    fn compare_color_in_range(&self, col_1: Color, col_2: Color, range: f32) -> bool
-    {
-        let color_1 = col_1.to_vec() * 255.0;
-        let color_2 = col_2.to_vec() * 255.0;
-        if color_1.x >= (color_2.x - range) && color_1.x <= (color_2.x + range) &&
-        color_1.y >= (color_2.y - range) && color_1.y <= (color_2.y + range) &&
-        color_1.z >= (color_2.z - range) && color_1.z <= (color_2.z + range) &&
-        color_1.w >= (color_2.w - range) && color_1.w <= (color_2.w + range) {
-            return true;
-        }
-        return false;
+// Where the Pack Files come from
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum PackSource
+{
+    // load_file -> File on native, Fetch in the Browser
+    Window,
+    // No Window -> plain Disk Reads, no Sounds
+    Disk,
+}
+impl PackSource
+{
+    async fn read(&self, path: &str) -> Result<Vec<u8>, LoadError>
+    {
+        let bytes = match self
+        {
+            PackSource::Window => load_file(path).await.map_err(|error| error.to_string()),
+            PackSource::Disk => std::fs::read(path).map_err(|error| error.to_string()),
+        };
+        bytes.map_err(|error| LoadError::new(path, LoadErrorKind::Missing, &error))
+    }
+    async fn read_string(&self, path: &str) -> Result<String, LoadError>
+    {
+        let bytes = self.read(path).await?;
+        String::from_utf8(bytes).map_err(|error| LoadError::new(path, LoadErrorKind::Decode, &error.to_string()))
+    }
+    // Decoded here so broken Images do not panic
+    async fn read_image(&self, path: &str) -> Result<Image, LoadError>
+    {
+        let bytes = self.read(path).await?;
+        LevelLoader::decode_png(&bytes).map_err(|error| LoadError::new(path, LoadErrorKind::Decode, &error))
+    }
+}
+// Disk Reads never wait -> the Load Path is done after its first Poll
+fn run_ready<F: std::future::Future>(future: F) -> F::Output
+{
+    let mut future = std::pin::pin!(future);
+    let mut context = std::task::Context::from_waker(std::task::Waker::noop());
+    match future.as_mut().poll(&mut context)
+    {
+        std::task::Poll::Ready(output) => output,
+        std::task::Poll::Pending => panic!("Disk Reads do not wait"),
+    }
+}
+// Parsed Side File a Stage names
+fn find_side_file<'a, T>(files: &'a [(String, T)], file: &Option<String>) -> Option<&'a T>
+{
+    let file = file.as_ref()?;
+    files.iter().find(|(name, _)| name == file).map(|(_, content)| content)
+}
+// Pack Folder in the Temp Directory with the first Stage Image as "stage.png" -> Tests bring their own Manifest & Side Files
+#[cfg(test)]
+pub fn write_test_pack(name: &str, files: &[(&str, &str)]) -> String
+{
+    let folder = std::env::temp_dir().join(format!("vust_nexus_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&folder).unwrap();
+    std::fs::copy(format!("{}Level_1_Stage_1.png", DEFAULT_LEVEL_PACK), folder.join("stage.png")).unwrap();
+    for (file, text) in files
+    {
+        std::fs::write(folder.join(file), text).unwrap();
+    }
+    format!("{}/", folder.display())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn decodes_pngs_without_panicking()
+    {
+        let bytes = std::fs::read(format!("{}Level_1_Stage_1.png", DEFAULT_LEVEL_PACK)).unwrap();
+        let image = LevelLoader::decode_png(&bytes).unwrap();
+        assert_eq!((image.width, image.height), (256, 24));
+        assert_eq!(image.bytes.len(), 256 * 24 * 4);
+
+        assert!(LevelLoader::decode_png(b"not a png").is_err());
+        assert!(LevelLoader::decode_png(&bytes[..bytes.len() / 2]).is_err());
+    }
+
+    #[test]
+    fn image_hazard_markers_need_a_file_block()
+    {
+        let mut loader = LevelLoader::new();
+        let text = "[hazard]\nkind = blade\narea = 4, 2, 2, 2\npath = 3, 0\n";
+        loader.hazard_files.push(("hazards.cfg".to_string(), StageHazard::parse_all(text).unwrap()));
+
+        let mut level_data = LoadedLevelData::new();
+        level_data.stage.hazards = Some("hazards.cfg".to_string());
+        level_data.add_named_element(HAZARD_MARKER_KIND, vec2(4.0, 2.0));
+        let hazards = loader.collect_hazards("stage.png", &level_data);
+        assert_eq!(hazards.len(), 1);
+        assert!(loader.errors.is_empty());
+
+        level_data.add_named_element(HAZARD_MARKER_KIND, vec2(9.0, 9.0));
+        let hazards = loader.collect_hazards("stage.png", &level_data);
+        assert_eq!(hazards.len(), 1);
+        assert_eq!(loader.errors.len(), 1);
     }
-    
 }
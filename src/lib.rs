@@ -0,0 +1,153 @@
+use macroquad::prelude::*;
+
+pub const GAME_SIZE_X: f32 = 1920.0;
+pub const GAME_SIZE_Y: f32 = 1080.0;
+pub const SHOW_COLLISION: bool = false;
+pub const COLLISION_COLOR: Color = WHITE;
+
+
+pub const SELECTED_LEVEL: usize = 0;
+
+// Default Scroll Speed -> Stages set their own in the Level Manifest
+pub const LEVEL_SPEED: f32 = 300.0;
+
+// Engine
+//------------------
+mod world;
+pub use world::*;
+
+mod assetloader;
+pub use assetloader::*;
+
+mod load_error;
+pub use load_error::*;
+
+mod config_reader;
+pub use config_reader::*;
+
+mod audio;
+pub use audio::*;
+
+mod renderer;
+pub use renderer::*;
+
+mod input;
+pub use input::*;
+
+mod action_map;
+pub use action_map::*;
+
+mod replay;
+pub use replay::*;
+
+mod save;
+pub use save::*;
+
+mod gameobject;
+pub use gameobject::*;
+
+mod collision;
+pub use collision::*;
+
+mod broadphase;
+pub use broadphase::*;
+
+mod events;
+pub use events::*;
+
+mod timestep;
+pub use timestep::*;
+
+mod random;
+pub use random::*;
+
+mod entity;
+pub use entity::*;
+
+mod animationcontroller;
+pub use animationcontroller::*;
+
+mod particle_sys;
+pub use particle_sys::*;
+mod particle_settings;
+pub use particle_settings::*;
+
+mod level;
+pub use level::*;
+mod levelloader;
+pub use levelloader::*;
+
+mod level_manifest;
+pub use level_manifest::*;
+
+mod level_palette;
+pub use level_palette::*;
+
+mod tile_atlas;
+pub use tile_atlas::*;
+
+mod scroll_curve;
+pub use scroll_curve::*;
+
+mod background;
+pub use background::*;
+
+mod spawner_definition;
+pub use spawner_definition::*;
+
+mod stage_trigger;
+pub use stage_trigger::*;
+
+mod hazard;
+pub use hazard::*;
+
+mod checkpoint;
+pub use checkpoint::*;
+
+mod tiled_import;
+pub use tiled_import::*;
+
+mod level_validation;
+pub use level_validation::*;
+
+mod stage_editor;
+pub use stage_editor::*;
+
+mod stage_generator;
+pub use stage_generator::*;
+mod level_blending;
+pub use level_blending::*;
+// Game
+//------------------
+mod game;
+pub use game::*;
+
+mod gamestate;
+pub use gamestate::*;
+
+mod event_subscribers;
+pub use event_subscribers::*;
+
+mod entityparam_settings;
+pub use entityparam_settings::*;
+
+mod player;
+pub use player::*;
+
+mod enemy;
+pub use enemy::*;
+
+mod enemytypes;
+pub use enemytypes::*;
+
+mod enemy_spawner;
+pub use enemy_spawner::*;
+
+mod enemypool;
+pub use enemypool::*;
+
+mod weapon;
+pub use weapon::*;
+
+mod missle;
+pub use missle::*;
@@ -0,0 +1,69 @@
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum LoadErrorKind
+{
+    // File does not exist or could not be read
+    Missing,
+    // File exists, but the Image or Sound inside is broken
+    Decode,
+    // Manifest, Palette or Map Text is malformed
+    Parse,
+    // File was read, but the Stage can not be played
+    Invalid,
+}
+impl LoadErrorKind
+{
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            LoadErrorKind::Missing => "missing",
+            LoadErrorKind::Decode => "broken",
+            LoadErrorKind::Parse => "invalid",
+            LoadErrorKind::Invalid => "unplayable",
+        }
+    }
+}
+
+// Collected while loading instead of panicking -> listed on the Load Error Screen
+#[derive(Clone, PartialEq, Debug)]
+pub struct LoadError
+{
+    pub file: String,
+    pub kind: LoadErrorKind,
+    pub reason: String,
+}
+impl LoadError
+{
+    pub fn new(file: &str, kind: LoadErrorKind, reason: &str) -> Self
+    {
+        Self { file: file.to_string(), kind, reason: reason.to_string() }
+    }
+}
+impl std::fmt::Display for LoadError
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result
+    {
+        write!(f, "{} ({}): {}", self.file, self.kind.get_name(), self.reason)
+    }
+}
+
+// Magenta / Black Checkerboard -> missing Textures stay visible instead of crashing the Game
+pub fn placeholder_texture() -> Texture2D
+{
+    let size = 16;
+    let mut bytes: Vec<u8> = Vec::with_capacity(size * size * 4);
+    for y in 0..size
+    {
+        for x in 0..size
+        {
+            let checker = (x / 4 + y / 4) % 2 == 0;
+            let color: [u8; 4] = if checker { [255, 0, 255, 255] } else { [0, 0, 0, 255] };
+            bytes.extend_from_slice(&color);
+        }
+    }
+    let texture = Texture2D::from_rgba8(size as u16, size as u16, &bytes);
+    texture.set_filter(FilterMode::Nearest);
+    texture
+}
@@ -1,85 +1,5 @@
 use macroquad::prelude::*;
-
-pub const GAME_SIZE_X: f32 = 1920.0;
-pub const GAME_SIZE_Y: f32 = 1080.0;
-pub const LATE_UPDATE_TICK: f32 = 0.065;
-pub const FIXED_UPDATE_TICK: f32 = 0.01;
-pub const SHOW_COLLISION: bool = false;
-pub const COLLISION_COLOR: Color = WHITE;
-
-
-pub const SELECTED_LEVEL: usize = 0;
-
-pub const LEVEL_SPEED: f32 = 300.0;
-
-// Engine
-//------------------
-mod world;
-pub use world::*;
-
-mod assetloader;
-pub use assetloader::*;
-
-mod audio;
-pub use audio::*;
-
-mod renderer;
-pub use renderer::*;
-
-mod gameobject;
-pub use gameobject::*;
-
-mod collision;
-pub use collision::*;
-
-mod entity;
-pub use entity::*;
-
-mod animationcontroller;
-pub use animationcontroller::*;
-
-mod particle_sys;
-pub use particle_sys::*;
-mod particle_settings;
-pub use particle_settings::*;
-
-mod level;
-pub use level::*;
-mod levelloader;
-pub use levelloader::*;
-mod level_blending;
-pub use level_blending::*;
-// Game
-//------------------
-mod game;
-pub use game::*;
-
-mod gamestate;
-pub use gamestate::*;
-
-mod entityparam_settings;
-pub use entityparam_settings::*;
-
-mod player;
-pub use player::*;
-
-mod enemy;
-pub use enemy::*;
-
-mod enemytypes;
-pub use enemytypes::*;
-
-mod enemy_spawner;
-pub use enemy_spawner::*;
-
-mod enemypool;
-pub use enemypool::*;
-
-mod weapon;
-pub use weapon::*;
-
-mod missle;
-pub use missle::*;
+use vust_nexus::*;
 
 fn window_conf() -> Conf
 {
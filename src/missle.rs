@@ -1,5 +1,4 @@
 use super::*;
-use macroquad::audio::{play_sound, PlaySoundParams};
 pub struct MisslePool
 {
     pool: Vec<Missle>,
@@ -23,14 +22,21 @@ impl MisslePool
             self.pool.push(missle);
         }
     }
-    pub fn fire_missle(&mut self, from_weapon: Entity, dir: Vec2, missle_spawn_offset: Vec2, world: &mut World)
+    pub fn fire_missle(&mut self, from_weapon: Entity, layer: CollisionLayer, dir: Vec2, missle_spawn_offset: Vec2, world: &mut World)
     {
         let free_slot = self.get_free_slot();
         match free_slot
         {
             Some(slot) => {
-                self.pool[slot].setup_missle(from_weapon, dir, missle_spawn_offset);
+                let shooter = from_weapon.handle;
+                self.pool[slot].setup_missle(from_weapon, layer, dir, missle_spawn_offset);
                 self.pool[slot].fire();
+                world.events.publish(GameEvent::MissileFired { 
+                    shooter, 
+                    layer, 
+                    position: self.pool[slot].entity.transform.position, 
+                    direction: dir 
+                });
                 self.active_pool.push(self.pool[slot].clone());
                 world.set_entity(&mut self.pool[slot].entity);
                 //println!("Missle tag: {}",  self.pool[slot].entity.tag );
@@ -100,7 +106,7 @@ pub struct Missle
 {
     misslepool_id: usize, 
     entity: Entity,
-    weapon: Option<Entity>,
+    weapon: Option<EntityHandle>,
     dir: Vec2,
     sprite: Texture2D,
     color: Color,
@@ -120,13 +126,13 @@ impl Missle
             entity: Entity::new("Missle","Missle", world),
             weapon: None,
             dir: vec2(0.0, 0.0),
-            sprite: world.assets.get_asset_by_name("player_missle_1".to_string()).unwrap().get_texture_data(),
+            sprite: world.assets.get_asset("player_missle_1").get_texture_data(),
             color: WHITE,
-            sfx_hit: world.assets.get_asset_by_name("hit_1".to_string()).unwrap().get_sound_data(),
+            sfx_hit: world.assets.get_asset("hit_1").get_sound_data(),
         }
     }
 
-    pub fn setup_missle(&mut self,from_weapon: Entity, dir: Vec2, missle_spawn_offset: Vec2)
+    pub fn setup_missle(&mut self,from_weapon: Entity, layer: CollisionLayer, dir: Vec2, missle_spawn_offset: Vec2)
     {
         if self.sprite == Texture2D::empty()
         {
@@ -140,22 +146,36 @@ impl Missle
         }
 
         self.entity.transform.set_position(from_weapon.transform.position + missle_spawn_offset);
+        self.entity.transform.store_previous();
         self.entity.transform.rotation = from_weapon.transform.rotation;
         self.entity.entity_params = from_weapon.entity_params.clone();
-        self.entity.tag = format!("{} Missle", from_weapon.tag);
+        self.entity.set_collision(layer, Missle::get_target_mask(layer));
 
-        if from_weapon.tag.as_str().contains("Player")
+        if layer == CollisionLayer::PLAYER_PROJECTILE
         {
             self.color = WHITE;
-        }else if from_weapon.tag.as_str().contains("Enemy")
+        }else if layer == CollisionLayer::ENEMY_PROJECTILE
         {
             self.color = RED;
         }
 
-        self.weapon = Some(from_weapon);
+        self.weapon = Some(from_weapon.handle);
         self.dir = dir;
 
         
+    }
+    // Which Layers stop a Missle of the given Projectile Layer
+    pub fn get_target_mask(layer: CollisionLayer) -> CollisionLayer
+    {
+        if layer == CollisionLayer::PLAYER_PROJECTILE
+        {
+            return CollisionLayer::ENEMY | CollisionLayer::STRUCTURE | CollisionLayer::HAZARD;
+        }
+        if layer == CollisionLayer::ENEMY_PROJECTILE
+        {
+            return CollisionLayer::PLAYER;
+        }
+        CollisionLayer::NONE
     }
     pub fn fire(&mut self)
     {
@@ -182,8 +202,9 @@ impl GameObject for Missle
         {
             return;
         }
+        self.entity.transform.store_previous();
         //println!("Speed: {}", self.entity.entity_params.firespeed);
-        let position = self.entity.transform.position + (self.dir * self.entity.entity_params.firespeed * get_frame_time());
+        let position = self.entity.transform.position + (self.dir * self.entity.entity_params.firespeed * get_tick_time());
         self.entity.transform.set_position(position);
         if resolve_windowborder(self.entity.transform.rect, world.level_offset)
         {
@@ -194,14 +215,16 @@ impl GameObject for Missle
     }
 
     fn late_update(&mut self, world: &mut World) {
-        //let mut filtered_world = world.entities.clone();
-        //filtered_world.retain(|e| e.tag != "Missle");
-        for entity in world.get_actives().iter_mut()
+        if !self.entity.is_active
         {
-            if !entity.tag.contains("Missle")
-            {
-                self.on_collision(entity);
-            }
+            return;
+        }
+        self.resolve_collisions(world);
+
+        // Collision Callbacks have no World Access -> a Missle that got reset just hit something
+        if !self.entity.is_active
+        {
+            world.audio.play(&self.sfx_hit, 0.01);
         }
     }
 
@@ -210,14 +233,15 @@ impl GameObject for Missle
         {
             return;
         }
+        let render_rect = self.entity.transform.get_render_rect();
         if self.sprite == Texture2D::empty()
         {
-            draw_rectangle(self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.transform.rect.w, self.entity.transform.rect.h, self.color);
+            draw_rectangle(render_rect.x, render_rect.y, render_rect.w, render_rect.h, self.color);
         }else
         {
 
             let params = DrawTextureParams { dest_size: Some(self.entity.transform.get_fullsize()), rotation: self.entity.transform.rotation,..Default::default() };
-            draw_texture_ex(self.sprite, self.entity.transform.rect.x, self.entity.transform.rect.y, self.color, params);
+            draw_texture_ex(self.sprite, render_rect.x, render_rect.y, self.color, params);
         }
         if SHOW_COLLISION 
         {
@@ -227,61 +251,18 @@ impl GameObject for Missle
 }
 impl Collision for Missle
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
-        if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
+        if !self.entity.is_active || !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             //self.rect_color = GREEN;
             return;
         }
 
-        let mut params = PlaySoundParams::default();
-        params.volume = 0.01;
-
-        match entity.tag.as_str()
+        // The Mask already filtered out everything this Missle passes through
+        if entity.collision_layer.intersects(self.entity.collision_mask)
         {
-            "Enemy" => {
-                if self.entity.tag.contains("Player")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            "Player" => {
-                if self.entity.tag.contains("Enemy")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            "Wall" => {
-                if self.entity.tag.contains("Player") || self.entity.tag.contains("Enemy")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            "Destructible" => {
-                if self.entity.tag.contains("Player")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            "TrapWall" => {
-                if self.entity.tag.contains("Player")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            "Turret" => {
-                if self.entity.tag.contains("Player")
-                {
-                    self.reset_missle();
-                    play_sound( self.sfx_hit.sound.unwrap(), params);
-                }
-            },
-            _ => {}
+            self.reset_missle();
         }
     }
 }
\ No newline at end of file
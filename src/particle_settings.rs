@@ -4,7 +4,7 @@ use super::*;
 // Predefined ParticleParams Settings for easier use and Overview inside the entities
 
 
-pub fn thruster_settings( position: Vec2,dir: Vec2, color_tint: Color, spreading_range: Vec2 ) -> ParticleParams
+pub fn thruster_settings( position: Vec2,dir: Vec2, color_tint: Color, spreading_range: Vec2, rng: &mut RandomStream ) -> ParticleParams
 {    
 
     let mut params : ParticleParams = ParticleParams { 
@@ -29,14 +29,15 @@ pub fn thruster_settings( position: Vec2,dir: Vec2, color_tint: Color, spreading
     };
     params.randomize_velocity( 
         vec2(0.0, 2.0),
-        spreading_range
+        spreading_range,
+        rng
     );
 
     params
 }
 
 
-pub fn fire_settings( position: Vec2,dir: Vec2  ) -> ParticleParams
+pub fn fire_settings( position: Vec2,dir: Vec2, rng: &mut RandomStream ) -> ParticleParams
 {
     // | Right X | Left -X |  Bottom Y | Top -Y |
     let dir_multiplier = vec2(50.0, 50.0) + (vec2(50.0, 50.0) * dir);
@@ -91,13 +92,14 @@ pub fn fire_settings( position: Vec2,dir: Vec2  ) -> ParticleParams
 
     params.randomize_velocity( 
         vec2(0.0, 2.0),
-        vec2(-10.0, 10.0)
+        vec2(-10.0, 10.0),
+        rng
     );
     params
 }
 
 
-pub fn explosion_settings( color_1: Color,  color_2: Color , color_end: Color ) -> ParticleParams
+pub fn explosion_settings( color_1: Color,  color_2: Color , color_end: Color, rng: &mut RandomStream ) -> ParticleParams
 {
     // XMin , XMax , YMin , YMax
     // | Right X | Left -X |  Bottom Y | Top -Y |
@@ -132,11 +134,11 @@ pub fn explosion_settings( color_1: Color,  color_2: Color , color_end: Color )
         speed_end: 1.0,
     };
 
-    params.randomize_color(color_1, color_2);
+    params.randomize_color(color_1, color_2, rng);
     params
 }
 
-pub fn destruction_settings( color_1: Color,  color_2: Color , color_end: Color) -> ParticleParams
+pub fn destruction_settings( color_1: Color,  color_2: Color , color_end: Color, rng: &mut RandomStream) -> ParticleParams
 {
     // XMin , XMax , YMin , YMax
     // | Right X | Left -X |  Bottom Y | Top -Y |
@@ -171,6 +173,6 @@ pub fn destruction_settings( color_1: Color,  color_2: Color , color_end: Color)
         speed_end: 1.0,
     };
 
-    params.randomize_color(color_1, color_2);
+    params.randomize_color(color_1, color_2, rng);
     params
 }
\ No newline at end of file
@@ -1,7 +1,6 @@
 
 use super::*;
 use interpolation::*;
-use macroquad::rand::*;
 
 #[derive(Clone)]
 pub struct ParticleSystemPool
@@ -14,19 +13,19 @@ impl ParticleSystemPool
     {
         Self { pool: Vec::new()}
     }
-    pub fn spawn_system_at_position(&mut self, position: Vec2, particle_count: usize, params: ParticleParams)
+    pub fn spawn_system_at_position(&mut self, position: Vec2, particle_count: usize, params: ParticleParams, rng: &mut RandomStream)
     {
         let mut particle_system = ParticleSystem::new(particle_count, params);
         particle_system.transform.position = position;
-        particle_system.spawn_once();
+        particle_system.spawn_once(rng);
         self.pool.push(particle_system);
     }
 
-    pub fn spawn_constant_system_at_position(&mut self, position: Vec2, particle_count: usize, params: ParticleParams)
+    pub fn spawn_constant_system_at_position(&mut self, position: Vec2, particle_count: usize, params: ParticleParams, rng: &mut RandomStream)
     {
         let mut particle_system = ParticleSystem::new(particle_count, params);
         particle_system.transform.position = position;
-        particle_system.spawn_constant();
+        particle_system.spawn_constant(rng);
         self.pool.push(particle_system);
     }
     pub fn clear(&mut self) { self.pool.clear();}
@@ -78,26 +77,26 @@ impl ParticleSystem {
         self.params = params;
     }
 
-    pub fn spawn_constant(&mut self)
+    pub fn spawn_constant(&mut self, rng: &mut RandomStream)
     {
         if self.spawn_timer > 0.0
         {
-            self.spawn_timer -= self.params.spawn_rate * 2.0 * get_frame_time();
+            self.spawn_timer -= self.params.spawn_rate * 2.0 * get_tick_time();
             return;
         }
         self.spawn_timer = 1.0;
         for _i in 0..self.params.spawn_count 
         {
-            self.params.randomize();
+            self.params.randomize(rng);
             self.pool.spawn_particle(self.params,self.transform);
         }
     }
-    pub fn spawn_once(&mut self)
+    pub fn spawn_once(&mut self, rng: &mut RandomStream)
     {
         
         for _i in 0..self.params.spawn_count 
         {
-            self.params.randomize();
+            self.params.randomize(rng);
             self.pool.spawn_particle(self.params,self.transform);
         }
     }
@@ -190,24 +189,24 @@ impl ParticleParams {
     {
         self.position_random_range = vec4(range_x.x,range_x.y,range_y.x,range_y.y );
     }
-    pub fn randomize(&mut self)
+    pub fn randomize(&mut self, rng: &mut RandomStream)
     {
-        let value = vec2(RandomRange::gen_range(self.position_random_range.x, self.position_random_range.y), RandomRange::gen_range(self.position_random_range.z, self.position_random_range.w));
+        let value = vec2(rng.range_f32(self.position_random_range.x, self.position_random_range.y), rng.range_f32(self.position_random_range.z, self.position_random_range.w));
         self.position_random = vec2(value.x, value.y);
     }
 
-    pub fn randomize_velocity(&mut self, range_x: Vec2, range_y: Vec2)
+    pub fn randomize_velocity(&mut self, range_x: Vec2, range_y: Vec2, rng: &mut RandomStream)
     {
-        let value = vec2(RandomRange::gen_range(range_x.x, range_x.y), RandomRange::gen_range(range_y.x, range_y.y));
+        let value = vec2(rng.range_f32(range_x.x, range_x.y), rng.range_f32(range_y.x, range_y.y));
         self.velocity += vec2(value.x, value.y);
     }
 
-    pub fn randomize_color(&mut self, color_1: Color, color_2: Color )
+    pub fn randomize_color(&mut self, color_1: Color, color_2: Color, rng: &mut RandomStream )
     {
         let color = Color::new( 
-            RandomRange::gen_range(color_1.r, color_2.r),
-            RandomRange::gen_range(color_1.g, color_2.g),
-            RandomRange::gen_range(color_1.b, color_2.b),
+            rng.range_f32(color_1.r, color_2.r),
+            rng.range_f32(color_1.g, color_2.g),
+            rng.range_f32(color_1.b, color_2.b),
             1.0
         );
         self.color_begin = color;
@@ -394,7 +393,7 @@ impl Particle {
         if self.lifetime > 0.01
         {
             
-            self.lifetime -= 1.0 * self.params.speed_begin * get_frame_time();
+            self.lifetime -= 1.0 * self.params.speed_begin * get_tick_time();
             self.alpha = 1.0 - (( self.lifetime / self.params.lifetime)  );
 
             if self.params.use_velocity
@@ -1,5 +1,3 @@
-use macroquad::audio::{play_sound, PlaySoundParams};
-
 use super::*;
 
 pub struct Player
@@ -13,8 +11,6 @@ pub struct Player
     ship_angle: f32,
 
     sfx_move: SoundData,
-    sfx_shoot: SoundData,
-    sfx_on_hit: SoundData,
 }
 impl Player
 {
@@ -24,6 +20,10 @@ impl Player
         player_weapon.init(world);
         
         let mut entity = Entity::new("Player", "Player", world);
+        entity.set_collision(
+            CollisionLayer::PLAYER, 
+            CollisionLayer::ENEMY | CollisionLayer::ENEMY_PROJECTILE | CollisionLayer::HAZARD | CollisionLayer::TRIGGER
+        );
 
         entity.entity_params = settings.clone();
 
@@ -40,23 +40,16 @@ impl Player
 
             ship_angle: 0.0,
 
-            sfx_move:  world.assets.get_asset_by_name("fire_1".to_string()).unwrap().get_sound_data(),
-            sfx_shoot: world.assets.get_asset_by_name("fire_1".to_string()).unwrap().get_sound_data(),
-            sfx_on_hit: world.assets.get_asset_by_name("hurt_sound_1".to_string()).unwrap().get_sound_data(),
+            sfx_move:  world.assets.get_asset("fire_1").get_sound_data(),
 
         }
     }  
     pub fn shoot(&mut self, misslepool: &mut MisslePool, world: &mut World)
     {
-        if is_key_down(KeyCode::Space) || is_mouse_button_down(MouseButton::Left)
+        if world.input.is_down(InputAction::Fire)
         {
             self.weapon.set_stats( self.entity.entity_params.damage, self.entity.entity_params.firerate, self.entity.entity_params.firespeed);
-            if self.weapon.shoot( misslepool, world)
-            {
-                let mut params = PlaySoundParams::default();
-                params.volume = 0.15;
-                play_sound( self.sfx_shoot.sound.unwrap(), params)
-            }
+            self.weapon.shoot( misslepool, world);
         }
     }
     pub fn update_ship_visuals(&mut self, world: &mut World)
@@ -68,26 +61,26 @@ impl Player
             self.entity.transform.rect.y + (self.entity.transform.get_fullsize().y * 0.4), 
         );
 
-        if is_key_down(KeyCode::S) {
+        if world.input.is_down(InputAction::MoveDown) {
             spawn_position = vec2( self.entity.transform.rect.x + (draw_scale.x * 0.2), self.entity.transform.rect.y - (draw_scale.y * 0.2));
             if self.ship_angle < 25.0
             {
-                self.ship_angle += 350.0 * get_frame_time();
+                self.ship_angle += 350.0 * get_tick_time();
             }
-        } else if is_key_down(KeyCode::W) {
+        } else if world.input.is_down(InputAction::MoveUp) {
             spawn_position = vec2( self.entity.transform.rect.x + (draw_scale.x * 0.2),self.entity.transform.rect.y + (draw_scale.y * 0.8));
             if self.ship_angle > -25.0
             {
-                self.ship_angle -= 350.0 * get_frame_time();
+                self.ship_angle -= 350.0 * get_tick_time();
             }
         }else {
             if self.ship_angle < 0.0
             {
-                self.ship_angle += 350.0 * get_frame_time();
+                self.ship_angle += 350.0 * get_tick_time();
             }
             if self.ship_angle > 0.0
             {
-                self.ship_angle -= 350.0 * get_frame_time();
+                self.ship_angle -= 350.0 * get_tick_time();
             }
         }
 
@@ -96,7 +89,8 @@ impl Player
         world.particlesystem_pool.spawn_system_at_position(
             self.entity.transform.position, 
             2, 
-            thruster_settings(spawn_position,vec2(-5.0, 0.0), color_u8!(255, 0, 255, 0), vec2(-3.0, 3.0))
+            thruster_settings(spawn_position,vec2(-5.0, 0.0), color_u8!(255, 0, 255, 0), vec2(-3.0, 3.0), &mut world.random.cosmetic),
+            &mut world.random.cosmetic
         );
         
 
@@ -119,15 +113,16 @@ impl GameObject for Player
             self.entity.transform.set_scale( 2.0);
         }
         self.entity.transform.set_position( vec2( self.entity.transform.position.x + world.level_offset * 0.0,self.entity.transform.position.y + GAME_SIZE_Y * 0.5 ));
+        self.entity.transform.store_previous();
         self.entity.set_rect_color(WHITE);
     }
     fn update(&mut self, world: &mut World) {
         
-
+        self.entity.transform.store_previous();
         self.entity.hit_cooldown();
         if self.reached_end {
             world.level_completed = true;
-            self.entity.transform.set_position( vec2(self.entity.transform.position.x + LEVEL_SPEED * get_frame_time(), self.entity.transform.position.y));
+            self.entity.transform.set_position( vec2(self.entity.transform.position.x + world.scroll_speed * get_tick_time(), self.entity.transform.position.y));
             self.update_ship_visuals(world);
             // WEAPON
             self.weapon.set_parent(Some(self.entity.clone()));
@@ -135,59 +130,63 @@ impl GameObject for Player
             return;
         }
         
+        // Crushers can slide into the Ship -> Walls it already overlaps don't block, so it can get out
+        let mut blocking_walls = world.get_active_level().get_blocking_walls(world.level_offset);
+        blocking_walls.retain(|wall| wall.intersect(self.entity.transform.rect).is_none_or(|overlap| overlap.w <= 0.0 || overlap.h <= 0.0));
+
         // MOVEMENT
-        if is_key_down(KeyCode::W)
+        if world.input.is_down(InputAction::MoveUp)
         {
             let mut updated_transform = self.entity.transform;
-            let new_position = self.entity.transform.position - (vec2(0.0, self.entity.entity_params.speed) * get_frame_time());
+            let new_position = self.entity.transform.position - (vec2(0.0, self.entity.entity_params.speed) * get_tick_time());
             updated_transform.set_position(new_position);
 
-            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, world.get_active_level().get_blocking_walls(world.level_offset))
+            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, blocking_walls.clone())
             {
                 self.entity.transform.set_position(updated_transform.position);
             }
         }
-        if is_key_down(KeyCode::S)
+        if world.input.is_down(InputAction::MoveDown)
         {
             let mut updated_transform = self.entity.transform;
-            let new_position = self.entity.transform.position + (vec2(0.0, self.entity.entity_params.speed) * get_frame_time());
+            let new_position = self.entity.transform.position + (vec2(0.0, self.entity.entity_params.speed) * get_tick_time());
             updated_transform.set_position(new_position);
 
-            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, world.get_active_level().get_blocking_walls(world.level_offset))
+            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, blocking_walls.clone())
             {
                 self.entity.transform.set_position(updated_transform.position);
             }
         }
 
-        if is_key_down(KeyCode::A)
+        if world.input.is_down(InputAction::MoveLeft)
         {
             let mut updated_transform = self.entity.transform;
-            let new_position = self.entity.transform.position - vec2(self.entity.entity_params.speed, 0.0) * get_frame_time();
+            let new_position = self.entity.transform.position - vec2(self.entity.entity_params.speed, 0.0) * get_tick_time();
             updated_transform.set_position(new_position);
 
-            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, world.get_active_level().get_blocking_walls(world.level_offset))
+            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, blocking_walls.clone())
             {
                 self.entity.transform.set_position(updated_transform.position);
             }
         }
-        if is_key_down(KeyCode::D)
+        if world.input.is_down(InputAction::MoveRight)
         {
             let mut updated_transform = self.entity.transform;
-            let new_position = self.entity.transform.position + vec2(self.entity.entity_params.speed, 0.0) * get_frame_time();
+            let new_position = self.entity.transform.position + vec2(self.entity.entity_params.speed, 0.0) * get_tick_time();
             updated_transform.set_position(new_position);
 
-            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, world.get_active_level().get_blocking_walls(world.level_offset))
+            if !resolve_windowborder(updated_transform.rect, world.level_offset) && !resolve_levelwalls(updated_transform.rect, blocking_walls.clone())
             {
                 self.entity.transform.set_position(updated_transform.position);
             }
         }
 
         let mut updated_transform = self.entity.transform;
-        let new_position = self.entity.transform.position + (vec2(self.entity.entity_params.speed * 3.0, 0.0) * get_frame_time());
+        let new_position = self.entity.transform.position + (vec2(self.entity.entity_params.speed * 3.0 * world.get_scroll_factor(), 0.0) * get_tick_time());
         updated_transform.set_position(new_position);
-        if !resolve_levelwalls(updated_transform.rect, world.get_active_level().get_blocking_walls(world.level_offset))
+        if !resolve_levelwalls(updated_transform.rect, blocking_walls.clone())
         {
-            self.entity.transform.set_position( vec2(self.entity.transform.position.x + LEVEL_SPEED * get_frame_time(), self.entity.transform.position.y));
+            self.entity.transform.set_position( vec2(self.entity.transform.position.x + world.scroll_speed * get_tick_time(), self.entity.transform.position.y));
         }
 
         if resolve_deathzone(updated_transform.rect, world.level_offset)
@@ -205,16 +204,20 @@ impl GameObject for Player
         world.set_entity(&mut self.entity);
     }
     fn late_update(&mut self, world: &mut World) {
-        for entity in world.get_actives().iter_mut()
-        {
-            self.on_collision( entity);
+        let reached_end = self.reached_end;
+        let health = self.entity.entity_params.health;
+        self.resolve_collisions(world);
 
+        world.events.publish_damage(&self.entity, health, EventSource::Player);
+        if self.reached_end && !reached_end
+        {
+            world.events.publish(GameEvent::LevelEndReached);
         }
         self.weapon.late_update(world);
 
-        if is_key_down(KeyCode::W) {
+        if world.input.is_down(InputAction::MoveUp) {
             //self.sprite.animation_controller.get_statemachine_mut().SetState(2);
-        } else if is_key_down(KeyCode::S) {
+        } else if world.input.is_down(InputAction::MoveDown) {
             //self.sprite.animation_controller.get_statemachine_mut().SetState(1);
         } else {
             //self.sprite.animation_controller.get_statemachine_mut().SetState(0);
@@ -228,9 +231,10 @@ impl GameObject for Player
         {
             draw_rectangle_lines(self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.transform.rect.w, self.entity.transform.rect.h, 2.0,COLLISION_COLOR);
         }
+        let render_rect = self.entity.transform.get_render_rect();
         if self.sprite.texture_data == Texture2D::empty()
         {
-            draw_rectangle(self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.transform.rect.w, self.entity.transform.rect.h, self.entity.get_rect_color());
+            draw_rectangle(render_rect.x, render_rect.y, render_rect.w, render_rect.h, self.entity.get_rect_color());
         }else
         {
             
@@ -245,8 +249,8 @@ impl GameObject for Player
 
             draw_texture_ex(
                 self.sprite.texture_data, 
-                self.entity.transform.rect.x , 
-                self.entity.transform.rect.y , 
+                render_rect.x , 
+                render_rect.y , 
                 self.entity.get_rect_color(), 
                 params);
         }
@@ -255,34 +259,27 @@ impl GameObject for Player
 }
 impl Collision for Player
 {
+    fn get_collision_entity(&self) -> &Entity { &self.entity }
     fn on_collision(&mut self, entity: &mut Entity) {
         if !resolve_intersection(self.entity.transform.rect,entity.transform.rect)
         {
             //self.hit_wall = false;
             return;
         }
-        let mut params = PlaySoundParams::default();
-        params.volume = 0.15;
-
-        match entity.tag.as_str()
+        // Enemies, Hazards & Trap Walls stay in Contact over many Ticks -> only hurt again after the Hit Feedback ran out
+        let contact = CollisionLayer::ENEMY | CollisionLayer::HAZARD;
+        if entity.collision_layer.intersects(contact) && self.entity.hit_feedback_timer <= 0.0
         {
-            "Enemy" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            "Enemy Weapon Missle" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            "End" => {
-                self.reached_end = true;
-            }
-            "TrapWall" => {
-                self.entity.hit(&entity.entity_params);
-                play_sound(self.sfx_on_hit.sound.unwrap(), params);
-            }
-            
-            _ => {}
+            self.entity.hit(&entity.entity_params);
+        }
+        // Missles are used up on Hit -> every one of them counts
+        if entity.collision_layer.intersects(CollisionLayer::ENEMY_PROJECTILE)
+        {
+            self.entity.hit(&entity.entity_params);
+        }
+        if entity.collision_layer.intersects(CollisionLayer::TRIGGER)
+        {
+            self.reached_end = true;
         }
     }
 } 
\ No newline at end of file
@@ -0,0 +1,145 @@
+// Salts -> every Stream gets its own Sequence from the same Run Seed
+const LEVEL_STREAM: u64 = 0x4C45_5645_4C00_0001;
+const GAMEPLAY_STREAM: u64 = 0x4741_4D45_0000_0002;
+const COSMETIC_STREAM: u64 = 0x4655_5846_5800_0003;
+
+// Seedable Generator (SplitMix64) -> same Seed, same Sequence on every Machine
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct RandomStream
+{
+    state: u64,
+}
+impl RandomStream
+{
+    pub fn new(seed: u64) -> Self
+    {
+        Self { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64
+    {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    // 0.0 - 1.0 (exclusive)
+    pub fn next_f32(&mut self) -> f32
+    {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+    // Same Behaviour as macroquad's gen_range -> low can be bigger than high
+    pub fn range_f32(&mut self, low: f32, high: f32) -> f32
+    {
+        low + (high - low) * self.next_f32()
+    }
+    // low (inclusive) - high (exclusive)
+    pub fn range_i32(&mut self, low: i32, high: i32) -> i32
+    {
+        if high <= low
+        {
+            return low;
+        }
+        low + (self.next_u64() % (high - low) as u64) as i32
+    }
+}
+
+// All Randomness of a Run -> Cosmetic Effects can never shift the Gameplay Sequence
+#[derive(Clone, Copy, Debug)]
+pub struct WorldRandom
+{
+    seed: u64,
+    // Tile Rotations and other Level Visuals
+    pub level: RandomStream,
+    // Everything that changes the Outcome of a Run
+    pub gameplay: RandomStream,
+    // Particles and Effects
+    pub cosmetic: RandomStream,
+}
+impl WorldRandom
+{
+    pub fn new(seed: u64) -> Self
+    {
+        let mut random = Self {
+            seed,
+            level: RandomStream::new(0),
+            gameplay: RandomStream::new(0),
+            cosmetic: RandomStream::new(0),
+        };
+        random.begin_stage(0);
+        random
+    }
+    // Every Stage starts from a fresh Sequence -> a Stage plays the same no matter how the Run got there
+    pub fn begin_stage(&mut self, stage: usize)
+    {
+        let stage_seed = RandomStream::new(self.seed ^ (stage as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)).next_u64();
+        self.level = RandomStream::new(stage_seed ^ LEVEL_STREAM);
+        self.gameplay = RandomStream::new(stage_seed ^ GAMEPLAY_STREAM);
+        self.cosmetic = RandomStream::new(stage_seed ^ COSMETIC_STREAM);
+    }
+    pub fn get_seed(&self) -> u64 { self.seed }
+}
+
+// New Seed for a fresh Run
+pub fn seed_from_time() -> u64
+{
+    RandomStream::new((macroquad::miniquad::date::now() * 1000.0) as u64).next_u64()
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn sequence(stream: &mut RandomStream) -> Vec<u64>
+    {
+        (0..32).map(|_| stream.next_u64()).collect()
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_sequence()
+    {
+        assert_eq!(sequence(&mut RandomStream::new(42)), sequence(&mut RandomStream::new(42)));
+        assert_ne!(sequence(&mut RandomStream::new(42)), sequence(&mut RandomStream::new(43)));
+
+        let mut a = WorldRandom::new(0xBEEF);
+        let mut b = WorldRandom::new(0xBEEF);
+        assert_eq!(sequence(&mut a.gameplay), sequence(&mut b.gameplay));
+        assert_eq!(sequence(&mut a.level), sequence(&mut b.level));
+
+        let mut range = RandomStream::new(7);
+        for _ in 0..1000
+        {
+            let value = range.range_i32(-3, 5);
+            assert!((-3..5).contains(&value));
+            assert!((0.0..1.0).contains(&range.next_f32()));
+        }
+    }
+
+    #[test]
+    fn begin_stage_reseeds_every_stream_on_its_own()
+    {
+        let mut random = WorldRandom::new(0xBEEF);
+        random.begin_stage(3);
+        let stage_three = random;
+        assert_ne!(stage_three.level, stage_three.gameplay);
+        assert_ne!(stage_three.gameplay, stage_three.cosmetic);
+
+        // Cosmetic Rolls never shift the Gameplay Sequence
+        let mut effects = stage_three;
+        for _ in 0..100 { effects.cosmetic.next_u64(); }
+        assert_eq!(effects.gameplay, stage_three.gameplay);
+        assert_eq!(effects.level, stage_three.level);
+
+        // Stage 3 plays the same no matter how much of Stage 2 was used up
+        let mut run = WorldRandom::new(0xBEEF);
+        run.begin_stage(2);
+        let stage_two = run.gameplay;
+        for _ in 0..500 { run.gameplay.next_u64(); run.level.next_u64(); }
+        run.begin_stage(3);
+        assert_ne!(run.gameplay, stage_two);
+        assert_eq!(run.gameplay, stage_three.gameplay);
+        assert_eq!(run.level, stage_three.level);
+        assert_eq!(run.cosmetic, stage_three.cosmetic);
+    }
+}
@@ -63,4 +63,110 @@ pub fn inside_visible_area(rect: Rect, viewspace_position: Vec2, radius: f32) ->
         return true;
     }
     return false;
-}
\ No newline at end of file
+}
+
+// ---------------------------------
+// Render Backend -> owns the Camera and the Game Render Target
+
+pub trait RenderBackend
+{
+    // false -> Game skips every Draw Call
+    fn is_drawing(&self) -> bool;
+    fn begin_frame(&mut self);
+    // Camera follows the Level Offset
+    fn set_view(&mut self, level_offset: f32);
+    fn end_frame(&mut self);
+}
+
+pub struct MacroquadRenderer
+{
+    camera: Camera2D,
+    render_target: RenderTarget,
+}
+impl Default for MacroquadRenderer
+{
+    fn default() -> Self { Self::new() }
+}
+impl MacroquadRenderer
+{
+    pub fn new() -> Self
+    {
+        // Create Render Target for Game
+        let game_render_target = render_target(GAME_SIZE_X as u32, GAME_SIZE_Y as u32);
+        game_render_target.texture.set_filter(FilterMode::Linear);
+
+        // Create & Set Camera
+        let camera_rect = Rect::new(0.0,0.0, GAME_SIZE_X , GAME_SIZE_Y );
+        let mut camera =Camera2D::from_display_rect(camera_rect);
+        camera.render_target = Some(game_render_target);
+        set_camera(&camera);
+
+        Self { camera, render_target: game_render_target }
+    }
+}
+impl RenderBackend for MacroquadRenderer
+{
+    fn is_drawing(&self) -> bool { true }
+    fn begin_frame(&mut self) {
+        // Setup Camera
+        let camera_rect = Rect::new(0.0,0.0, GAME_SIZE_X , GAME_SIZE_Y );
+        let mut camera =Camera2D::from_display_rect(camera_rect);
+        camera.render_target = Some(self.render_target);
+        set_camera(&camera);
+
+        //Draw & Clear Background
+        clear_background(BLACK);
+        draw_rectangle( 0.0, 0.0,  GAME_SIZE_X ,  GAME_SIZE_Y , BLACK);
+    }
+    fn set_view(&mut self, level_offset: f32) {
+        let level_position = vec2( GAME_SIZE_X * 0.5 + level_offset , GAME_SIZE_Y * 0.5);
+        self.camera.target = level_position;
+        set_camera(&self.camera);
+    }
+    fn end_frame(&mut self) {
+        // Set Default Camera
+        set_default_camera();
+        // calculate game view size based on window size
+        let game_diff_w = GAME_SIZE_X / GAME_SIZE_X as f32;
+        let game_diff_h = GAME_SIZE_Y / GAME_SIZE_Y as f32;
+        let aspect_diff = game_diff_w.min(game_diff_h);
+        
+        let scaled_game_size_w = screen_width() as f32 * aspect_diff;
+        let scaled_game_size_h = screen_height() as f32 * aspect_diff;
+        
+        let width_padding = (screen_width() - scaled_game_size_w) * 0.5f32;
+        let height_padding = (screen_height() - scaled_game_size_h) * 0.5f32;
+        
+        // Draw Game on Screen
+        clear_background(BLACK);
+        draw_texture_ex(
+            self.render_target.texture,
+            width_padding,
+            height_padding,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(Vec2::new(scaled_game_size_w, scaled_game_size_h)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
+
+// Headless -> no Window, no Draw Calls
+pub struct NullRenderer {}
+impl Default for NullRenderer
+{
+    fn default() -> Self { Self::new() }
+}
+impl NullRenderer
+{
+    pub fn new() -> Self { Self {} }
+}
+impl RenderBackend for NullRenderer
+{
+    fn is_drawing(&self) -> bool { false }
+    fn begin_frame(&mut self) {}
+    fn set_view(&mut self, _level_offset: f32) {}
+    fn end_frame(&mut self) {}
+}
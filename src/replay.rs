@@ -0,0 +1,264 @@
+use std::io::{Error, ErrorKind};
+use super::*;
+
+pub const REPLAY_VERSION: u8 = 1;
+pub const LAST_REPLAY_FILE: &str = "last_replay.vnr";
+const REPLAY_MAGIC: &[u8; 4] = b"VNRP";
+
+// Everything a Stage needs to start exactly like the recorded one
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ReplayHeader
+{
+    pub seed: u64,
+    pub stage: usize,
+    pub difficulty: i32,
+    pub health: f32,
+}
+
+// Per Tick Input of one Stage -> Frames are stored run-length encoded
+#[derive(Clone, PartialEq, Debug)]
+pub struct Replay
+{
+    pub header: ReplayHeader,
+    pub frames: Vec<InputFrame>,
+}
+impl Replay
+{
+    pub fn new(header: ReplayHeader) -> Self
+    {
+        Self { header, frames: Vec::new() }
+    }
+    pub fn record(&mut self, frame: InputFrame)
+    {
+        self.frames.push(frame);
+    }
+    pub fn len(&self) -> usize { self.frames.len() }
+    pub fn is_empty(&self) -> bool { self.frames.is_empty() }
+
+    // ---------------------------------
+    // File Format: Magic, Version, Header, Run Count, Runs of (Count u16, Buttons u8, Aim i16 i16)
+
+    pub fn to_bytes(&self) -> Vec<u8>
+    {
+        let mut bytes: Vec<u8> = Vec::new();
+        bytes.extend_from_slice(REPLAY_MAGIC);
+        bytes.push(REPLAY_VERSION);
+        bytes.extend_from_slice(&self.header.seed.to_le_bytes());
+        bytes.extend_from_slice(&(self.header.stage as u32).to_le_bytes());
+        bytes.extend_from_slice(&self.header.difficulty.to_le_bytes());
+        bytes.extend_from_slice(&self.header.health.to_le_bytes());
+
+        let mut runs: Vec<(u16, [u8; 5])> = Vec::new();
+        for frame in self.frames.iter()
+        {
+            let encoded = Replay::encode_frame(frame);
+            match runs.last_mut()
+            {
+                Some(run) if run.1 == encoded && run.0 < u16::MAX => { run.0 += 1; }
+                _ => { runs.push((1, encoded)); }
+            }
+        }
+        bytes.extend_from_slice(&(runs.len() as u32).to_le_bytes());
+        for run in runs.iter()
+        {
+            bytes.extend_from_slice(&run.0.to_le_bytes());
+            bytes.extend_from_slice(&run.1);
+        }
+        bytes
+    }
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error>
+    {
+        let mut reader = ByteReader { bytes, cursor: 0 };
+        if reader.take(4)? != REPLAY_MAGIC
+        {
+            return Err(Error::new(ErrorKind::InvalidData, "Not a Replay File"));
+        }
+        let version = reader.take(1)?[0];
+        if version != REPLAY_VERSION
+        {
+            return Err(Error::new(ErrorKind::InvalidData, format!("Unsupported Replay Version {}", version)));
+        }
+        let header = ReplayHeader {
+            seed: u64::from_le_bytes(reader.take_array()?),
+            stage: u32::from_le_bytes(reader.take_array()?) as usize,
+            difficulty: i32::from_le_bytes(reader.take_array()?),
+            health: f32::from_le_bytes(reader.take_array()?),
+        };
+        let mut replay = Replay::new(header);
+        let run_count = u32::from_le_bytes(reader.take_array()?);
+        for _i in 0..run_count
+        {
+            let count = u16::from_le_bytes(reader.take_array()?);
+            let frame = Replay::decode_frame(&reader.take_array()?);
+            for _j in 0..count
+            {
+                replay.frames.push(frame);
+            }
+        }
+        Ok(replay)
+    }
+    pub fn save(&self, path: &str) -> Result<(), Error>
+    {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            std::fs::write(path, self.to_bytes())?;
+        }
+        Ok(())
+    }
+    pub fn load(path: &str) -> Result<Self, Error>
+    {
+        Replay::from_bytes(&std::fs::read(path)?)
+    }
+
+    // Menu Input is not part of the Simulation -> only held Actions and Aim get stored, Gameplay Actions sit in the lowest Byte
+    fn encode_frame(frame: &InputFrame) -> [u8; 5]
+    {
+        let buttons = frame.get_down_bits() as u8;
+        let aim_x = (frame.aim.x as i16).to_le_bytes();
+        let aim_y = (frame.aim.y as i16).to_le_bytes();
+        [buttons, aim_x[0], aim_x[1], aim_y[0], aim_y[1]]
+    }
+    fn decode_frame(data: &[u8; 5]) -> InputFrame
+    {
+        let mut frame = InputFrame::new();
+        frame.set_down_bits(data[0] as u32);
+        frame.aim = vec2(
+            i16::from_le_bytes([data[1], data[2]]) as f32,
+            i16::from_le_bytes([data[3], data[4]]) as f32,
+        );
+        frame
+    }
+}
+
+struct ByteReader<'a>
+{
+    bytes: &'a [u8],
+    cursor: usize,
+}
+impl<'a> ByteReader<'a>
+{
+    fn take(&mut self, count: usize) -> Result<&'a [u8], Error>
+    {
+        if self.cursor + count > self.bytes.len()
+        {
+            return Err(Error::new(ErrorKind::UnexpectedEof, "Replay File ends early"));
+        }
+        let slice = &self.bytes[self.cursor..self.cursor + count];
+        self.cursor += count;
+        Ok(slice)
+    }
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], Error>
+    {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+}
+
+// Hands the recorded Frames back one Tick at a Time
+pub struct ReplayPlayer
+{
+    replay: Replay,
+    cursor: usize,
+}
+impl ReplayPlayer
+{
+    pub fn new(replay: Replay) -> Self
+    {
+        Self { replay, cursor: 0 }
+    }
+    pub fn get_header(&self) -> ReplayHeader { self.replay.header }
+    pub fn is_finished(&self) -> bool { self.cursor >= self.replay.frames.len() }
+    pub fn next_frame(&mut self) -> Option<InputFrame>
+    {
+        if self.is_finished()
+        {
+            return None;
+        }
+        self.cursor += 1;
+        Some(self.replay.frames[self.cursor - 1])
+    }
+}
+impl InputBackend for ReplayPlayer
+{
+    fn poll(&mut self) -> InputFrame {
+        self.next_frame().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    // Fire held, Up & Down switching every 50 Ticks, Aim following
+    fn recorded_stage() -> Replay
+    {
+        let mut replay = Replay::new(ReplayHeader { seed: 0xBEEF, stage: 3, difficulty: 2, health: 75.0 });
+        let mut frame = InputFrame::new();
+        frame.set_down(InputAction::Fire, true);
+        for tick in 0..300
+        {
+            frame.set_down(InputAction::MoveUp, tick % 100 < 50);
+            frame.set_down(InputAction::MoveDown, tick % 100 >= 50);
+            frame.aim = vec2(640.0, (tick / 100) as f32 * 20.0 - 20.0);
+            replay.record(frame);
+        }
+        replay
+    }
+
+    #[test]
+    fn plays_back_every_recorded_tick()
+    {
+        let replay = recorded_stage();
+        let bytes = replay.to_bytes();
+        // Held Input collapses into Runs -> 6 Runs instead of 300 Frames
+        assert_eq!(bytes.len(), 29 + 6 * 7);
+
+        let mut player = ReplayPlayer::new(Replay::from_bytes(&bytes).unwrap());
+        assert_eq!(player.get_header(), replay.header);
+        for frame in replay.frames.iter()
+        {
+            assert!(!player.is_finished());
+            assert_eq!(player.poll(), *frame);
+        }
+        // Past the End -> nothing held anymore
+        assert!(player.is_finished());
+        let idle = player.poll();
+        assert!(InputAction::all().iter().all(|action| !idle.is_down(*action)));
+    }
+
+    #[test]
+    fn keeps_only_gameplay_input()
+    {
+        let mut frame = InputFrame::new();
+        frame.set_down(InputAction::Fire, true);
+        frame.set_down(InputAction::MoveLeft, true);
+        frame.set_down(InputAction::OpenEditor, true);
+        frame.set_down(InputAction::Restart, true);
+        frame.set_released(InputAction::Confirm, true);
+        frame.wheel = 3.0;
+        let mut replay = Replay::new(ReplayHeader { seed: 1, stage: 0, difficulty: 0, health: 100.0 });
+        replay.record(frame);
+
+        let played = ReplayPlayer::new(Replay::from_bytes(&replay.to_bytes()).unwrap()).poll();
+        assert!(played.is_down(InputAction::Fire));
+        assert!(played.is_down(InputAction::MoveLeft));
+        assert!(!played.is_down(InputAction::OpenEditor));
+        assert!(!played.is_down(InputAction::Restart));
+        assert!(!played.is_released(InputAction::Confirm));
+        assert_eq!(played.wheel, 0.0);
+        assert_eq!(played.aim, frame.aim);
+    }
+
+    #[test]
+    fn rejects_broken_files()
+    {
+        let bytes = recorded_stage().to_bytes();
+        assert!(Replay::from_bytes(b"NOPE").is_err());
+        assert!(Replay::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut wrong_version = bytes.clone();
+        wrong_version[4] = REPLAY_VERSION + 1;
+        assert!(Replay::from_bytes(&wrong_version).is_err());
+    }
+}
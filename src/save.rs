@@ -0,0 +1,224 @@
+use super::*;
+
+pub const PROFILE_VERSION: u32 = 1;
+// File Name on native, localStorage Key in the Browser
+pub const PROFILE_KEY: &str = "vustnexus_profile";
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ProfileSettings
+{
+    pub sfx_volume: f32,
+    // Game Over continues from the last Checkpoint instead of restarting the Campaign
+    pub continue_from_checkpoint: bool,
+}
+impl Default for ProfileSettings
+{
+    fn default() -> Self { Self::new() }
+}
+impl ProfileSettings
+{
+    pub fn new() -> Self
+    {
+        Self { sfx_volume: 1.0, continue_from_checkpoint: false }
+    }
+}
+
+// Everything that survives a Restart of the Game
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Profile
+{
+    pub high_score: i32,
+    pub last_score: i32,
+    // Highest Stage Index the Player has entered
+    pub best_stage: usize,
+    pub settings: ProfileSettings,
+}
+impl Default for Profile
+{
+    fn default() -> Self { Self::new() }
+}
+impl Profile
+{
+    pub fn new() -> Self
+    {
+        Self { high_score: 0, last_score: 0, best_stage: 0, settings: ProfileSettings::new() }
+    }
+
+    pub fn load() -> Self
+    {
+        match storage_read(PROFILE_KEY)
+        {
+            Some(text) => Profile::from_text(&text),
+            None => {
+                println!("No Profile found -> new Profile");
+                Profile::new()
+            }
+        }
+    }
+    pub fn save(&self)
+    {
+        match storage_write(PROFILE_KEY, &self.to_text())
+        {
+            Ok(()) => {}
+            Err(error) => { println!("Could not save Profile: {}", error); }
+        }
+    }
+
+    pub fn submit_score(&mut self, score: i32)
+    {
+        self.last_score = score;
+        if score > self.high_score
+        {
+            self.high_score = score;
+        }
+    }
+    // Only ever goes up -> returns true if it changed
+    pub fn submit_stage(&mut self, stage: usize) -> bool
+    {
+        if stage > self.best_stage
+        {
+            self.best_stage = stage;
+            return true;
+        }
+        false
+    }
+
+    // ---------------------------------
+    // Format -> "version = 1" first, then one "key = value" per Line
+    // Unknown Keys are ignored and missing Keys keep their Default -> Fields can be added without a new Version
+    // A Version Bump is only needed once a Key gets renamed or changes its Meaning -> convert the old Entries in from_text then
+    // Newer or unreadable Versions are never guessed at -> the Game starts with a new Profile instead
+
+    pub fn to_text(&self) -> String
+    {
+        let mut text = String::new();
+        text.push_str(format!("version = {}\n", PROFILE_VERSION).as_str());
+        text.push_str(format!("high_score = {}\n", self.high_score).as_str());
+        text.push_str(format!("last_score = {}\n", self.last_score).as_str());
+        text.push_str(format!("best_stage = {}\n", self.best_stage).as_str());
+        text.push_str(format!("sfx_volume = {}\n", self.settings.sfx_volume).as_str());
+        text.push_str(format!("continue_from_checkpoint = {}\n", self.settings.continue_from_checkpoint).as_str());
+        text
+    }
+    pub fn from_text(text: &str) -> Self
+    {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        for (line_number, line) in config_lines(text)
+        {
+            if let Ok((key, value)) = config_entry(line_number, line)
+            {
+                entries.push((key.to_string(), value.to_string()));
+            }
+        }
+
+        // No Version Key -> treated as the first Version
+        let version = match Profile::find(&entries, "version")
+        {
+            Some(value) => value.parse::<u32>().ok(),
+            None => Some(1),
+        };
+        // One Arm per known Version -> a Bump adds an Arm that converts the old Entries before reading them
+        match version
+        {
+            Some(PROFILE_VERSION) => Profile::read_entries(&entries),
+            Some(version) if version > PROFILE_VERSION => {
+                println!("Profile Version {} is newer than this Game ({}) -> new Profile", version, PROFILE_VERSION);
+                Profile::new()
+            }
+            _ => {
+                println!("Profile has an unknown Version -> new Profile");
+                Profile::new()
+            }
+        }
+    }
+    // Keys of the current Version
+    fn read_entries(entries: &[(String, String)]) -> Self
+    {
+        let mut profile = Profile::new();
+        if let Some(value) = Profile::find(entries, "high_score") { profile.high_score = value.parse().unwrap_or(0); }
+        if let Some(value) = Profile::find(entries, "last_score") { profile.last_score = value.parse().unwrap_or(0); }
+        if let Some(value) = Profile::find(entries, "best_stage") { profile.best_stage = value.parse().unwrap_or(0); }
+        if let Some(value) = Profile::find(entries, "sfx_volume") { profile.settings.sfx_volume = value.parse::<f32>().unwrap_or(1.0).clamp(0.0, 1.0); }
+        if let Some(value) = Profile::find(entries, "continue_from_checkpoint") { profile.settings.continue_from_checkpoint = value.parse().unwrap_or(false); }
+        profile
+    }
+
+    fn find<'a>(entries: &'a [(String, String)], key: &str) -> Option<&'a str>
+    {
+        entries.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+// ---------------------------------
+// Storage -> a File next to the Game on native, localStorage in the Browser (see index.html)
+
+#[cfg(not(target_arch = "wasm32"))]
+fn storage_read(key: &str) -> Option<String>
+{
+    std::fs::read_to_string(format!("{}.sav", key)).ok()
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn storage_write(key: &str, text: &str) -> Result<(), std::io::Error>
+{
+    std::fs::write(format!("{}.sav", key), text)
+}
+
+#[cfg(target_arch = "wasm32")]
+extern "C" {
+    fn vn_storage_length(key: *const u8, key_len: u32) -> i32;
+    fn vn_storage_read(key: *const u8, key_len: u32, buffer: *mut u8, buffer_len: u32);
+    fn vn_storage_write(key: *const u8, key_len: u32, value: *const u8, value_len: u32);
+}
+#[cfg(target_arch = "wasm32")]
+fn storage_read(key: &str) -> Option<String>
+{
+    // -1 -> Key does not exist
+    let length = unsafe { vn_storage_length(key.as_ptr(), key.len() as u32) };
+    if length < 0
+    {
+        return None;
+    }
+    let mut buffer = vec![0u8; length as usize];
+    unsafe { vn_storage_read(key.as_ptr(), key.len() as u32, buffer.as_mut_ptr(), buffer.len() as u32); }
+    String::from_utf8(buffer).ok()
+}
+#[cfg(target_arch = "wasm32")]
+fn storage_write(key: &str, text: &str) -> Result<(), std::io::Error>
+{
+    unsafe { vn_storage_write(key.as_ptr(), key.len() as u32, text.as_ptr(), text.len() as u32); }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn round_trips_through_text()
+    {
+        let mut profile = Profile::new();
+        profile.submit_score(120);
+        profile.submit_stage(4);
+        profile.settings.sfx_volume = 0.5;
+        profile.settings.continue_from_checkpoint = true;
+        assert_eq!(Profile::from_text(&profile.to_text()), profile);
+    }
+    #[test]
+    fn keeps_defaults_for_missing_keys_and_newer_versions()
+    {
+        let profile = Profile::from_text("high_score = 50\nunknown = 1\n");
+        assert_eq!(profile.high_score, 50);
+        assert_eq!(profile.settings, ProfileSettings::new());
+        let newer = Profile::from_text(format!("version = {}\nhigh_score = 50\n", PROFILE_VERSION + 1).as_str());
+        assert_eq!(newer, Profile::new());
+    }
+    #[test]
+    fn reads_only_known_versions()
+    {
+        let current = Profile::from_text(format!("version = {}\nhigh_score = 50\nbest_stage = 3\n", PROFILE_VERSION).as_str());
+        assert_eq!((current.high_score, current.best_stage), (50, 3));
+        assert_eq!(Profile::from_text("version = 0\nhigh_score = 50\n"), Profile::new());
+        assert_eq!(Profile::from_text("version = two\nhigh_score = 50\n"), Profile::new());
+    }
+}
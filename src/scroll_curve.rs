@@ -0,0 +1,144 @@
+use super::*;
+
+// Scroll Speed (Pixel per Second) once the left View Edge reaches this Column
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ScrollKey
+{
+    pub column: f32,
+    pub speed: f32,
+}
+
+// Camera stops with its left Edge on this Column until the Lock is cleared
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScrollLock
+{
+    pub column: f32,
+    // Spawner Group to clear -> None clears every Spawner on the locked Screen
+    pub group: Option<String>,
+}
+impl ScrollLock
+{
+    pub fn get_offset(&self) -> f32
+    {
+        self.column * LEVEL_SCALE
+    }
+}
+
+// Speed Keys & Scroll Locks of one Stage -> both sorted by Column
+#[derive(Clone, PartialEq, Debug)]
+pub struct ScrollCurve
+{
+    pub keys: Vec<ScrollKey>,
+    pub locks: Vec<ScrollLock>,
+}
+impl Default for ScrollCurve
+{
+    fn default() -> Self { Self::new() }
+}
+impl ScrollCurve
+{
+    pub fn new() -> Self
+    {
+        Self { keys: Vec::new(), locks: Vec::new() }
+    }
+    pub fn add_key(&mut self, key: ScrollKey)
+    {
+        let index = self.keys.iter().position(|k| k.column > key.column).unwrap_or(self.keys.len());
+        self.keys.insert(index, key);
+    }
+    pub fn add_lock(&mut self, lock: ScrollLock)
+    {
+        let index = self.locks.iter().position(|l| l.column > lock.column).unwrap_or(self.locks.len());
+        self.locks.insert(index, lock);
+    }
+
+    // Stage Speed at Column 0 blends into the first Key, after the last Key its Speed holds -> in between both Keys blend
+    pub fn get_speed(&self, level_offset: f32, stage_speed: f32) -> f32
+    {
+        let column = level_offset / LEVEL_SCALE;
+        let next = self.keys.iter().position(|k| k.column > column);
+        match next
+        {
+            // Stages start half a Screen before Column 0 -> the Stage Speed holds until then
+            Some(0) => {
+                let first = self.keys[0];
+                let t = if first.column > 0.0 { (column / first.column).clamp(0.0, 1.0) } else { 0.0 };
+                stage_speed + (first.speed - stage_speed) * t
+            }
+            Some(index) => {
+                let (from, to) = (self.keys[index - 1], self.keys[index]);
+                let t = (column - from.column) / (to.column - from.column);
+                from.speed + (to.speed - from.speed) * t
+            }
+            None => match self.keys.last()
+            {
+                Some(key) => key.speed,
+                None => stage_speed,
+            },
+        }
+    }
+
+    // "column, speed" -> Stopping is done with a Scroll Lock
+    pub fn parse_key(value: &str) -> Result<ScrollKey, String>
+    {
+        match parse_vec2(value)
+        {
+            Some(key) if key.y > 0.0 => Ok(ScrollKey { column: key.x, speed: key.y }),
+            Some(_) => Err("scroll_key Speed has to be above 0 -> use scroll_lock to stop".to_string()),
+            None => Err("scroll_key needs column, speed".to_string()),
+        }
+    }
+    // "column" or "column, group"
+    pub fn parse_lock(value: &str) -> Result<ScrollLock, String>
+    {
+        let (column, group) = match value.split_once(',')
+        {
+            Some((column, group)) => (column.trim(), Some(group.trim().to_string())),
+            None => (value.trim(), None),
+        };
+        let column = column.parse::<f32>().map_err(|_| "scroll_lock Column is not a Number".to_string())?;
+        Ok(ScrollLock { column, group: group.filter(|g| !g.is_empty()) })
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn curve() -> ScrollCurve
+    {
+        let mut curve = ScrollCurve::new();
+        curve.add_key(ScrollCurve::parse_key("100, 500").unwrap());
+        curve.add_key(ScrollCurve::parse_key("50, 200").unwrap());
+        curve.add_lock(ScrollCurve::parse_lock("80, boss").unwrap());
+        curve.add_lock(ScrollCurve::parse_lock("20").unwrap());
+        curve
+    }
+
+    #[test]
+    fn blends_from_the_stage_speed_through_every_key()
+    {
+        let curve = curve();
+        let at = |column: f32| curve.get_speed(column * LEVEL_SCALE, 300.0);
+        assert_eq!(at(-10.0), 300.0);
+        assert_eq!(at(0.0), 300.0);
+        assert_eq!(at(25.0), 250.0);
+        assert_eq!(at(50.0), 200.0);
+        assert_eq!(at(75.0), 350.0);
+        assert_eq!(at(200.0), 500.0);
+        assert_eq!(ScrollCurve::new().get_speed(1000.0, 300.0), 300.0);
+    }
+    #[test]
+    fn sorts_and_parses_keys_and_locks()
+    {
+        let curve = curve();
+        assert_eq!(curve.keys.iter().map(|k| k.column).collect::<Vec<f32>>(), vec![50.0, 100.0]);
+        assert_eq!(curve.locks[0], ScrollLock { column: 20.0, group: None });
+        assert_eq!(curve.locks[1].group, Some("boss".to_string()));
+        assert_eq!(curve.locks[1].get_offset(), 80.0 * LEVEL_SCALE);
+        assert!(ScrollCurve::parse_key("10, 0").is_err());
+        assert!(ScrollCurve::parse_key("10").is_err());
+        assert!(ScrollCurve::parse_lock("far").is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use super::*;
+
+// One Group of Spawn Steps -> every Step spawns one Enemy per Formation Offset
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnWave
+{
+    // Enemy Type Ids -> cycled through Enemy by Enemy, so Waves can mix Types
+    pub enemies: Vec<usize>,
+    pub count: usize,
+    // Seconds before the first Step -> counted from the last Step of the Wave before
+    pub delay: f32,
+    // Seconds between Steps -> None uses the Difficulty Interval of the Spawner
+    pub interval: Option<f32>,
+    // Pixel Offsets from the Spawner Center
+    pub formation: Vec<Vec2>,
+}
+impl SpawnWave
+{
+    // What a Spawnmap Pixel describes -> count Enemies of one Type, one after another
+    pub fn single(enemy_type: usize, count: usize) -> Self
+    {
+        Self { enemies: vec![enemy_type], count, delay: 0.0, interval: None, formation: vec![vec2(0.0, 0.0)] }
+    }
+    pub fn get_enemy_type(&self, enemy_index: usize) -> usize
+    {
+        self.enemies[enemy_index % self.enemies.len()]
+    }
+}
+
+// Choreographed Spawner from a Stage "spawners" File -> replaces Count & Type of the Spawnmap
+#[derive(Clone, PartialEq, Debug)]
+pub struct SpawnerDefinition
+{
+    pub name: String,
+    // Structure Pixel of the Spawner -> Tiled Spawners can use the Property "definition" instead
+    pub cell: Option<Vec2>,
+    // Column the left View Edge has to reach before the first Wave -> None starts once the Spawner is in View
+    pub trigger: Option<f32>,
+    // Scroll Locks & Stage Triggers address Spawners by Group
+    pub group: Option<String>,
+    pub waves: Vec<SpawnWave>,
+}
+impl SpawnerDefinition
+{
+    pub fn new(name: &str) -> Self
+    {
+        Self { name: name.to_string(), cell: None, trigger: None, group: None, waves: Vec::new() }
+    }
+    pub fn matches(&self, cell: Vec2, properties: Option<&SpawnerProperties>) -> bool
+    {
+        if self.cell == Some(cell)
+        {
+            return true;
+        }
+        match properties.and_then(|p| find_property(&p.custom, "definition"))
+        {
+            Some(name) => name == self.name,
+            None => false,
+        }
+    }
+    pub fn get_trigger_offset(&self) -> Option<f32>
+    {
+        self.trigger.map(|column| column * LEVEL_SCALE)
+    }
+
+    // Format -> one "[spawner]" Block per Spawner (name, cell, trigger, group), followed by its "[wave]" Blocks  |  "#" starts a Comment
+    // Wave Keys -> enemies (Ids or Names), count, delay, interval, formation ("x, y; x, y; ...")
+    pub fn parse_all(text: &str) -> Result<Vec<SpawnerDefinition>, String>
+    {
+        let mut definitions: Vec<SpawnerDefinition> = Vec::new();
+        // false -> Keys belong to the Spawner, true -> to its last Wave
+        let mut in_wave = false;
+        for (line_number, line) in config_lines(text)
+        {
+            if line == "[spawner]"
+            {
+                let name = format!("Spawner {}", definitions.len() + 1);
+                definitions.push(SpawnerDefinition::new(&name));
+                in_wave = false;
+                continue;
+            }
+            let definition = match definitions.last_mut()
+            {
+                Some(definition) => definition,
+                None => { return Err(format!("Line {}: expected [spawner] first", line_number)); }
+            };
+            if line == "[wave]"
+            {
+                definition.waves.push(SpawnWave::single(0, 1));
+                in_wave = true;
+                continue;
+            }
+            let (key, value) = config_entry(line_number, line)?;
+
+            if !in_wave
+            {
+                match key
+                {
+                    "name" => { definition.name = value.to_string(); }
+                    "group" => { definition.group = if value.is_empty() { None } else { Some(value.to_string()) }; }
+                    "cell" => {
+                        let cell = parse_vec2(value).ok_or(format!("Line {}: cell needs x, y", line_number))?;
+                        definition.cell = Some(cell);
+                    }
+                    "trigger" => {
+                        let column = value.parse::<f32>().map_err(|_| format!("Line {}: trigger is not a Column", line_number))?;
+                        definition.trigger = Some(column);
+                    }
+                    _ => { println!("Spawner Line {}: unknown Key {}", line_number, key); }
+                }
+                continue;
+            }
+            let wave = definition.waves.last_mut().unwrap();
+            match key
+            {
+                "enemies" => {
+                    wave.enemies = value.split(',')
+                        .map(|name| enemy_type_id(name.trim()))
+                        .collect::<Option<Vec<usize>>>()
+                        .ok_or(format!("Line {}: unknown Enemy Type in {}", line_number, value))?;
+                }
+                "count" => {
+                    wave.count = value.parse::<usize>().ok().filter(|count| *count > 0).ok_or(format!("Line {}: count has to be a Number above 0", line_number))?;
+                }
+                "delay" => {
+                    wave.delay = value.parse::<f32>().map_err(|_| format!("Line {}: delay is not a Number", line_number))?;
+                }
+                "interval" => {
+                    let interval = value.parse::<f32>().map_err(|_| format!("Line {}: interval is not a Number", line_number))?;
+                    wave.interval = Some(interval.max(0.0));
+                }
+                "formation" => {
+                    wave.formation = value.split(';')
+                        .map(parse_vec2)
+                        .collect::<Option<Vec<Vec2>>>()
+                        .ok_or(format!("Line {}: formation needs x, y Offsets split by ';'", line_number))?;
+                }
+                _ => { println!("Spawner Line {}: unknown Key {}", line_number, key); }
+            }
+        }
+
+        for definition in definitions.iter()
+        {
+            if definition.waves.is_empty()
+            {
+                return Err(format!("{} has no [wave]", definition.name));
+            }
+        }
+        Ok(definitions)
+    }
+}
+
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_spawners_and_waves()
+    {
+        let text = "[spawner]\nname = Pincer\ncell = 40, 3\ntrigger = 30\ngroup = pincer\n[wave]\nenemies = gunner, 2\ncount = 4\ninterval = 0.5\nformation = 0, 0; 0, 32\n[wave]\ndelay = 2\n";
+        let definitions = SpawnerDefinition::parse_all(text).unwrap();
+        assert_eq!(definitions.len(), 1);
+        let definition = &definitions[0];
+        assert_eq!(definition.cell, Some(vec2(40.0, 3.0)));
+        assert_eq!(definition.get_trigger_offset(), Some(30.0 * LEVEL_SCALE));
+        assert_eq!(definition.group, Some("pincer".to_string()));
+        assert_eq!(definition.waves.len(), 2);
+        assert_eq!(definition.waves[0].enemies, vec![1, 2]);
+        assert_eq!(definition.waves[0].get_enemy_type(3), 2);
+        assert_eq!(definition.waves[0].formation, vec![vec2(0.0, 0.0), vec2(0.0, 32.0)]);
+        assert_eq!(definition.waves[1].delay, 2.0);
+    }
+    #[test]
+    fn rejects_broken_spawners()
+    {
+        assert!(SpawnerDefinition::parse_all("[wave]\n").is_err());
+        assert!(SpawnerDefinition::parse_all("[spawner]\nname = No Waves\n").is_err());
+        assert!(SpawnerDefinition::parse_all("[spawner]\n[wave]\nenemies = 99\n").is_err());
+        assert!(SpawnerDefinition::parse_all("[spawner]\n[wave]\ncount = 0\n").is_err());
+        assert!(SpawnerDefinition::parse_all("[spawner]\n[wave]\nformation = 0; 1, 1\n").is_err());
+    }
+    #[test]
+    fn spawners_run_their_waves_after_the_trigger()
+    {
+        let text = "[spawner]\ncell = 4, 4\ntrigger = 10\n[wave]\ncount = 2\ninterval = 0.1\nformation = 0, 0; 0, 32; 0, 64\n[wave]\ndelay = 0.5\ncount = 1\n";
+        let definition = &SpawnerDefinition::parse_all(text).unwrap()[0];
+        let mut world = World::new_headless(1);
+        let mut enemypool = EnemyPool::new();
+        enemypool.create_pool(20, &mut world);
+        let mut spawner = EnemySpawner::create_spawner(0, 0, &mut world);
+        spawner.apply_definition(definition);
+
+        // Trigger Column not reached -> nothing spawns however long it waits
+        world.level_offset = 9.0 * LEVEL_SCALE;
+        for _ in 0..200 { spawner.update(&mut enemypool, &mut world); }
+        assert_eq!(enemypool.get_alive_count(), 0);
+
+        // First Wave spawns its whole Formation per Step, then the second Wave a single Enemy
+        world.level_offset = 10.0 * LEVEL_SCALE;
+        for _ in 0..60
+        {
+            spawner.update(&mut enemypool, &mut world);
+            if enemypool.get_alive_count() > 0 { break; }
+        }
+        assert_eq!(enemypool.get_alive_count(), 3);
+        for _ in 0..200 { spawner.update(&mut enemypool, &mut world); }
+        assert!(spawner.is_exhausted());
+        assert_eq!(enemypool.get_alive_count(), 3 * 2 + 1);
+    }
+    #[test]
+    fn loader_binds_definitions_to_spawner_cells()
+    {
+        let manifest = "name = Test\n[stage]\nname = Intro\nstructure = stage.png\nspawners = spawners.cfg\n";
+        let spawners = "[spawner]\nname = Bound\ncell = 146, 21\n[wave]\ncount = 3\n[spawner]\nname = Nowhere\ncell = 1, 1\n[wave]\n";
+        let folder = write_test_pack("spawners", &[(MANIFEST_FILE, manifest), ("spawners.cfg", spawners)]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+        assert_eq!(loader.levels.len(), 1);
+
+        // Cell without a Spawner Pixel -> reported, the Definition binds to nothing
+        assert_eq!(loader.errors.len(), 1);
+        assert_eq!(loader.errors[0].kind, LoadErrorKind::Invalid);
+        assert!(loader.errors[0].reason.contains("Nowhere"));
+
+        let mut world = World::new_headless(1);
+        let chunks = LevelChunk::split(&loader.levels[0], &mut LevelData::new(LEVEL_SCALE), &mut world);
+        let spawners: Vec<&PlacedSpawner> = chunks.iter().flat_map(|chunk| chunk.spawners.iter()).collect();
+        let bound: Vec<&&PlacedSpawner> = spawners.iter().filter(|spawner| spawner.definition.is_some()).collect();
+        assert_eq!(bound.len(), 1);
+        assert_eq!(bound[0].tile.cell, vec2(146.0, 21.0));
+        assert_eq!(bound[0].definition.as_ref().unwrap().name, "Bound");
+        assert_eq!(spawners.len(), 7);
+    }
+}
@@ -0,0 +1,383 @@
+use super::*;
+
+const EDITOR_SCROLL_SPEED: f32 = 1200.0;
+
+// Kinds the Editor paints -> PreviousTool & NextTool cycle in this Order
+const EDITOR_TOOLS: [LevelElementKind; 7] = [
+    LevelElementKind::Wall,
+    LevelElementKind::BlockingWall,
+    LevelElementKind::TrapWall,
+    LevelElementKind::Destructible,
+    LevelElementKind::EnemySpawner,
+    LevelElementKind::Turret,
+    LevelElementKind::LevelEnd,
+];
+
+// Help Line of the Editor -> Actions with their Label, the Game fills in the current Bindings
+pub const EDITOR_HELP: [(&[InputAction], &str); 10] = [
+    (&[InputAction::PreviousTool, InputAction::NextTool], "Tool"),
+    (&[InputAction::Fire], "Paint"),
+    (&[InputAction::Erase], "Erase"),
+    (&[InputAction::MoveLeft, InputAction::MoveRight], "Scroll"),
+    (&[InputAction::MoveUp, InputAction::MoveDown], "Count"),
+    (&[InputAction::NextEnemyType], "Type"),
+    (&[InputAction::Playtest], "Playtest"),
+    (&[InputAction::Export], "Export"),
+    (&[InputAction::PreviousStage, InputAction::NextStage], "Stage"),
+    (&[InputAction::Pause], "Leave"),
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EditorCommand
+{
+    None,
+    // Play the edited Stage from the current Scroll Position
+    Playtest,
+    // Write Structure & Spawnmap back into the Pack Folder
+    Export,
+    SwitchStage(usize),
+    Leave,
+}
+
+// Paints Level Elements on the Structure Grid of one Stage
+pub struct StageEditor
+{
+    pub stage_index: usize,
+    pub data: LoadedLevelData,
+    pub palette: LevelPalette,
+    // Same Meaning as the Level Offset -> Left Edge of the View
+    pub scroll: f32,
+    pub tool: LevelElementKind,
+    pub spawner_count: usize,
+    pub spawner_type: usize,
+    // Last Export or Playtest Result -> shown in the Editor UI
+    pub status: String,
+    // Data changed since the Game last took it -> see take_edited
+    edited: bool,
+}
+impl StageEditor
+{
+    pub fn new(stage_index: usize, data: LoadedLevelData, palette: LevelPalette) -> Self
+    {
+        Self {
+            stage_index,
+            data,
+            palette,
+            scroll: -GAME_SIZE_X * 0.5,
+            tool: LevelElementKind::BlockingWall,
+            spawner_count: 10,
+            spawner_type: 0,
+            status: String::new(),
+            edited: false,
+        }
+    }
+
+    pub fn update(&mut self, input: &InputFrame, stage_count: usize) -> EditorCommand
+    {
+        // Scroll
+        let mut direction = 0.0;
+        if input.is_down(InputAction::MoveLeft) { direction -= 1.0; }
+        if input.is_down(InputAction::MoveRight) { direction += 1.0; }
+        direction -= input.wheel.signum();
+        self.scroll = (self.scroll + direction * EDITOR_SCROLL_SPEED * get_frame_time()).max(-GAME_SIZE_X * 0.5);
+
+        // Tools
+        let tool_index = EDITOR_TOOLS.iter().position(|tool| *tool == self.tool).unwrap_or(0);
+        if input.is_released(InputAction::NextTool) { self.tool = EDITOR_TOOLS[(tool_index + 1) % EDITOR_TOOLS.len()]; }
+        if input.is_released(InputAction::PreviousTool) { self.tool = EDITOR_TOOLS[(tool_index + EDITOR_TOOLS.len() - 1) % EDITOR_TOOLS.len()]; }
+        // Spawner Settings -> Count up & down, Type cycles through every Enemy Type
+        if input.is_released(InputAction::MoveUp) { self.spawner_count = (self.spawner_count + 1).min(255); }
+        if input.is_released(InputAction::MoveDown) { self.spawner_count = self.spawner_count.saturating_sub(1).max(1); }
+        if input.is_released(InputAction::NextEnemyType)
+        {
+            self.spawner_type += 1;
+            if EnemySpawner::enemy_type_from_id(self.spawner_type).is_none()
+            {
+                self.spawner_type = 0;
+            }
+        }
+
+        // Paint & Erase
+        if let Some(cell) = self.get_hovered_cell(input)
+        {
+            if input.is_down(InputAction::Fire)
+            {
+                self.paint(cell);
+            }
+            if input.is_down(InputAction::Erase)
+            {
+                self.erase(cell);
+            }
+        }
+
+        // Commands
+        if input.is_released(InputAction::Playtest) { return EditorCommand::Playtest; }
+        if input.is_released(InputAction::Export) { return EditorCommand::Export; }
+        if input.is_released(InputAction::NextStage) && self.stage_index + 1 < stage_count { return EditorCommand::SwitchStage(self.stage_index + 1); }
+        if input.is_released(InputAction::PreviousStage) && self.stage_index > 0 { return EditorCommand::SwitchStage(self.stage_index - 1); }
+        if input.is_released(InputAction::Pause) { return EditorCommand::Leave; }
+        EditorCommand::None
+    }
+
+    // Cell under the Mouse -> None above or below the Stage
+    fn get_hovered_cell(&self, input: &InputFrame) -> Option<Vec2>
+    {
+        let x = ((input.aim.x + self.scroll) / LEVEL_SCALE).floor();
+        let y = (input.aim.y / LEVEL_SCALE).floor();
+        if x < 0.0 || y < 0.0 || y >= LEVEL_ROWS as f32
+        {
+            return None;
+        }
+        Some(vec2(x, y))
+    }
+
+    // One Element per Cell -> same as one Color per Pixel in the Structure Image
+    pub fn paint(&mut self, cell: Vec2)
+    {
+        let already_painted = match self.tool
+        {
+            LevelElementKind::EnemySpawner => self.data.enemy_spawner.iter().any(|s| s.0 == cell && s.1 == self.spawner_count && s.2 == self.spawner_type),
+            _ => self.get_kind_at(cell) == Some(self.tool),
+        };
+        if already_painted
+        {
+            return;
+        }
+        self.erase(cell);
+        self.edited = true;
+        self.data.add_element(self.tool, cell);
+        match self.tool
+        {
+            LevelElementKind::EnemySpawner => {
+                let spawner = self.data.enemy_spawner.last_mut().unwrap();
+                spawner.1 = self.spawner_count;
+                spawner.2 = self.spawner_type;
+                // Tiled Stages carry Properties per Spawner -> keep both Lists the same Length
+                if !self.data.spawner_properties.is_empty()
+                {
+                    self.data.spawner_properties.push(SpawnerProperties { delay: 0.0, custom: TiledProperties::new() });
+                }
+            }
+            // The Level reads its End Top to Bottom
+            LevelElementKind::LevelEnd => { self.data.level_end.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap()); }
+            _ => {}
+        }
+        self.grow_to(cell);
+    }
+    pub fn erase(&mut self, cell: Vec2)
+    {
+        if self.get_kind_at(cell).is_none() && !self.data.markers.iter().any(|marker| marker.position == cell)
+        {
+            return;
+        }
+        self.edited = true;
+        let data = &mut self.data;
+        for cells in [&mut data.walls, &mut data.infected_wall_filling, &mut data.blockingWalls, &mut data.trapWalls, &mut data.destructibles, &mut data.turrets, &mut data.level_end]
+        {
+            cells.retain(|position| *position != cell);
+        }
+        if let Some(index) = data.enemy_spawner.iter().position(|s| s.0 == cell)
+        {
+            data.enemy_spawner.remove(index);
+            if index < data.spawner_properties.len()
+            {
+                data.spawner_properties.remove(index);
+            }
+        }
+        data.markers.retain(|marker| marker.position != cell);
+    }
+    // True once after every Change -> the Game copies the Data back into the Loader only then
+    pub fn take_edited(&mut self) -> bool
+    {
+        std::mem::replace(&mut self.edited, false)
+    }
+    fn grow_to(&mut self, cell: Vec2)
+    {
+        self.data.size.0 = self.data.size.0.max(cell.x as u32 + 1);
+        self.data.size.1 = self.data.size.1.max(cell.y as u32 + 1).max(LEVEL_ROWS);
+    }
+
+    pub fn get_kind_at(&self, cell: Vec2) -> Option<LevelElementKind>
+    {
+        let data = &self.data;
+        if data.walls.contains(&cell) { return Some(LevelElementKind::Wall); }
+        if data.infected_wall_filling.contains(&cell) { return Some(LevelElementKind::InfectedWall); }
+        if data.blockingWalls.contains(&cell) { return Some(LevelElementKind::BlockingWall); }
+        if data.trapWalls.contains(&cell) { return Some(LevelElementKind::TrapWall); }
+        if data.destructibles.contains(&cell) { return Some(LevelElementKind::Destructible); }
+        if data.enemy_spawner.iter().any(|s| s.0 == cell) { return Some(LevelElementKind::EnemySpawner); }
+        if data.turrets.contains(&cell) { return Some(LevelElementKind::Turret); }
+        if data.level_end.contains(&cell) { return Some(LevelElementKind::LevelEnd); }
+        None
+    }
+
+    // ---------------------------------
+    // Export
+
+    // Same Layout LevelLoader reads -> Palette Colors in the Structure, Count (green) & Type (blue) in the Spawnmap
+    pub fn to_images(&self) -> Result<(Image, Image), String>
+    {
+        let (width, height) = self.get_export_size();
+        let mut structure = Image::gen_image_color(width as u16, height as u16, BLACK);
+        let mut spawnmap = Image::gen_image_color(width as u16, height as u16, BLANK);
+
+        let data = &self.data;
+        let elements = [
+            (LevelElementKind::Wall, &data.walls),
+            (LevelElementKind::InfectedWall, &data.infected_wall_filling),
+            (LevelElementKind::BlockingWall, &data.blockingWalls),
+            (LevelElementKind::TrapWall, &data.trapWalls),
+            (LevelElementKind::Destructible, &data.destructibles),
+            (LevelElementKind::Turret, &data.turrets),
+            (LevelElementKind::LevelEnd, &data.level_end),
+        ];
+        for (kind, cells) in elements.iter()
+        {
+            let color = self.get_palette_color(kind.get_name())?;
+            for cell in cells.iter()
+            {
+                structure.set_pixel(cell.x as u32, cell.y as u32, color);
+            }
+        }
+        let spawner_color = self.get_palette_color(LevelElementKind::EnemySpawner.get_name())?;
+        for (cell, count, enemy_type) in data.enemy_spawner.iter()
+        {
+            structure.set_pixel(cell.x as u32, cell.y as u32, spawner_color);
+            spawnmap.set_pixel(cell.x as u32, cell.y as u32, color_u8!(255, *count as u8, *enemy_type as u8, 255));
+        }
+        // Markers without a Palette Color would be lost -> the Palette has to know them
+        for marker in data.markers.iter()
+        {
+            let color = self.get_palette_color(&marker.kind)?;
+            structure.set_pixel(marker.position.x as u32, marker.position.y as u32, color);
+        }
+        Ok((structure, spawnmap))
+    }
+    fn get_export_size(&self) -> (u32, u32)
+    {
+        let mut size = (self.data.size.0, self.data.size.1.max(LEVEL_ROWS));
+        let mut cells: Vec<Vec2> = Vec::new();
+        cells.extend(self.data.walls.iter().chain(self.data.infected_wall_filling.iter()).chain(self.data.blockingWalls.iter()));
+        cells.extend(self.data.trapWalls.iter().chain(self.data.destructibles.iter()).chain(self.data.turrets.iter()).chain(self.data.level_end.iter()));
+        cells.extend(self.data.enemy_spawner.iter().map(|s| s.0));
+        cells.extend(self.data.markers.iter().map(|m| m.position));
+        for cell in cells.iter()
+        {
+            size.0 = size.0.max(cell.x as u32 + 1);
+            size.1 = size.1.max(cell.y as u32 + 1);
+        }
+        size
+    }
+    fn get_palette_color(&self, kind: &str) -> Result<Color, String>
+    {
+        match self.palette.entries.iter().find(|entry| entry.kind == kind)
+        {
+            Some(entry) => Ok(entry.color),
+            None => Err(format!("Palette {} has no Color for {}", self.palette.name, kind)),
+        }
+    }
+
+    // Overwrites the Files named in the Manifest -> Stages without a Spawnmap get "<structure>_Spawnmap.png"
+    pub fn export(&mut self, folder: &str) -> Result<Vec<String>, String>
+    {
+        let stage = self.data.stage.clone();
+        if is_tiled_map(&stage.structure)
+        {
+            return Err(format!("{} is a Tiled Map -> edit it in Tiled", stage.structure));
+        }
+        let (structure, spawnmap) = self.to_images()?;
+        let spawnmap_file = match &stage.spawnmap
+        {
+            Some(file) => file.clone(),
+            None => format!("{}_Spawnmap.png", stage.structure.trim_end_matches(".png")),
+        };
+
+        let mut written = Vec::new();
+        for (file, image) in [(&stage.structure, &structure), (&spawnmap_file, &spawnmap)]
+        {
+            let path = format!("{}{}", folder, file);
+            write_png(&path, image)?;
+            written.push(path);
+        }
+        if stage.spawnmap.is_none()
+        {
+            println!("Add \"spawnmap = {}\" to {} of the Manifest", spawnmap_file, stage.name);
+            self.data.stage.spawnmap = Some(spawnmap_file);
+        }
+        Ok(written)
+    }
+
+    // ---------------------------------
+    // Draw
+
+    pub fn draw(&self)
+    {
+        let first_column = (self.scroll / LEVEL_SCALE).floor().max(0.0) as u32;
+        let last_column = ((self.scroll + GAME_SIZE_X) / LEVEL_SCALE).ceil() as u32;
+
+        // Grid
+        for column in first_column..=last_column
+        {
+            let x = column as f32 * LEVEL_SCALE;
+            draw_line(x, 0.0, x, GAME_SIZE_Y, 1.0, color_u8!(255,255,255,20));
+        }
+        for row in 0..=LEVEL_ROWS
+        {
+            let y = row as f32 * LEVEL_SCALE;
+            draw_line(self.scroll.max(0.0), y, self.scroll + GAME_SIZE_X, y, 1.0, color_u8!(255,255,255,20));
+        }
+
+        // Elements in their Palette Color
+        let data = &self.data;
+        let elements = [
+            (LevelElementKind::Wall, &data.walls),
+            (LevelElementKind::InfectedWall, &data.infected_wall_filling),
+            (LevelElementKind::BlockingWall, &data.blockingWalls),
+            (LevelElementKind::TrapWall, &data.trapWalls),
+            (LevelElementKind::Destructible, &data.destructibles),
+            (LevelElementKind::Turret, &data.turrets),
+            (LevelElementKind::LevelEnd, &data.level_end),
+        ];
+        for (kind, cells) in elements.iter()
+        {
+            let color = self.get_palette_color(kind.get_name()).unwrap_or(GRAY);
+            for cell in cells.iter().filter(|cell| cell.x as u32 >= first_column && cell.x as u32 <= last_column)
+            {
+                draw_rectangle(cell.x * LEVEL_SCALE, cell.y * LEVEL_SCALE, LEVEL_SCALE, LEVEL_SCALE, color);
+            }
+        }
+        let spawner_color = self.get_palette_color(LevelElementKind::EnemySpawner.get_name()).unwrap_or(RED);
+        for (cell, count, enemy_type) in data.enemy_spawner.iter()
+        {
+            draw_rectangle(cell.x * LEVEL_SCALE, cell.y * LEVEL_SCALE, LEVEL_SCALE, LEVEL_SCALE, spawner_color);
+            draw_text(format!("{}|{}", count, enemy_type).as_str(), cell.x * LEVEL_SCALE + 2.0, cell.y * LEVEL_SCALE + LEVEL_SCALE * 0.6, 20.0, WHITE);
+        }
+        for marker in data.markers.iter()
+        {
+            draw_rectangle_lines(marker.position.x * LEVEL_SCALE, marker.position.y * LEVEL_SCALE, marker.size.x * LEVEL_SCALE, marker.size.y * LEVEL_SCALE, 3.0, ORANGE);
+        }
+    }
+    // Screen Space -> called after the View is back at the Scroll Position
+    pub fn draw_ui(&self, input: &InputFrame, stage_count: usize, help: &str)
+    {
+        let offset = self.scroll;
+        if let Some(cell) = self.get_hovered_cell(input) { draw_rectangle_lines(cell.x * LEVEL_SCALE, cell.y * LEVEL_SCALE, LEVEL_SCALE, LEVEL_SCALE, 3.0, YELLOW); }
+
+        draw_rectangle(offset, 0.0, GAME_SIZE_X, 60.0, color_u8!(0,0,0,190));
+        let text = format!("EDITOR  {} ({} / {})  |  Tool: {}  |  Spawner: {} x Type {}  |  Column {}",
+            self.data.stage.name, self.stage_index + 1, stage_count, self.tool.get_name(), self.spawner_count, self.spawner_type, (self.scroll / LEVEL_SCALE).max(0.0) as u32);
+        draw_text(text.as_str(), offset + 10.0, 38.0, 30.0, WHITE);
+
+        draw_text(help, offset + 10.0, GAME_SIZE_Y - 20.0, 24.0, GRAY);
+        if !self.status.is_empty()
+        {
+            draw_text(self.status.as_str(), offset + 10.0, GAME_SIZE_Y - 50.0, 24.0, YELLOW);
+        }
+    }
+}
+
+fn write_png(path: &str, image: &Image) -> Result<(), String>
+{
+    ::image::save_buffer(path, &image.bytes, image.width() as u32, image.height() as u32, ::image::ColorType::Rgba8)
+        .map_err(|error| format!("could not write {}: {}", path, error))
+}
@@ -0,0 +1,377 @@
+use super::*;
+
+// Columns before the first Section -> the Player spawns half a Screen before Column 0
+const START_COLUMNS: usize = 24;
+// Open Columns in front of the Level End
+const END_COLUMNS: usize = 8;
+// Blocking Rows between the Lane and the Wall Filling
+const BORDER_ROWS: i32 = 2;
+const MAX_ATTEMPTS: usize = 8;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum SectionKind
+{
+    Corridor,
+    // Lane narrows down to the smallest Gap the Player fits through
+    Chokepoint,
+    // Lane Borders are Trap Walls
+    TrapSection,
+    // Turrets along the Lane Borders
+    TurretNest,
+}
+impl SectionKind
+{
+    pub fn all() -> [SectionKind; 4]
+    {
+        [SectionKind::Corridor, SectionKind::Chokepoint, SectionKind::TrapSection, SectionKind::TurretNest]
+    }
+}
+
+// Free Rows of one Column -> top ..= bottom
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Lane
+{
+    top: i32,
+    bottom: i32,
+}
+impl Lane
+{
+    fn height(&self) -> i32 { self.bottom - self.top + 1 }
+    fn center(&self) -> i32 { (self.top + self.bottom) / 2 }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct Column
+{
+    lane: Lane,
+    top_border: LevelElementKind,
+    bottom_border: LevelElementKind,
+}
+
+// Builds Stages after the Campaign -> same Seed & Difficulty, same Stage
+pub struct StageGenerator
+{
+    pub seed: u64,
+    pub difficulty: i32,
+    // Columns including the Level End
+    pub width: usize,
+    // Player Hitbox & Speed -> decide how narrow and how steep the Lane may get
+    pub player_size: Vec2,
+    pub player_speed: f32,
+    pub scroll_speed: f32,
+}
+impl StageGenerator
+{
+    pub fn new(seed: u64, difficulty: i32) -> Self
+    {
+        let difficulty = difficulty.max(0);
+        Self {
+            seed,
+            difficulty,
+            width: (160 + difficulty as usize * 8).min(256),
+            player_size: vec2(60.0, 60.0),
+            player_speed: EntitySettings::player_settings().speed,
+            scroll_speed: LEVEL_SPEED,
+        }
+    }
+    // Every Stage & Difficulty of a Run gets its own Seed -> Replays rebuild it from their Header
+    pub fn for_stage(run_seed: u64, stage: usize, difficulty: i32) -> Self
+    {
+        let salt = (stage as u64).wrapping_mul(0xA076_1D64_78BD_642F) ^ (difficulty as u64).wrapping_mul(0xE703_7ED1_A0B4_28DB);
+        let seed = RandomStream::new(run_seed ^ salt).next_u64();
+        StageGenerator::new(seed, difficulty)
+    }
+
+    // Rows the Player needs -> one more than the Hitbox, it is never aligned to the Grid
+    pub fn get_clearance(&self) -> i32
+    {
+        (self.player_size.y / LEVEL_SCALE).ceil() as i32 + 1
+    }
+    // Rows the Lane may move per Column -> the Player has to keep up while the Level scrolls
+    pub fn get_max_shift(&self) -> i32
+    {
+        ((self.player_speed / self.scroll_speed).floor() as i32).clamp(0, 1)
+    }
+
+    pub fn generate(&self, name: &str) -> LoadedLevelData
+    {
+        // Failed Attempts continue the same Sequence -> the Result still only depends on the Seed
+        let mut random = RandomStream::new(self.seed);
+        for attempt in 0..MAX_ATTEMPTS
+        {
+            let level_data = self.build(&mut random, name, true);
+            if has_passable_route(&level_data, self.get_clearance(), self.get_max_shift())
+            {
+                return level_data;
+            }
+            println!("Generated Layout {} has no Route -> next Attempt", attempt + 1);
+        }
+        // Plain Corridors always have a Route
+        self.build(&mut random, name, false)
+    }
+
+    fn build(&self, random: &mut RandomStream, name: &str, hazards: bool) -> LoadedLevelData
+    {
+        let rows = LEVEL_ROWS as i32;
+        let min_gap = self.get_clearance() + 1;
+        let max_gap = (12 - self.difficulty).max(min_gap + 2);
+
+        // Start Lane around the Spawn Row of the Player
+        let spawn_row = rows / 2;
+        let mut lane = Lane { top: spawn_row - max_gap / 2, bottom: spawn_row - max_gap / 2 + max_gap - 1 };
+        let mut columns: Vec<Column> = Vec::new();
+        for _ in 0..START_COLUMNS
+        {
+            columns.push(Column { lane, top_border: LevelElementKind::BlockingWall, bottom_border: LevelElementKind::BlockingWall });
+        }
+
+        let sections_end = self.width.saturating_sub(END_COLUMNS + 1);
+        while columns.len() < sections_end
+        {
+            let kind = if hazards { SectionKind::all()[random.range_i32(0, 4) as usize] } else { SectionKind::Corridor };
+            let length = (random.range_i32(12, 30) as usize).min(sections_end - columns.len());
+            let target_gap = random.range_i32(min_gap + 1, max_gap + 1);
+            let target_center = random.range_i32(BORDER_ROWS + target_gap / 2, rows - BORDER_ROWS - target_gap / 2);
+
+            for step in 0..length
+            {
+                let (gap, border) = match kind
+                {
+                    SectionKind::Corridor => (target_gap, LevelElementKind::BlockingWall),
+                    // Narrow in the first Half, open up again in the second
+                    SectionKind::Chokepoint => (if step < length / 2 { min_gap } else { max_gap }, LevelElementKind::BlockingWall),
+                    SectionKind::TrapSection => (target_gap.max(min_gap + 1), LevelElementKind::TrapWall),
+                    SectionKind::TurretNest => (target_gap.max(min_gap + 2), LevelElementKind::BlockingWall),
+                };
+                lane = self.step_lane(lane, target_center, gap, min_gap);
+                let mut column = Column { lane, top_border: border, bottom_border: border };
+                if kind == SectionKind::TurretNest && step % 4 == 2
+                {
+                    if (step / 4) % 2 == 0 { column.top_border = LevelElementKind::Turret; } else { column.bottom_border = LevelElementKind::Turret; }
+                }
+                columns.push(column);
+            }
+        }
+        // Open Run-out in front of the Level End
+        while columns.len() < self.width - 1
+        {
+            lane = self.step_lane(lane, spawn_row, max_gap, min_gap);
+            columns.push(Column { lane, top_border: LevelElementKind::BlockingWall, bottom_border: LevelElementKind::BlockingWall });
+        }
+        if hazards
+        {
+            self.place_spawners(random, &mut columns);
+        }
+        self.to_level_data(random, &columns, name)
+    }
+
+    // Either moves or resizes the Lane by one Row -> two neighbouring Columns always share at least min_gap - 1 free Rows
+    fn step_lane(&self, lane: Lane, target_center: i32, target_gap: i32, min_gap: i32) -> Lane
+    {
+        let rows = LEVEL_ROWS as i32;
+        let mut next = lane;
+        if lane.height() > target_gap && lane.height() > min_gap
+        {
+            if lane.center() < target_center { next.top += 1; } else { next.bottom -= 1; }
+        }
+        else if lane.height() < target_gap
+        {
+            if lane.center() < target_center { next.bottom += 1; } else { next.top -= 1; }
+        }
+        else if self.get_max_shift() > 0 && lane.center() != target_center
+        {
+            let shift = (target_center - lane.center()).signum();
+            next.top += shift;
+            next.bottom += shift;
+        }
+        // Keep the Borders inside the Stage
+        if next.top < BORDER_ROWS || next.bottom > rows - 1 - BORDER_ROWS
+        {
+            return lane;
+        }
+        next
+    }
+
+    // Spawners sit in the Lane Borders -> Count & Types grow with the Difficulty
+    fn place_spawners(&self, random: &mut RandomStream, columns: &mut [Column])
+    {
+        let spawner_count = 2 + self.difficulty as usize;
+        let last_column = columns.len().saturating_sub(END_COLUMNS);
+        for _ in 0..spawner_count
+        {
+            let index = random.range_i32(START_COLUMNS as i32, last_column as i32) as usize;
+            let column = &mut columns[index];
+            if random.next_f32() < 0.5
+            {
+                if column.top_border == LevelElementKind::BlockingWall { column.top_border = LevelElementKind::EnemySpawner; }
+            }
+            else
+            {
+                if column.bottom_border == LevelElementKind::BlockingWall { column.bottom_border = LevelElementKind::EnemySpawner; }
+            }
+        }
+    }
+
+    fn to_level_data(&self, random: &mut RandomStream, columns: &[Column], name: &str) -> LoadedLevelData
+    {
+        let rows = LEVEL_ROWS as i32;
+        let mut level_data = LoadedLevelData::new();
+        let mut stage = StageEntry::new(name);
+        stage.scroll_speed = self.scroll_speed;
+        level_data.stage = stage;
+        level_data.palette = "Generated".to_string();
+        level_data.size = (self.width as u32, LEVEL_ROWS);
+
+        // Boss (5) only shows up once the Difficulty is high enough
+        let max_enemy_type = (2 + self.difficulty).min(5);
+        for (x, column) in columns.iter().enumerate()
+        {
+            for y in 0..rows
+            {
+                let position = vec2(x as f32, y as f32);
+                let lane = column.lane;
+                let kind = if y >= lane.top && y <= lane.bottom { continue; }
+                    else if y == lane.top - 1 { column.top_border }
+                    else if y == lane.bottom + 1 { column.bottom_border }
+                    else if y >= lane.top - BORDER_ROWS && y <= lane.bottom + BORDER_ROWS { LevelElementKind::BlockingWall }
+                    else if random.next_f32() < 0.05 { LevelElementKind::InfectedWall }
+                    else { LevelElementKind::Wall };
+                level_data.add_element(kind, position);
+                if kind == LevelElementKind::EnemySpawner
+                {
+                    let spawner = level_data.enemy_spawner.last_mut().unwrap();
+                    spawner.1 = (3 + 2 * self.difficulty as usize).min(40);
+                    spawner.2 = random.range_i32(0, max_enemy_type + 1) as usize;
+                }
+            }
+        }
+        // Full Height Level End -> read Top to Bottom
+        for y in 0..rows
+        {
+            level_data.add_element(LevelElementKind::LevelEnd, vec2((self.width - 1) as f32, y as f32));
+        }
+        level_data
+    }
+}
+
+// Walks the Stage Column by Column with a Window of `clearance` free Rows, starting at the Spawn Row
+// Conservative -> a Shift only counts if the Window is free in both Columns
+pub fn has_passable_route(level_data: &LoadedLevelData, clearance: i32, max_shift: i32) -> bool
+{
+    let (width, rows) = (level_data.size.0 as usize, level_data.size.1.max(LEVEL_ROWS) as usize);
+    if width == 0
+    {
+        return false;
+    }
+    let mut solid = vec![vec![false; rows]; width];
+    let cells = level_data.blockingWalls.iter()
+        .chain(level_data.trapWalls.iter())
+        .chain(level_data.destructibles.iter())
+        .chain(level_data.turrets.iter())
+        .chain(level_data.enemy_spawner.iter().map(|s| &s.0));
+    for cell in cells
+    {
+        let (x, y) = (cell.x as usize, cell.y as usize);
+        if x < width && y < rows
+        {
+            solid[x][y] = true;
+        }
+    }
+    // Hazards block every Cell their Area sweeps along the Path -> Gates & Doors count as closed
+    for hazard in level_data.hazards.iter()
+    {
+        let mut points = vec![vec2(0.0, 0.0)];
+        points.extend(hazard.path.iter().cloned());
+        for (from, to) in points.iter().zip(points.iter().skip(1).chain(points.last()))
+        {
+            let start = from.min(*to) + hazard.area.point();
+            let end = from.max(*to) + hazard.area.point() + hazard.area.size();
+            let columns = start.x.max(0.0) as usize..end.x.ceil().max(0.0) as usize;
+            let cells = start.y.max(0.0) as usize..end.y.ceil().max(0.0) as usize;
+            for column in solid.iter_mut().take(columns.end).skip(columns.start)
+            {
+                for cell in column.iter_mut().take(cells.end).skip(cells.start)
+                {
+                    *cell = true;
+                }
+            }
+        }
+    }
+    let clearance = clearance.max(1) as usize;
+    let is_free = |x: usize, top: usize| (top..top + clearance).all(|y| y < rows && !solid[x][y]);
+
+    let spawn_row = rows / 2;
+    let mut reachable: Vec<bool> = (0..rows).map(|top| top <= spawn_row && spawn_row < top + clearance && is_free(0, top)).collect();
+    // Level End Column is no Obstacle
+    let last_column = level_data.level_end.iter().map(|end| end.x as usize).min().unwrap_or(width);
+    for x in 1..last_column.min(width)
+    {
+        let mut next = vec![false; rows];
+        for (top, open) in next.iter_mut().enumerate()
+        {
+            if !is_free(x, top) || !is_free(x - 1, top)
+            {
+                continue;
+            }
+            let low = top.saturating_sub(max_shift as usize);
+            let high = (top + max_shift as usize).min(rows - 1);
+            *open = (low..=high).any(|previous| reachable[previous]);
+        }
+        if !next.iter().any(|r| *r)
+        {
+            return false;
+        }
+        reachable = next;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn same_layout(a: &LoadedLevelData, b: &LoadedLevelData) -> bool
+    {
+        a.walls == b.walls && a.blockingWalls == b.blockingWalls && a.trapWalls == b.trapWalls && a.turrets == b.turrets && a.enemy_spawner == b.enemy_spawner
+    }
+
+    #[test]
+    fn generated_stages_have_a_route_and_follow_their_seed()
+    {
+        for seed in [1, 7, 42, 0xBEEF]
+        {
+            for difficulty in [0, 2, 5, 9]
+            {
+                let generator = StageGenerator::for_stage(seed, 10, difficulty);
+                let level_data = generator.generate("Test");
+                assert!(has_passable_route(&level_data, generator.get_clearance(), generator.get_max_shift()), "Seed {} Difficulty {}", seed, difficulty);
+                assert_eq!(level_data.size.0 as usize, generator.width);
+                assert!(same_layout(&level_data, &StageGenerator::for_stage(seed, 10, difficulty).generate("Test")));
+            }
+        }
+        // Other Stage or Run -> other Layout
+        let level_data = StageGenerator::for_stage(1, 10, 2).generate("Test");
+        assert!(!same_layout(&level_data, &StageGenerator::for_stage(1, 11, 2).generate("Test")));
+        assert!(!same_layout(&level_data, &StageGenerator::for_stage(2, 10, 2).generate("Test")));
+    }
+    #[test]
+    fn hazards_block_the_route()
+    {
+        let generator = StageGenerator::new(3, 0);
+        let level_data = generator.build(&mut RandomStream::new(3), "Corridor", false);
+        let (clearance, max_shift) = (generator.get_clearance(), generator.get_max_shift());
+        assert!(has_passable_route(&level_data, clearance, max_shift));
+
+        let mut gated = level_data.clone();
+        gated.hazards.push(StageHazard::new("Gate", HazardKind::LaserGate, Rect::new(40.0, 0.0, 1.0, LEVEL_ROWS as f32)));
+        assert!(!has_passable_route(&gated, clearance, max_shift));
+
+        // Crusher starts above the Stage and sweeps down through the whole Lane
+        let mut crushed = level_data.clone();
+        let mut crusher = StageHazard::new("Crusher", HazardKind::Crusher, Rect::new(40.0, 0.0, 2.0, 2.0));
+        crusher.path = vec![vec2(0.0, LEVEL_ROWS as f32 - 2.0)];
+        crushed.hazards.push(crusher);
+        assert!(!has_passable_route(&crushed, clearance, max_shift));
+    }
+}
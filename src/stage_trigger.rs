@@ -0,0 +1,242 @@
+use super::*;
+
+pub const TRIGGER_MARKER_KIND: &str = "trigger";
+
+// What has to enter the Trigger Area
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TriggerSource
+{
+    // Player Hitbox overlaps the Area
+    Player,
+    // View overlaps the Area -> fires once the Area scrolls in
+    Scroll,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum TriggerAction
+{
+    // Held Spawners of this Group start
+    ActivateSpawners(String),
+    // Pixel per Second -> None hands the Speed back to the Scroll Curve
+    ScrollSpeed(Option<f32>),
+    Banner(String),
+    // Asset Name of the Sound
+    Sound(String),
+    // Blocking Walls inside this Area (Cells) break
+    OpenWall(Rect),
+    // Boss spawns at this Cell
+    StartBoss(Vec2),
+}
+
+// Area of a Stage that fires its Actions in Order once entered
+#[derive(Clone, PartialEq, Debug)]
+pub struct StageTrigger
+{
+    pub name: String,
+    // In Cells
+    pub area: Rect,
+    pub source: TriggerSource,
+    // false -> fires again every Time it is entered
+    pub once: bool,
+    pub actions: Vec<TriggerAction>,
+}
+impl StageTrigger
+{
+    // Keys -> on (player, scroll), once (true, false) and one Action per Key:
+    // spawners = group | scroll_speed = speed or curve | banner = text | sound = asset | open_wall = x, y, w, h | boss = x, y
+    pub fn from_properties(name: &str, area: Rect, properties: &TiledProperties) -> Result<Self, String>
+    {
+        let mut trigger = Self { name: name.to_string(), area, source: TriggerSource::Player, once: true, actions: Vec::new() };
+        for (key, value) in properties.iter()
+        {
+            let value = value.trim();
+            match key.as_str()
+            {
+                "name" => { trigger.name = value.to_string(); }
+                "on" => {
+                    trigger.source = match value
+                    {
+                        "player" => TriggerSource::Player,
+                        "scroll" => TriggerSource::Scroll,
+                        _ => { return Err(format!("{}: on has to be player or scroll", trigger.name)); }
+                    };
+                }
+                "once" => {
+                    trigger.once = value.parse::<bool>().map_err(|_| format!("{}: once has to be true or false", trigger.name))?;
+                }
+                "spawners" => { trigger.actions.push(TriggerAction::ActivateSpawners(value.to_string())); }
+                "scroll_speed" => {
+                    let speed = match value
+                    {
+                        "curve" => None,
+                        _ => Some(value.parse::<f32>().ok().filter(|s| *s > 0.0).ok_or(format!("{}: scroll_speed has to be above 0 or curve", trigger.name))?),
+                    };
+                    trigger.actions.push(TriggerAction::ScrollSpeed(speed));
+                }
+                "banner" => { trigger.actions.push(TriggerAction::Banner(value.to_string())); }
+                "sound" => { trigger.actions.push(TriggerAction::Sound(value.to_string())); }
+                "open_wall" => {
+                    let numbers = parse_numbers(value).ok_or(format!("{}: open_wall needs x, y, w, h", trigger.name))?;
+                    match numbers.as_slice()
+                    {
+                        [x, y, w, h] => { trigger.actions.push(TriggerAction::OpenWall(Rect::new(*x, *y, *w, *h))); }
+                        _ => { return Err(format!("{}: open_wall needs x, y, w, h", trigger.name)); }
+                    }
+                }
+                "boss" => {
+                    let cell = parse_vec2(value).ok_or(format!("{}: boss needs x, y", trigger.name))?;
+                    trigger.actions.push(TriggerAction::StartBoss(cell));
+                }
+                // Tiled Objects carry their own Properties too
+                _ => {}
+            }
+        }
+        if trigger.actions.is_empty()
+        {
+            return Err(format!("{} has no Action", trigger.name));
+        }
+        Ok(trigger)
+    }
+
+    // Format -> one "[trigger]" Block per Trigger with "area = x, y, w, h" (Cells) and the Keys of from_properties  |  "#" starts a Comment
+    pub fn parse_all(text: &str) -> Result<Vec<StageTrigger>, String>
+    {
+        // Area & Properties of every Block -> turned into Triggers once the Block is complete
+        let mut blocks: Vec<(Option<Rect>, TiledProperties)> = Vec::new();
+        for (line_number, line) in config_lines(text)
+        {
+            if line == "[trigger]"
+            {
+                blocks.push((None, TiledProperties::new()));
+                continue;
+            }
+            let block = match blocks.last_mut()
+            {
+                Some(block) => block,
+                None => { return Err(format!("Line {}: expected [trigger] first", line_number)); }
+            };
+            let (key, value) = config_entry(line_number, line)?;
+            if key == "area"
+            {
+                match parse_numbers(value).as_deref()
+                {
+                    Some([x, y, w, h]) => { block.0 = Some(Rect::new(*x, *y, *w, *h)); }
+                    _ => { return Err(format!("Line {}: area needs x, y, w, h", line_number)); }
+                }
+                continue;
+            }
+            block.1.push((key.to_string(), value.to_string()));
+        }
+
+        let mut triggers = Vec::new();
+        for (i, (area, properties)) in blocks.iter().enumerate()
+        {
+            let name = format!("Trigger {}", i + 1);
+            let area = area.ok_or(format!("{} has no area", name))?;
+            triggers.push(StageTrigger::from_properties(&name, area, properties)?);
+        }
+        Ok(triggers)
+    }
+
+    pub fn get_world_rect(&self) -> Rect
+    {
+        Rect::new(self.area.x * LEVEL_SCALE, self.area.y * LEVEL_SCALE, self.area.w * LEVEL_SCALE, self.area.h * LEVEL_SCALE)
+    }
+    pub fn is_entered(&self, player_rect: Rect, level_offset: f32) -> bool
+    {
+        let rect = self.get_world_rect();
+        match self.source
+        {
+            TriggerSource::Player => rect.overlaps(&player_rect),
+            TriggerSource::Scroll => level_offset + GAME_SIZE_X >= rect.x && level_offset <= rect.x + rect.w,
+        }
+    }
+    // Spawners of this Group wait for the Trigger
+    pub fn activates_group(&self, group: &str) -> bool
+    {
+        self.actions.iter().any(|action| *action == TriggerAction::ActivateSpawners(group.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_triggers_with_actions_in_order()
+    {
+        let text = "[trigger]\narea = 10, 0, 2, 24\non = scroll\nonce = false\nbanner = Incoming\nspawners = pincer\nscroll_speed = curve\nopen_wall = 20, 4, 2, 3\n";
+        let triggers = StageTrigger::parse_all(text).unwrap();
+        assert_eq!(triggers.len(), 1);
+        let trigger = &triggers[0];
+        assert_eq!(trigger.area, Rect::new(10.0, 0.0, 2.0, 24.0));
+        assert_eq!(trigger.source, TriggerSource::Scroll);
+        assert!(!trigger.once);
+        assert_eq!(trigger.actions, vec![
+            TriggerAction::Banner("Incoming".to_string()),
+            TriggerAction::ActivateSpawners("pincer".to_string()),
+            TriggerAction::ScrollSpeed(None),
+            TriggerAction::OpenWall(Rect::new(20.0, 4.0, 2.0, 3.0)),
+        ]);
+        assert!(trigger.activates_group("pincer"));
+    }
+    #[test]
+    fn rejects_broken_triggers()
+    {
+        assert!(StageTrigger::parse_all("banner = Lost\n").is_err());
+        assert!(StageTrigger::parse_all("[trigger]\nbanner = No Area\n").is_err());
+        assert!(StageTrigger::parse_all("[trigger]\narea = 0, 0, 1, 1\n").is_err());
+        assert!(StageTrigger::parse_all("[trigger]\narea = 0, 0, 1, 1\non = enemy\nbanner = x\n").is_err());
+        assert!(StageTrigger::parse_all("[trigger]\narea = 0, 0, 1, 1\nscroll_speed = 0\n").is_err());
+    }
+    #[test]
+    fn sources_decide_when_a_trigger_is_entered()
+    {
+        let text = "[trigger]\narea = 10, 5, 2, 2\nbanner = Door\n[trigger]\narea = 100, 0, 2, 24\non = scroll\nspawners = pincer\n";
+        let triggers = StageTrigger::parse_all(text).unwrap();
+        let (player, scroll) = (&triggers[0], &triggers[1]);
+        let cell = |x: f32, y: f32| Rect::new(x * LEVEL_SCALE, y * LEVEL_SCALE, LEVEL_SCALE, LEVEL_SCALE);
+
+        // Player Triggers need the Player inside, the Scroll Offset does not matter
+        assert!(player.is_entered(cell(10.0, 5.0), 0.0));
+        assert!(!player.is_entered(cell(10.0, 8.0), 10.0 * LEVEL_SCALE));
+        // Scroll Triggers fire once the Area scrolls into View, wherever the Player is
+        assert!(!scroll.is_entered(cell(100.0, 0.0), 100.0 * LEVEL_SCALE - GAME_SIZE_X - 1.0));
+        assert!(scroll.is_entered(cell(0.0, 0.0), 100.0 * LEVEL_SCALE - GAME_SIZE_X));
+    }
+    #[test]
+    fn grouped_spawners_wait_for_their_trigger()
+    {
+        let mut loaded = LoadedLevelData::new();
+        loaded.size = (16, LEVEL_ROWS);
+        loaded.enemy_spawner = vec![(vec2(2.0, 2.0), 1, 0), (vec2(4.0, 2.0), 0, 0)];
+        loaded.spawner_definitions = SpawnerDefinition::parse_all("[spawner]\ncell = 4, 2\ngroup = pincer\n[wave]\ncount = 1\n").unwrap();
+        loaded.triggers = StageTrigger::parse_all("[trigger]\narea = 10, 5, 2, 2\nspawners = pincer\n").unwrap();
+        let mut world = World::new_headless(1);
+
+        let chunks = LevelChunk::split(&loaded, &mut LevelData::new(LEVEL_SCALE), &mut world);
+        let held: Vec<bool> = chunks[0].spawners.iter().map(|spawner| spawner.held).collect();
+        assert_eq!(held, vec![false, true]);
+
+        let mut enemypool = EnemyPool::new();
+        enemypool.create_pool(10, &mut world);
+        let mut leveldata = LevelData::new(LEVEL_SCALE);
+        leveldata.load_chunk(&chunks[0], &mut world);
+        // No Sprite Sheet headless -> back to one Cell so the Spawners sit in View
+        for spawner in leveldata.enemy_spawner.iter_mut() { spawner.entity.transform.set_size(vec2(1.0, 1.0)); }
+        let mut run = |leveldata: &mut LevelData, world: &mut World|
+        {
+            for _ in 0..60
+            {
+                for spawner in leveldata.enemy_spawner.iter_mut() { spawner.update(&mut enemypool, world); }
+            }
+            enemypool.get_alive_count()
+        };
+        assert_eq!(run(&mut leveldata, &mut world), 1);
+
+        // Trigger Action releases the Group -> its Spawner starts
+        leveldata.release_spawners("pincer");
+        assert_eq!(run(&mut leveldata, &mut world), 2);
+    }
+}
@@ -0,0 +1,261 @@
+use super::*;
+
+pub const DEFAULT_TILE_ATLAS_NAME: &str = "Default";
+
+// Drawn Shape of a Wall -> picked from the open Sides (no solid Neighbour) of its Cell
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TileShape
+{
+    // No open Side
+    Interior,
+    // Open Side drawn at the Top of the Atlas Tile
+    Edge,
+    // Open Sides drawn at the Top & Right
+    Corner,
+    // Open Sides drawn at the Top & Bottom
+    Strip,
+    // Only the Bottom is closed
+    End,
+    // Every Side is open
+    Single,
+}
+impl TileShape
+{
+    pub fn all() -> [TileShape; 6]
+    {
+        [TileShape::Interior, TileShape::Edge, TileShape::Corner, TileShape::Strip, TileShape::End, TileShape::Single]
+    }
+    pub fn get_name(&self) -> &'static str
+    {
+        match self
+        {
+            TileShape::Interior => "interior",
+            TileShape::Edge => "edge",
+            TileShape::Corner => "corner",
+            TileShape::Strip => "strip",
+            TileShape::End => "end",
+            TileShape::Single => "single",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<TileShape>
+    {
+        TileShape::all().into_iter().find(|shape| shape.get_name() == name)
+    }
+
+    // Open Sides Top, Right, Bottom, Left -> Shape & Clockwise Rotation in Degrees
+    // None -> the Shape looks the same from every Side, the random Rotation stays
+    pub fn from_open_sides(open: [bool; 4]) -> (TileShape, Option<f32>)
+    {
+        let count = open.iter().filter(|o| **o).count();
+        let first = open.iter().position(|o| *o).unwrap_or(0);
+        match count
+        {
+            0 => (TileShape::Interior, None),
+            1 => (TileShape::Edge, Some(first as f32 * 90.0)),
+            2 => {
+                if open[0] == open[2]
+                {
+                    (TileShape::Strip, Some(if open[0] { 0.0 } else { 90.0 }))
+                }
+                else
+                {
+                    // Top & Left wraps around -> Corner rotated to the Left
+                    let start = if open[0] && open[3] { 3 } else { first };
+                    (TileShape::Corner, Some(start as f32 * 90.0))
+                }
+            }
+            3 => {
+                let closed = open.iter().position(|o| !*o).unwrap_or(2);
+                (TileShape::End, Some(((closed + 2) % 4) as f32 * 90.0))
+            }
+            _ => (TileShape::Single, None),
+        }
+    }
+}
+
+// Where every Shape of plain & infected Walls sits in tile_texture_atlas -> loaded per Level Pack
+#[derive(Clone, PartialEq, Debug)]
+pub struct TileAtlas
+{
+    pub name: String,
+    // Width & Height of one Atlas Tile in Pixel
+    pub tile_size: f32,
+    // Pixel Offset per Shape -> same Order as TileShape::all()
+    pub wall: [Vec2; 6],
+    pub infected_wall: [Vec2; 6],
+}
+impl Default for TileAtlas
+{
+    // Atlas Layout before Autotiling -> one Tile for every Shape
+    fn default() -> Self
+    {
+        Self {
+            name: DEFAULT_TILE_ATLAS_NAME.to_string(),
+            tile_size: 16.0,
+            wall: [vec2(0.0, 0.0); 6],
+            infected_wall: [vec2(32.0, 0.0); 6],
+        }
+    }
+}
+impl TileAtlas
+{
+    pub fn get_offset(&self, shape: TileShape, infected: bool) -> Vec2
+    {
+        let index = TileShape::all().iter().position(|s| *s == shape).unwrap_or(0);
+        if infected { self.infected_wall[index] } else { self.wall[index] }
+    }
+
+    // Format -> "name" and "tile_size", then one "wall.<shape> = x, y" or "infected_wall.<shape> = x, y" per Line  |  "#" starts a Comment
+    // Missing Shapes keep the Tile of the Default Layout
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        let mut atlas = TileAtlas::default();
+        for (line_number, line) in config_lines(text)
+        {
+            let (key, value) = config_entry(line_number, line)?;
+            match key
+            {
+                "name" => { atlas.name = value.to_string(); }
+                "tile_size" => {
+                    atlas.tile_size = value.parse::<f32>().map_err(|_| format!("Line {}: tile_size is not a Number", line_number))?;
+                }
+                _ => {
+                    let (kind, shape_name) = key.split_once('.').ok_or(format!("Line {}: {} has to be wall.<shape> or infected_wall.<shape>", line_number, key))?;
+                    let shape = TileShape::from_name(shape_name).ok_or(format!("Line {}: unknown Shape {}", line_number, shape_name))?;
+                    let offset = parse_vec2(value).ok_or(format!("Line {}: Offset of {} needs x, y", line_number, key))?;
+                    let index = TileShape::all().iter().position(|s| *s == shape).unwrap();
+                    match kind
+                    {
+                        "wall" => { atlas.wall[index] = offset; }
+                        "infected_wall" => { atlas.infected_wall[index] = offset; }
+                        _ => { return Err(format!("Line {}: unknown Wall Kind {}", line_number, kind)); }
+                    }
+                }
+            }
+        }
+        if atlas.tile_size <= 0.0
+        {
+            return Err("tile_size has to be above 0".to_string());
+        }
+        Ok(atlas)
+    }
+}
+
+// Solid Cells of a Stage -> Neighbour Lookup for Autotiling
+pub struct TileGrid
+{
+    width: i32,
+    height: i32,
+    solid: Vec<bool>,
+}
+impl TileGrid
+{
+    // Destructibles break & the Level End is open -> neither closes a Wall Side
+    pub fn new(level_data: &LoadedLevelData) -> Self
+    {
+        let (width, height) = (level_data.size.0 as i32, level_data.size.1.max(LEVEL_ROWS) as i32);
+        let mut grid = Self { width, height, solid: vec![false; (width * height).max(0) as usize] };
+        let cells = level_data.walls.iter()
+            .chain(level_data.infected_wall_filling.iter())
+            .chain(level_data.blockingWalls.iter())
+            .chain(level_data.trapWalls.iter())
+            .chain(level_data.turrets.iter())
+            .chain(level_data.enemy_spawner.iter().map(|s| &s.0));
+        for cell in cells
+        {
+            let (x, y) = (cell.x as i32, cell.y as i32);
+            if x >= 0 && y >= 0 && x < width && y < height
+            {
+                grid.solid[(y * width + x) as usize] = true;
+            }
+        }
+        grid
+    }
+    // Outside the Stage counts as solid -> no Edges along the Screen Border
+    pub fn is_solid(&self, x: i32, y: i32) -> bool
+    {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height
+        {
+            return true;
+        }
+        self.solid[(y * self.width + x) as usize]
+    }
+    pub fn get_open_sides(&self, cell: Vec2) -> [bool; 4]
+    {
+        let (x, y) = (cell.x as i32, cell.y as i32);
+        [!self.is_solid(x, y - 1), !self.is_solid(x + 1, y), !self.is_solid(x, y + 1), !self.is_solid(x - 1, y)]
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    #[test]
+    fn parses_shape_offsets()
+    {
+        let atlas = TileAtlas::parse("tile_size = 16\nwall.corner = 16, 0\ninfected_wall.single = 48, 16\n").unwrap();
+        assert_eq!(atlas.get_offset(TileShape::Corner, false), vec2(16.0, 0.0));
+        assert_eq!(atlas.get_offset(TileShape::Single, true), vec2(48.0, 16.0));
+        // Missing Shapes keep the Default Tile
+        assert_eq!(atlas.get_offset(TileShape::Edge, false), vec2(0.0, 0.0));
+    }
+    #[test]
+    fn rejects_broken_atlases()
+    {
+        assert!(TileAtlas::parse("wall.round = 0, 0\n").is_err());
+        assert!(TileAtlas::parse("floor.edge = 0, 0\n").is_err());
+        assert!(TileAtlas::parse("wall.edge = 0\n").is_err());
+        assert!(TileAtlas::parse("tile_size = 0\n").is_err());
+    }
+    #[test]
+    fn picks_shapes_from_open_sides()
+    {
+        assert_eq!(TileShape::from_open_sides([false; 4]), (TileShape::Interior, None));
+        assert_eq!(TileShape::from_open_sides([false, true, false, false]), (TileShape::Edge, Some(90.0)));
+        assert_eq!(TileShape::from_open_sides([true, false, false, true]), (TileShape::Corner, Some(270.0)));
+        assert_eq!(TileShape::from_open_sides([false, true, false, true]), (TileShape::Strip, Some(90.0)));
+        assert_eq!(TileShape::from_open_sides([true, true, false, true]), (TileShape::End, Some(0.0)));
+        assert_eq!(TileShape::from_open_sides([true; 4]), (TileShape::Single, None));
+    }
+    #[test]
+    fn split_walls_take_their_atlas_tile()
+    {
+        let mut loaded = LoadedLevelData::new();
+        loaded.size = (16, LEVEL_ROWS);
+        for y in 1..4
+        {
+            for x in 1..4
+            {
+                loaded.walls.push(vec2(x as f32, y as f32));
+            }
+        }
+        loaded.infected_wall_filling.push(vec2(1.0, 8.0));
+        loaded.tile_atlas = TileAtlas::parse("tile_size = 17\nwall.interior = 0, 34\nwall.edge = 17, 34\nwall.corner = 34, 34\ninfected_wall.single = 32, 0\n").unwrap();
+        let mut world = World::new_headless(1);
+        let chunks = LevelChunk::split(&loaded, &mut LevelData::new(1.0), &mut world);
+
+        let wall_at = |x: f32, y: f32| *chunks[0].walls.iter().find(|wall| wall.tile.cell == vec2(x, y)).unwrap();
+        assert!(chunks[0].walls.iter().all(|wall| wall.tile_size == 17.0));
+        assert_eq!(wall_at(2.0, 2.0).sprite, vec2(0.0, 34.0));
+        // Edges & Corners turn their open Side outwards
+        assert_eq!((wall_at(2.0, 1.0).sprite, wall_at(2.0, 1.0).tile.rotation), (vec2(17.0, 34.0), 0.0));
+        assert_eq!((wall_at(3.0, 2.0).sprite, wall_at(3.0, 2.0).tile.rotation), (vec2(17.0, 34.0), 90.0_f32.to_radians()));
+        assert_eq!((wall_at(3.0, 1.0).sprite, wall_at(3.0, 1.0).tile.rotation), (vec2(34.0, 34.0), 0.0));
+        assert_eq!((wall_at(1.0, 3.0).sprite, wall_at(1.0, 3.0).tile.rotation), (vec2(34.0, 34.0), 180.0_f32.to_radians()));
+        assert_eq!(chunks[0].infected_walls[0].sprite, vec2(32.0, 0.0));
+    }
+    #[test]
+    fn missing_atlas_keeps_the_default_layout()
+    {
+        let manifest = "name = Test\ntile_atlas = missing.cfg\n[stage]\nname = Intro\nstructure = stage.png\n";
+        let folder = write_test_pack("atlas", &[(MANIFEST_FILE, manifest)]);
+        let mut loader = LevelLoader::new();
+        loader.level_loader_init_headless(&folder);
+        assert_eq!(loader.levels.len(), 1);
+        assert_eq!(loader.levels[0].tile_atlas.name, DEFAULT_TILE_ATLAS_NAME);
+        assert_eq!(loader.errors.len(), 1);
+        assert_eq!(loader.errors[0].kind, LoadErrorKind::Missing);
+    }
+}
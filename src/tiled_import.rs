@@ -0,0 +1,542 @@
+use std::collections::HashMap;
+use tinyjson::JsonValue;
+use super::*;
+
+// Tiled stores Flip Flags in the upper Bits of every Tile Id
+const TILED_GID_MASK: u32 = 0x0FFF_FFFF;
+
+// Custom Properties of a Layer, Object or Tile -> Values are kept as Text
+pub type TiledProperties = Vec<(String, String)>;
+
+pub fn find_property<'a>(properties: &'a TiledProperties, name: &str) -> Option<&'a str>
+{
+    properties.iter().find(|(key, _)| key == name).map(|(_, value)| value.as_str())
+}
+
+#[derive(Clone, Debug)]
+pub struct TiledObject
+{
+    // "type" in older Tiled Versions, "class" since 1.9
+    pub kind: String,
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub gid: u32,
+    pub properties: TiledProperties,
+}
+
+#[derive(Clone, Debug)]
+pub enum TiledLayer
+{
+    Tiles { name: String, properties: TiledProperties, width: usize, data: Vec<u32> },
+    Objects { name: String, objects: Vec<TiledObject> },
+}
+impl TiledLayer
+{
+    // Data has to fill the Layer exactly -> Cells are read Row by Row
+    fn tiles(name: String, properties: TiledProperties, width: usize, height: usize, data: Vec<u32>) -> Result<TiledLayer, String>
+    {
+        if width == 0 || height == 0
+        {
+            return Err(format!("Layer {}: width and height have to be above 0", name));
+        }
+        if data.len() != width * height
+        {
+            return Err(format!("Layer {}: {} Tiles for {} x {} Cells", name, data.len(), width, height));
+        }
+        Ok(TiledLayer::Tiles { name, properties, width, data })
+    }
+}
+
+// Everything the Importer needs from a .tmx / .tmj File -> both Formats end up here
+#[derive(Clone, Debug)]
+pub struct TiledMap
+{
+    pub width: usize,
+    pub height: usize,
+    pub tile_width: f32,
+    pub tile_height: f32,
+    // Global Tile Id -> Class of that Tile in an embedded Tileset
+    pub tile_classes: HashMap<u32, String>,
+    pub layers: Vec<TiledLayer>,
+}
+impl Default for TiledMap
+{
+    fn default() -> Self { Self::new() }
+}
+impl TiledMap
+{
+    pub fn new() -> Self
+    {
+        Self { width: 0, height: 0, tile_width: 1.0, tile_height: 1.0, tile_classes: HashMap::new(), layers: Vec::new() }
+    }
+
+    // ---------------------------------
+    // Tiled JSON (.tmj / .json)
+
+    pub fn from_json(text: &str) -> Result<Self, String>
+    {
+        let root: JsonValue = text.parse().map_err(|error| format!("Invalid JSON: {:?}", error))?;
+        let mut map = TiledMap::new();
+        if json_bool(&root, "infinite")
+        {
+            return Err("Infinite Maps are not supported".to_string());
+        }
+        map.width = json_number(&root, "width").ok_or("Map has no width")? as usize;
+        map.height = json_number(&root, "height").ok_or("Map has no height")? as usize;
+        map.tile_width = json_number(&root, "tilewidth").unwrap_or(1.0) as f32;
+        map.tile_height = json_number(&root, "tileheight").unwrap_or(1.0) as f32;
+
+        for tileset in json_array(&root, "tilesets").iter()
+        {
+            let first_gid = json_number(tileset, "firstgid").unwrap_or(1.0) as u32;
+            if json_string(tileset, "source").is_some()
+            {
+                println!("Tiled: external Tileset {} -> Tile Classes are ignored", json_string(tileset, "source").unwrap());
+            }
+            for tile in json_array(tileset, "tiles").iter()
+            {
+                let id = json_number(tile, "id").unwrap_or(0.0) as u32;
+                let class = json_string(tile, "class").or(json_string(tile, "type"));
+                match class
+                {
+                    Some(class) if !class.is_empty() => { map.tile_classes.insert(first_gid + id, class.to_string()); }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut layers: Vec<&JsonValue> = json_array(&root, "layers").iter().collect();
+        // Groups -> flattened in Order
+        let mut i = 0;
+        while i < layers.len()
+        {
+            if json_string(layers[i], "type") == Some("group")
+            {
+                let group = layers.remove(i);
+                for (offset, child) in json_array(group, "layers").iter().enumerate()
+                {
+                    layers.insert(i + offset, child);
+                }
+                continue;
+            }
+            i += 1;
+        }
+
+        for layer in layers
+        {
+            let name = json_string(layer, "name").unwrap_or("").to_string();
+            match json_string(layer, "type")
+            {
+                Some("tilelayer") => {
+                    let data = match layer.get::<HashMap<String, JsonValue>>().and_then(|l| l.get("data"))
+                    {
+                        Some(JsonValue::Array(values)) => values.iter().map(|v| json_as_number(v).unwrap_or(0.0) as u32).collect(),
+                        Some(_) => { return Err(format!("Layer {}: only the CSV Tile Layer Format is supported", name)); }
+                        None => { return Err(format!("Layer {}: no Tile Data", name)); }
+                    };
+                    let width = json_number(layer, "width").unwrap_or(map.width as f64) as usize;
+                    let height = json_number(layer, "height").unwrap_or(map.height as f64) as usize;
+                    map.layers.push(TiledLayer::tiles(name, json_properties(layer), width, height, data)?);
+                }
+                Some("objectgroup") => {
+                    let mut objects = Vec::new();
+                    for object in json_array(layer, "objects").iter()
+                    {
+                        objects.push(TiledObject {
+                            kind: json_string(object, "class").or(json_string(object, "type")).unwrap_or("").to_string(),
+                            x: json_number(object, "x").unwrap_or(0.0) as f32,
+                            y: json_number(object, "y").unwrap_or(0.0) as f32,
+                            width: json_number(object, "width").unwrap_or(0.0) as f32,
+                            height: json_number(object, "height").unwrap_or(0.0) as f32,
+                            gid: json_number(object, "gid").unwrap_or(0.0) as u32,
+                            properties: json_properties(object),
+                        });
+                    }
+                    map.layers.push(TiledLayer::Objects { name, objects });
+                }
+                // Image Layers are only Reference for the Artists
+                _ => {}
+            }
+        }
+        Ok(map)
+    }
+
+    // ---------------------------------
+    // Tiled XML (.tmx)
+
+    pub fn from_tmx(text: &str) -> Result<Self, String>
+    {
+        let document = roxmltree::Document::parse(text).map_err(|error| format!("Invalid TMX: {}", error))?;
+        let root = document.root_element();
+        if !root.has_tag_name("map")
+        {
+            return Err("TMX has no map Element".to_string());
+        }
+        if root.attribute("infinite") == Some("1")
+        {
+            return Err("Infinite Maps are not supported".to_string());
+        }
+        let mut map = TiledMap::new();
+        map.width = xml_number(root, "width").ok_or("Map has no width")? as usize;
+        map.height = xml_number(root, "height").ok_or("Map has no height")? as usize;
+        map.tile_width = xml_number(root, "tilewidth").unwrap_or(1.0);
+        map.tile_height = xml_number(root, "tileheight").unwrap_or(1.0);
+
+        for tileset in root.children().filter(|n| n.has_tag_name("tileset"))
+        {
+            let first_gid = xml_number(tileset, "firstgid").unwrap_or(1.0) as u32;
+            if let Some(source) = tileset.attribute("source") { println!("Tiled: external Tileset {} -> Tile Classes are ignored", source); }
+            for tile in tileset.children().filter(|n| n.has_tag_name("tile"))
+            {
+                let id = xml_number(tile, "id").unwrap_or(0.0) as u32;
+                match tile.attribute("class").or(tile.attribute("type"))
+                {
+                    Some(class) if !class.is_empty() => { map.tile_classes.insert(first_gid + id, class.to_string()); }
+                    _ => {}
+                }
+            }
+        }
+
+        // Groups -> flattened in Order
+        let mut layers = Vec::new();
+        let mut pending: Vec<roxmltree::Node> = root.children().filter(|n| n.is_element()).collect();
+        pending.reverse();
+        while let Some(node) = pending.pop()
+        {
+            if node.has_tag_name("group")
+            {
+                let mut children: Vec<roxmltree::Node> = node.children().filter(|n| n.is_element()).collect();
+                children.reverse();
+                pending.extend(children);
+                continue;
+            }
+            layers.push(node);
+        }
+
+        for layer in layers
+        {
+            let name = layer.attribute("name").unwrap_or("").to_string();
+            if layer.has_tag_name("layer")
+            {
+                let data_node = layer.children().find(|n| n.has_tag_name("data")).ok_or(format!("Layer {}: no Tile Data", name))?;
+                if data_node.attribute("encoding") != Some("csv")
+                {
+                    return Err(format!("Layer {}: only the CSV Tile Layer Format is supported", name));
+                }
+                let data: Vec<u32> = data_node.text().unwrap_or("")
+                    .split(',')
+                    .map(|v| v.trim())
+                    .filter(|v| !v.is_empty())
+                    .map(|v| v.parse::<u32>().unwrap_or(0))
+                    .collect();
+                let width = xml_number(layer, "width").unwrap_or(map.width as f32) as usize;
+                let height = xml_number(layer, "height").unwrap_or(map.height as f32) as usize;
+                map.layers.push(TiledLayer::tiles(name, xml_properties(layer), width, height, data)?);
+            }
+            else if layer.has_tag_name("objectgroup")
+            {
+                let mut objects = Vec::new();
+                for object in layer.children().filter(|n| n.has_tag_name("object"))
+                {
+                    objects.push(TiledObject {
+                        kind: object.attribute("class").or(object.attribute("type")).unwrap_or("").to_string(),
+                        x: xml_number(object, "x").unwrap_or(0.0),
+                        y: xml_number(object, "y").unwrap_or(0.0),
+                        width: xml_number(object, "width").unwrap_or(0.0),
+                        height: xml_number(object, "height").unwrap_or(0.0),
+                        gid: xml_number(object, "gid").unwrap_or(0.0) as u32,
+                        properties: xml_properties(object),
+                    });
+                }
+                map.layers.push(TiledLayer::Objects { name, objects });
+            }
+        }
+        Ok(map)
+    }
+
+    // ---------------------------------
+    // Conversion -> same Grid as the PNG Stages (1 Tile = 1 Pixel)
+
+    // Tile Layers: Kind from the Layer Property "kind", the Layer Name or the Class of each Tile
+    // Object Layers: Kind from the Object Class -> Rectangles fill every Cell they cover
+    pub fn to_level_data(&self) -> Result<LoadedLevelData, String>
+    {
+        let mut level_data = LoadedLevelData::new();
+        for layer in self.layers.iter()
+        {
+            match layer
+            {
+                TiledLayer::Tiles { name, properties, width, data } => {
+                    // Layer Names only count if they name a built-in Kind -> "Background 2" is no Kind
+                    let layer_kind = match find_property(properties, "kind")
+                    {
+                        Some(kind) => Some(kind_name(kind)),
+                        None => Some(kind_name(name)).filter(|kind| LevelElementKind::from_name(kind).is_some()),
+                    };
+                    for (index, gid) in data.iter().enumerate()
+                    {
+                        let gid = gid & TILED_GID_MASK;
+                        if gid == 0
+                        {
+                            continue;
+                        }
+                        let kind = match &layer_kind
+                        {
+                            Some(kind) => kind.clone(),
+                            None => match self.tile_classes.get(&gid)
+                            {
+                                Some(class) => kind_name(class),
+                                None => { return Err(format!("Layer {}: Tile {} at ({}, {}) has no Kind", name, gid, index % width, index / width)); }
+                            },
+                        };
+                        let cell = vec2((index % width) as f32, (index / width) as f32);
+                        add_tiled_element(&kind, &mut level_data, cell, vec2(1.0, 1.0), &TiledProperties::new())?;
+                    }
+                }
+                TiledLayer::Objects { name, objects } => {
+                    for object in objects.iter()
+                    {
+                        if object.kind.is_empty()
+                        {
+                            return Err(format!("Layer {}: Object at ({}, {}) has no Class", name, object.x, object.y));
+                        }
+                        let kind = kind_name(&object.kind);
+                        // Tile Objects are anchored at their bottom left Corner
+                        let top = if object.gid != 0 { object.y - object.height } else { object.y };
+                        let first = vec2((object.x / self.tile_width).floor(), (top / self.tile_height).floor());
+                        let size = vec2(
+                            (object.width / self.tile_width).round().max(1.0),
+                            (object.height / self.tile_height).round().max(1.0),
+                        );
+                        // Walls and the Level End fill their Area, everything else is one Element
+                        match LevelElementKind::from_name(&kind)
+                        {
+                            Some(LevelElementKind::EnemySpawner) | Some(LevelElementKind::Turret) | None => {
+                                add_tiled_element(&kind, &mut level_data, first, size, &object.properties)?;
+                            }
+                            Some(_) => {
+                                for y in 0..size.y as usize
+                                {
+                                    for x in 0..size.x as usize
+                                    {
+                                        add_tiled_element(&kind, &mut level_data, first + vec2(x as f32, y as f32), vec2(1.0, 1.0), &object.properties)?;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // Level End is read Top to Bottom by the Level
+        level_data.level_end.sort_by(|a, b| a.y.partial_cmp(&b.y).unwrap());
+        level_data.size = (self.width as u32, self.height as u32);
+        Ok(level_data)
+    }
+}
+
+// Tiled Names of the Level Elements -> Layer Names, Layer "kind" Properties and Classes
+// Plural and short Names are accepted as well, everything else becomes a LevelMarker
+fn kind_name(name: &str) -> String
+{
+    let name = name.to_lowercase().replace(' ', "_");
+    let kind = match name.as_str()
+    {
+        "walls" => "wall",
+        "infected_walls" => "infected_wall",
+        "blocking_walls" => "blocking_wall",
+        "trap_walls" | "trap" | "traps" => "trap_wall",
+        "destructibles" => "destructible",
+        "spawner" | "spawners" => "enemy_spawner",
+        "turrets" => "turret",
+        "hazards" => "hazard",
+        "checkpoints" => "checkpoint",
+        _ => name.as_str(),
+    };
+    kind.to_string()
+}
+fn add_tiled_element(kind: &str, level_data: &mut LoadedLevelData, cell: Vec2, size: Vec2, properties: &TiledProperties) -> Result<(), String>
+{
+    match LevelElementKind::from_name(kind)
+    {
+        Some(LevelElementKind::EnemySpawner) => {}
+        Some(kind) => {
+            level_data.add_element(kind, cell);
+            return Ok(());
+        }
+        // Markers keep their Area and every Property
+        None => {
+            level_data.markers.push(LevelMarker { kind: kind.to_string(), position: cell, size, properties: properties.clone() });
+            return Ok(());
+        }
+    }
+    let count = match find_property(properties, "count")
+    {
+        Some(value) => value.parse::<usize>().map_err(|_| format!("Spawner at {}: count '{}' is not a Number", cell, value))?,
+        None => 1,
+    };
+    let enemy_type = match find_property(properties, "enemy_type")
+    {
+        Some(value) => enemy_type_id(value).ok_or(format!("Spawner at {}: unknown enemy_type '{}'", cell, value))?,
+        None => 0,
+    };
+    let delay = match find_property(properties, "delay")
+    {
+        Some(value) => value.parse::<f32>().map_err(|_| format!("Spawner at {}: delay '{}' is not a Number", cell, value))?,
+        None => 0.0,
+    };
+    level_data.enemy_spawner.push((cell, count, enemy_type));
+    level_data.spawner_properties.push(SpawnerProperties { delay, custom: properties.clone() });
+    Ok(())
+}
+
+// Same Ids as the Blue Channel of a Spawnmap, or the Name of the EnemyType -> None for Ids no EnemyType has
+pub fn enemy_type_id(value: &str) -> Option<usize>
+{
+    if let Ok(id) = value.parse::<usize>()
+    {
+        return EnemySpawner::enemy_type_from_id(id).map(|_| id);
+    }
+    match value.to_lowercase().as_str()
+    {
+        "default" => Some(0),
+        "gunner" => Some(1),
+        "tank" => Some(2),
+        "heavygunner" | "heavy_gunner" => Some(3),
+        "exploder" => Some(4),
+        "boss" => Some(5),
+        _ => None,
+    }
+}
+
+// ---------------------------------
+// JSON Helpers -> missing Keys are None instead of a Panic
+
+fn json_field<'a>(value: &'a JsonValue, key: &str) -> Option<&'a JsonValue>
+{
+    value.get::<HashMap<String, JsonValue>>().and_then(|object| object.get(key))
+}
+fn json_as_number(value: &JsonValue) -> Option<f64>
+{
+    value.get::<f64>().copied()
+}
+fn json_number(value: &JsonValue, key: &str) -> Option<f64>
+{
+    json_field(value, key).and_then(json_as_number)
+}
+fn json_string<'a>(value: &'a JsonValue, key: &str) -> Option<&'a str>
+{
+    json_field(value, key).and_then(|v| v.get::<String>()).map(|s| s.as_str())
+}
+fn json_bool(value: &JsonValue, key: &str) -> bool
+{
+    json_field(value, key).and_then(|v| v.get::<bool>()).copied().unwrap_or(false)
+}
+fn json_array<'a>(value: &'a JsonValue, key: &str) -> &'a [JsonValue]
+{
+    match json_field(value, key).and_then(|v| v.get::<Vec<JsonValue>>())
+    {
+        Some(array) => array.as_slice(),
+        None => &[],
+    }
+}
+fn json_properties(value: &JsonValue) -> TiledProperties
+{
+    let mut properties = TiledProperties::new();
+    for property in json_array(value, "properties").iter()
+    {
+        let name = json_string(property, "name").unwrap_or("").to_string();
+        let value = match json_field(property, "value")
+        {
+            Some(JsonValue::String(text)) => text.clone(),
+            Some(JsonValue::Number(number)) => number.to_string(),
+            Some(JsonValue::Boolean(flag)) => flag.to_string(),
+            _ => String::new(),
+        };
+        properties.push((name, value));
+    }
+    properties
+}
+
+// ---------------------------------
+// TMX Helpers
+
+fn xml_number(node: roxmltree::Node, attribute: &str) -> Option<f32>
+{
+    node.attribute(attribute).and_then(|value| value.parse::<f32>().ok())
+}
+fn xml_properties(node: roxmltree::Node) -> TiledProperties
+{
+    let mut properties = TiledProperties::new();
+    for group in node.children().filter(|n| n.has_tag_name("properties"))
+    {
+        for property in group.children().filter(|n| n.has_tag_name("property"))
+        {
+            // Multiline Strings are stored as Text instead of the value Attribute
+            let value = property.attribute("value").or(property.text()).unwrap_or("");
+            properties.push((property.attribute("name").unwrap_or("").to_string(), value.to_string()));
+        }
+    }
+    properties
+}
+
+// Picks the Parser from the File Extension
+pub fn import_tiled_map(path: &str, text: &str) -> Result<LoadedLevelData, String>
+{
+    let map = if path.ends_with(".tmx")
+    {
+        TiledMap::from_tmx(text)?
+    }
+    else
+    {
+        TiledMap::from_json(text)?
+    };
+    map.to_level_data()
+}
+pub fn is_tiled_map(path: &str) -> bool
+{
+    path.ends_with(".tmx") || path.ends_with(".tmj") || path.ends_with(".json")
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn map_json(layer_width: usize, data: &str, spawner_type: &str) -> String
+    {
+        format!(r#"{{"width": 2, "height": 2, "tilewidth": 16, "tileheight": 16, "layers": [
+            {{"type": "tilelayer", "name": "walls", "width": {}, "height": 2, "data": [{}]}},
+            {{"type": "objectgroup", "name": "objects", "objects": [
+                {{"class": "spawner", "x": 16, "y": 0, "width": 16, "height": 16,
+                  "properties": [{{"name": "enemy_type", "type": "string", "value": "{}"}}]}}
+            ]}}
+        ]}}"#, layer_width, data, spawner_type)
+    }
+
+    #[test]
+    fn converts_layers_and_spawners()
+    {
+        let map = TiledMap::from_json(&map_json(2, "1, 0, 0, 1", "tank")).unwrap();
+        let level_data = map.to_level_data().unwrap();
+        assert_eq!(level_data.walls, vec![vec2(0.0, 0.0), vec2(1.0, 1.0)]);
+        assert_eq!(level_data.enemy_spawner, vec![(vec2(1.0, 0.0), 1, 2)]);
+    }
+    #[test]
+    fn rejects_layers_that_do_not_fit()
+    {
+        assert!(TiledMap::from_json(&map_json(0, "1, 0, 0, 1", "tank")).is_err());
+        assert!(TiledMap::from_json(&map_json(2, "1, 0, 0", "tank")).is_err());
+    }
+    #[test]
+    fn rejects_unknown_enemy_types()
+    {
+        assert_eq!(enemy_type_id("5"), Some(5));
+        assert_eq!(enemy_type_id("Heavy_Gunner"), Some(3));
+        assert_eq!(enemy_type_id("6"), None);
+        let map = TiledMap::from_json(&map_json(2, "1, 0, 0, 1", "42")).unwrap();
+        assert!(map.to_level_data().is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::cell::Cell;
+use super::*;
+
+// Length of one Simulation Step -> every Gameplay System advances by exactly this much
+pub const SIMULATION_TICK: f32 = 1.0 / 120.0;
+// Longest Frame the Accumulator takes in -> keeps a Hitch from piling up endless Steps
+pub const MAX_FRAME_TIME: f32 = 0.25;
+
+thread_local! {
+    static RENDER_ALPHA: Cell<f32> = const { Cell::new(1.0) };
+}
+
+// Simulation Counterpart to get_frame_time()
+pub fn get_tick_time() -> f32
+{
+    SIMULATION_TICK
+}
+// How far the current Frame sits between the last two Simulation Steps (0.0 - 1.0)
+pub fn get_render_alpha() -> f32
+{
+    RENDER_ALPHA.with(|alpha| alpha.get())
+}
+pub fn set_render_alpha(alpha: f32)
+{
+    RENDER_ALPHA.with(|value| value.set(alpha.clamp(0.0, 1.0)));
+}
+
+// Collects the real Frame Time and hands it out in fixed Steps
+#[derive(Clone)]
+pub struct FixedTimestep
+{
+    tick: f32,
+    accumulator: f32,
+    pub steps: u64,
+}
+impl FixedTimestep
+{
+    pub fn new(tick: f32) -> Self
+    {
+        Self { tick, accumulator: 0.0, steps: 0 }
+    }
+    pub fn accumulate(&mut self, frame_time: f32)
+    {
+        self.accumulator += frame_time.min(MAX_FRAME_TIME);
+    }
+    // Call in a Loop -> true as long as a whole Step is left
+    pub fn step(&mut self) -> bool
+    {
+        if self.accumulator >= self.tick
+        {
+            self.accumulator -= self.tick;
+            self.steps += 1;
+            return true;
+        }
+        false
+    }
+    pub fn alpha(&self) -> f32
+    {
+        self.accumulator / self.tick
+    }
+    pub fn reset(&mut self)
+    {
+        self.accumulator = 0.0;
+        self.steps = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    fn run_steps(timestep: &mut FixedTimestep) -> u64
+    {
+        let mut count = 0;
+        while timestep.step()
+        {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn hands_out_whole_steps_and_keeps_the_rest_as_alpha()
+    {
+        let mut timestep = FixedTimestep::new(0.125);
+        timestep.accumulate(0.1875);
+        assert_eq!(run_steps(&mut timestep), 1);
+        assert_eq!(timestep.alpha(), 0.5);
+
+        // Left over Time carries into the next Frame
+        timestep.accumulate(0.0625);
+        assert_eq!(run_steps(&mut timestep), 1);
+        assert_eq!(timestep.alpha(), 0.0);
+
+        // Short Frame -> no Step, only the Alpha moves
+        timestep.accumulate(0.03125);
+        assert_eq!(run_steps(&mut timestep), 0);
+        assert_eq!(timestep.alpha(), 0.25);
+        assert_eq!(timestep.steps, 2);
+    }
+
+    #[test]
+    fn long_frames_only_catch_up_to_the_limit()
+    {
+        let mut timestep = FixedTimestep::new(0.125);
+        timestep.accumulate(5.0);
+        assert_eq!(run_steps(&mut timestep) as f32, MAX_FRAME_TIME / 0.125);
+        assert_eq!(timestep.alpha(), 0.0);
+
+        let mut simulation = FixedTimestep::new(SIMULATION_TICK);
+        simulation.accumulate(10.0);
+        let steps = run_steps(&mut simulation);
+        assert!((steps as f32 - MAX_FRAME_TIME / SIMULATION_TICK).abs() <= 1.0);
+        assert!((0.0..1.0).contains(&simulation.alpha()));
+
+        simulation.reset();
+        assert_eq!(simulation.steps, 0);
+        assert_eq!(run_steps(&mut simulation), 0);
+    }
+
+    #[test]
+    fn render_alpha_stays_between_steps()
+    {
+        set_render_alpha(0.4);
+        assert_eq!(get_render_alpha(), 0.4);
+        set_render_alpha(3.0);
+        assert_eq!(get_render_alpha(), 1.0);
+        set_render_alpha(-1.0);
+        assert_eq!(get_render_alpha(), 0.0);
+    }
+}
@@ -7,6 +7,7 @@ pub struct Weapon
     parent: Option<Entity>,
     sprite: TextureAsset,
     direction: Vec2,
+    projectile_layer: CollisionLayer,
 
     pub missle_spawn_offset: Vec2,
     params: DrawTextureParams,
@@ -20,8 +21,9 @@ impl Weapon
         Self {
             entity: Entity::new(name, tag, world),
             parent: None,
-            sprite: world.assets.get_asset_by_name("weapon_sheet".to_string()).unwrap().get_texture_asset(),
+            sprite: world.assets.get_asset("weapon_sheet").get_texture_asset(),
             direction: vec2(0.0, 0.0),
+            projectile_layer: CollisionLayer::NONE,
             missle_spawn_offset: vec2(0.0, 0.0),
             params: DrawTextureParams::default(),
             cooldown_t: 0.0,
@@ -37,20 +39,14 @@ impl Weapon
         //println!("FSpeed: {}", self.entity.entity_params.firespeed);
         if self.cooldown_t <= 0.0 && !self.sprite.animation.is_playing
         {
-            misslepool.fire_missle( self.entity.clone(), self.direction, self.missle_spawn_offset, world);
+            misslepool.fire_missle( self.entity.clone(), self.projectile_layer, self.direction, self.missle_spawn_offset, world);
             self.cooldown_t = 2.0;
             self.sprite.animation.set_animation_speed( f32::clamp(self.entity.entity_params.firerate * 0.25, 1.0, 1000.0));
             self.sprite.animation.play_anim_once();
             //println!("Firerate: {}",self.entity.entity_params.firerate );
-
-            
-            world.particlesystem_pool.spawn_system_at_position(
-                self.entity.transform.position + self.missle_spawn_offset, 
-                16, 
-                fire_settings( self.entity.transform.position + self.missle_spawn_offset,self.direction));
             return true;
         }else  {
-            self.cooldown_t -= self.entity.entity_params.firerate * get_frame_time();
+            self.cooldown_t -= self.entity.entity_params.firerate * get_tick_time();
             return false;
         }
     }
@@ -78,9 +74,10 @@ impl GameObject for Weapon
                     self.entity.transform.set_size(self.sprite.get_sheet_tile_size());
                     self.entity.transform.set_scale(0.35);
                 }
-                self.entity.transform.set_position( vec2( screen_width() * 0.5, screen_height() * 0.5 ));
+                self.entity.transform.set_position( vec2( GAME_SIZE_X * 0.5, GAME_SIZE_Y * 0.5 ));
                 self.params.flip_x = false;
                 self.entity.entity_params.firespeed = 100.0;
+                self.projectile_layer = CollisionLayer::PLAYER_PROJECTILE;
             }
             "Enemy Weapon" => {
                 if self.sprite.texture_data == Texture2D::empty()
@@ -91,23 +88,26 @@ impl GameObject for Weapon
                     self.entity.transform.set_size(self.sprite.get_sheet_tile_size());
                     self.entity.transform.set_scale(0.3);
                 }
-                self.entity.transform.set_position( vec2( screen_width() * 0.5, screen_height() * 0.5 ));
+                self.entity.transform.set_position( vec2( GAME_SIZE_X * 0.5, GAME_SIZE_Y * 0.5 ));
                 self.params.flip_x = false;
                 self.entity.entity_params.firespeed = 100.0;
+                self.projectile_layer = CollisionLayer::ENEMY_PROJECTILE;
             }
             _ => {}
         }
     }
     fn update(&mut self, world: &mut World) {
         
+        self.entity.transform.store_previous();
+        // Animation gates the Cooldown -> advance it with the Simulation, not the Framerate
+        self.sprite.animation.update();
         match self.entity.tag.as_str()
         {
             "Player Weapon" => {
 
 
-                // Scalefactor -> Adjust relative mouseposition to screen scale
-                let scale_factor = vec2( GAME_SIZE_X  as f32, GAME_SIZE_Y  as f32)/vec2(screen_width(), screen_height());
-                let relative_mouseposition = vec2(mouse_position().0 * scale_factor.x, mouse_position().1 * scale_factor.y);
+                // Aim is already in Game Space
+                let relative_mouseposition = world.input.aim;
 
                 let mouseposition_worldoffset = vec2( relative_mouseposition.x + world.level_offset, relative_mouseposition.y);
                 //println!("{}", mouseposition_worldoffset);
@@ -126,7 +126,7 @@ impl GameObject for Weapon
                 self.entity.transform.rotation = f32::to_radians(rotation.to_degrees() - 90.0); 
                 
                 
-                self.direction += vec2(LEVEL_SPEED * get_frame_time(), 0.0).normalize() * 0.466;
+                self.direction += vec2(world.get_scroll_factor() * 0.466, 0.0);
                 // Update from parent
                 match &mut self.parent
                 {
@@ -168,18 +168,23 @@ impl GameObject for Weapon
     }
     fn draw(&mut self) {
 
+        let render_rect = self.entity.transform.get_render_rect();
         if self.sprite.texture_data == Texture2D::empty()
         {
-            draw_rectangle(self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.transform.rect.w, self.entity.transform.rect.h, DARKGRAY);
+            draw_rectangle(render_rect.x, render_rect.y, render_rect.w, render_rect.h, DARKGRAY);
         }else
         {
-            self.sprite.animation.update();
             let frame = self.sprite.get_current_animation_frame(); 
             self.params.source = frame;
             self.params.dest_size = Some(self.entity.transform.get_fullsize());
             
             self.params.rotation = self.entity.transform.rotation;
-            draw_texture_ex(self.sprite.texture_data, self.entity.transform.rect.x, self.entity.transform.rect.y, self.entity.get_rect_color(), self.params.clone());
+
+            // Pivot follows the interpolated Position
+            let render_shift = vec2(self.entity.transform.rect.x - render_rect.x, self.entity.transform.rect.y - render_rect.y);
+            let mut params = self.params.clone();
+            params.pivot = params.pivot.map(|pivot| pivot - render_shift);
+            draw_texture_ex(self.sprite.texture_data, render_rect.x, render_rect.y, self.entity.get_rect_color(), params);
         }
         match self.entity.tag.as_str()
         {
@@ -8,17 +8,35 @@ pub struct World
     pub available_levels: usize,
     pub level: Option<Level>,
     pub level_offset: f32,
+    // Pixel per Second -> set from the Stage Manifest
+    pub level_speed: f32,
+    // Pixel per Second of this Step -> follows the Scroll Curve, 0 while a Scroll Lock holds the Camera
+    pub scroll_speed: f32,
+    // Level Offset of the last Simulation Step -> Camera interpolates between both
+    previous_level_offset: f32,
     pub level_completed: bool,
     //selected_level: usize,
 
+    // Entity Slots -> Index of an EntityHandle
     pub entities: Vec<Entity>,
+    entity_generations: Vec<u32>,
+    free_entity_slots: Vec<usize>,
+
     active_entities: Vec<Entity>,
+    active_lookup: Vec<Option<usize>>,
+    broadphase: SpatialHash,
 
     collected_scorepoints: i32,
 
     pub difficulty_level: i32,
 
     pub particlesystem_pool: ParticleSystemPool,
+    pub events: EventQueue,
+    pub random: WorldRandom,
+
+    // Backends -> Gameplay never touches macroquad Input or Audio directly
+    pub input: InputFrame,
+    pub audio: Box<dyn AudioBackend>,
 }
 impl World
 {
@@ -30,21 +48,46 @@ impl World
         let mut loader = LevelLoader::new();
         loader.level_loader_init().await;
 
+        World::from_parts(assets, loader.levels.len(), Box::new(MacroquadAudio::new()))
+    }
+    // No Window -> empty Assets and silent Audio
+    pub fn new_headless(available_levels: usize) -> Self
+    {
+        let mut assets = AssetLibrary::new();
+        assets.asset_loader_init_headless();
+
+        World::from_parts(assets, available_levels, Box::new(NullAudio::new()))
+    }
+    pub fn from_parts(assets: AssetLibrary, available_levels: usize, audio: Box<dyn AudioBackend>) -> Self
+    {
         Self {
             assets: assets,
 
-            available_levels: loader.levels.len(),
+            available_levels,
             //level_loader: loader,
             level: None,
             level_offset: -GAME_SIZE_X * 0.5,
+            level_speed: LEVEL_SPEED,
+            scroll_speed: LEVEL_SPEED,
+            previous_level_offset: -GAME_SIZE_X * 0.5,
             level_completed: false,
             //selected_level: 0,
             
             entities: Vec::new(),
+            entity_generations: Vec::new(),
+            free_entity_slots: Vec::new(),
+
             active_entities: Vec::new(), 
+            active_lookup: Vec::new(),
+            broadphase: SpatialHash::new(BROADPHASE_CELL_SIZE),
             collected_scorepoints: 0,
             difficulty_level: 0,
             particlesystem_pool: ParticleSystemPool::new(),
+            events: EventQueue::new(),
+            random: WorldRandom::new(0),
+
+            input: InputFrame::new(),
+            audio,
         }
     }
     pub fn get_active_level(&self) -> &Level { 
@@ -53,21 +96,21 @@ impl World
 
     pub fn reload(&mut self)
     {
-        self.entities.clear();
-        self.active_entities.clear();
+        self.clear_entities();
         self.collected_scorepoints = 0;
         self.particlesystem_pool.clear();
+        self.events.clear();
         self.level = None;
-        println!("Reload! {}", self.entities.len());
+        println!("Reload! {}", self.entity_count());
     }
     pub fn reload_for_next_level(&mut self)
     {
-        self.entities.clear();
-        self.active_entities.clear();
+        self.clear_entities();
         //self.collected_scorepoints = 0;
         self.particlesystem_pool.clear();
+        self.events.clear();
         self.level = None;
-        println!("Load next Level! {}", self.entities.len());
+        println!("Load next Level! {}", self.entity_count());
     }
 
     pub fn fixed_update(&mut self)
@@ -86,60 +129,188 @@ impl World
         self.active_entities = self.entities.clone();
         self.active_entities.retain(|e| e.is_active == true && e.in_view == true);
 
-        
+        self.active_lookup.clear();
+        self.active_lookup.resize(self.entities.len(), None);
+        for i in 0..self.active_entities.len()
+        {
+            self.active_lookup[self.active_entities[i].handle.index] = Some(i);
+        }
 
+        self.previous_level_offset = self.level_offset;
         if !self.get_active_level().has_reached_level_end(self.level_offset)
         {
-            self.level_offset += LEVEL_SPEED * get_frame_time();
+            let mut level_offset = self.level_offset + self.scroll_speed * get_tick_time();
+            // Scroll Lock ahead -> Camera stops exactly on it
+            match self.get_active_level().get_scroll_limit()
+            {
+                Some(limit) if self.level_offset <= limit && level_offset >= limit => {
+                    level_offset = limit;
+                    self.scroll_speed = 0.0;
+                }
+                _ => {}
+            }
+            self.level_offset = level_offset;
         }
     }
+    // 1.0 at the default Speed -> Missile & Wall Compensation were tuned for it
+    pub fn get_scroll_factor(&self) -> f32
+    {
+        self.scroll_speed / LEVEL_SPEED
+    }
+    // Jumps -> no Interpolation from the old Offset
+    pub fn set_level_offset(&mut self, offset: f32)
+    {
+        self.level_offset = offset;
+        self.previous_level_offset = offset;
+    }
+    pub fn get_render_offset(&self) -> f32
+    {
+        self.previous_level_offset + (self.level_offset - self.previous_level_offset) * get_render_alpha()
+    }
     pub fn get_actives(&mut self) -> &mut Vec<Entity>
     {
         //println!("Active Entities {}", self.active_entities.len());
         &mut self.active_entities
     }
 
-    pub fn add_entity(&mut self, new_entity: &mut Entity)
+    // ---------------------------------
+    // Broadphase
+
+    pub fn rebuild_broadphase(&mut self)
     {
-        self.entities.push(new_entity.clone());
+        self.broadphase.clear();
+        for entity in self.active_entities.iter()
+        {
+            if entity.collision_is_enabled
+            {
+                self.broadphase.insert(entity.handle, entity.transform.rect);
+            }
+        }
     }
-
-    pub fn remove_entity(&mut self, index: usize)
+    // Active Entities whose Cells touch the Rect
+    pub fn query_rect(&self, rect: Rect) -> Vec<EntityHandle>
     {
-        self.entities.remove(index);
+        let mut handles = self.broadphase.query(rect);
+        handles.retain(|h| self.get_active_index(*h).is_some());
+        handles
     }
-
-    pub fn set_entity(&mut self, entity: &mut Entity)
+    // Active Entities on one of the Mask Layers which really intersect the Rect
+    pub fn query_overlaps(&self, rect: Rect, mask: CollisionLayer) -> Vec<Entity>
     {
-        for i in 0..self.entities.len()
+        let mut overlaps = Vec::new();
+        for handle in self.broadphase.query(rect)
         {
-            if self.entities[i].id == entity.id
+            if let Some(active_index) = self.get_active_index(handle)
             {
-                self.entities[i] = entity.clone();
-                if !self.active_entities.contains(entity) && entity.is_active
-                {
-                    self.active_entities.push(entity.clone());
-                }else if self.active_entities.contains(entity) && !entity.is_active
+                let entity = &self.active_entities[active_index];
+                if entity.collision_layer.intersects(mask) && resolve_intersection(rect, entity.transform.rect)
                 {
-                    self.active_entities.retain(|a| a != entity);
+                    overlaps.push(entity.clone());
                 }
-                return;
             }
         }
+        overlaps
+    }
+    fn get_active_index(&self, handle: EntityHandle) -> Option<usize>
+    {
+        if !self.is_valid_handle(handle)
+        {
+            return None;
+        }
+        self.active_lookup[handle.index]
     }
 
+    // ---------------------------------
+    // Entity Slots
 
+    pub fn add_entity(&mut self, new_entity: &mut Entity) -> EntityHandle
+    {
+        let handle = match self.free_entity_slots.pop()
+        {
+            Some(index) => {
+                EntityHandle { index, generation: self.entity_generations[index] }
+            }
+            None => {
+                let index = self.entities.len();
+                if index >= self.entity_generations.len()
+                {
+                    self.entity_generations.push(0);
+                }
+                self.entities.push(new_entity.clone());
+                self.active_lookup.push(None);
+                EntityHandle { index, generation: self.entity_generations[index] }
+            }
+        };
+        new_entity.handle = handle;
+        self.entities[handle.index] = new_entity.clone();
+        handle
+    }
 
-    pub fn get_entity_by_id(&mut self, id: usize) -> Option<&Entity>
+    // Frees the Slot -> every Handle pointing to it is stale afterwards
+    pub fn remove_entity(&mut self, handle: EntityHandle) -> bool
     {
-        for entity in self.entities.iter()
+        if !self.is_valid_handle(handle)
         {
-            if entity.id == id
-            {
-                return Some(entity);
+            return false;
+        }
+        self.remove_active(handle.index);
+        self.entities[handle.index].is_active = false;
+        self.entities[handle.index].collision_is_enabled = false;
+        self.entity_generations[handle.index] += 1;
+        self.free_entity_slots.push(handle.index);
+        true
+    }
+
+    // Returns false if the Handle of the Entity is stale
+    pub fn set_entity(&mut self, entity: &mut Entity) -> bool
+    {
+        if !self.is_valid_handle(entity.handle)
+        {
+            return false;
+        }
+        let index = entity.handle.index;
+        self.entities[index] = entity.clone();
+
+        match self.active_lookup[index]
+        {
+            Some(active_index) => {
+                if entity.is_active
+                {
+                    self.active_entities[active_index] = entity.clone();
+                }else {
+                    self.remove_active(index);
+                }
+            }
+            None => {
+                if entity.is_active
+                {
+                    self.active_lookup[index] = Some(self.active_entities.len());
+                    self.active_entities.push(entity.clone());
+                }
             }
         }
-        return None;
+        true
+    }
+
+    pub fn is_valid_handle(&self, handle: EntityHandle) -> bool
+    {
+        handle.index < self.entities.len() && self.entity_generations[handle.index] == handle.generation
+    }
+    pub fn get_entity(&self, handle: EntityHandle) -> Option<&Entity>
+    {
+        if !self.is_valid_handle(handle)
+        {
+            return None;
+        }
+        Some(&self.entities[handle.index])
+    }
+    pub fn get_entity_mut(&mut self, handle: EntityHandle) -> Option<&mut Entity>
+    {
+        if !self.is_valid_handle(handle)
+        {
+            return None;
+        }
+        Some(&mut self.entities[handle.index])
     }
     pub fn get_entity_by_tag(&mut self, tag: &str) -> Option<&Entity>
     {
@@ -152,6 +323,71 @@ impl World
         }
         return None;
     }
+    pub fn entity_count(&self) -> usize
+    {
+        self.entities.len() - self.free_entity_slots.len()
+    }
 
+    fn remove_active(&mut self, index: usize)
+    {
+        if let Some(active_index) = self.active_lookup[index].take()
+        {
+            self.active_entities.swap_remove(active_index);
+            if active_index < self.active_entities.len()
+            {
+                let moved_index = self.active_entities[active_index].handle.index;
+                self.active_lookup[moved_index] = Some(active_index);
+            }
+        }
+    }
+    fn clear_entities(&mut self)
+    {
+        // Bump every Generation so Handles from the last Level can't resolve anymore
+        for generation in self.entity_generations.iter_mut()
+        {
+            *generation += 1;
+        }
+        self.entities.clear();
+        self.free_entity_slots.clear();
+        self.active_entities.clear();
+        self.active_lookup.clear();
+        self.broadphase.clear();
+    }
+}
+#[cfg(test)]
+mod tests
+{
+    use super::*;
 
-}
\ No newline at end of file
+    #[test]
+    fn stale_handles_stop_resolving()
+    {
+        let mut world = World::new_headless(1);
+        let first = Entity::new("first", "test", &mut world).handle;
+        assert_eq!(world.get_entity(first).unwrap().name, "first");
+        assert!(world.remove_entity(first));
+        assert!(world.get_entity(first).is_none());
+        assert!(!world.remove_entity(first));
+
+        // Reused Slot -> only the new Handle resolves
+        let mut second = Entity::new("second", "test", &mut world);
+        assert_eq!(second.handle.index, first.index);
+        assert_ne!(second.handle, first);
+        assert!(world.get_entity(first).is_none());
+        assert!(world.get_entity_mut(first).is_none());
+        assert_eq!(world.get_entity(second.handle).unwrap().name, "second");
+        let mut stale = second.clone();
+        stale.handle = first;
+        stale.name = "stale".to_string();
+        assert!(!world.set_entity(&mut stale));
+        assert!(world.set_entity(&mut second));
+        assert_eq!(world.get_entity(second.handle).unwrap().name, "second");
+
+        // Reload -> Handles of the last Level stay stale once their Slots are used again
+        world.reload();
+        let third = Entity::new("third", "test", &mut world).handle;
+        assert_eq!(third.index, second.handle.index);
+        assert!(world.get_entity(second.handle).is_none());
+        assert_eq!(world.entity_count(), 1);
+    }
+}
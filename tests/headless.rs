@@ -0,0 +1,134 @@
+use macroquad::prelude::*;
+use vust_nexus::*;
+
+// Fire held, weaving up & down -> the Player meets Walls, Enemies and Missles
+fn scripted_frames(count: usize) -> Vec<InputFrame>
+{
+    (0..count).map(|tick| {
+        let mut frame = InputFrame::new();
+        frame.set_down(InputAction::Fire, true);
+        frame.set_down(if (tick / 90) % 2 == 0 { InputAction::MoveUp } else { InputAction::MoveDown }, true);
+        frame.aim = vec2(GAME_SIZE_X, GAME_SIZE_Y * 0.5);
+        frame
+    }).collect()
+}
+
+fn run(game: &mut Game, max_ticks: usize) -> usize
+{
+    let mut ticks = 0;
+    while ticks < max_ticks && game.step_headless()
+    {
+        ticks += 1;
+    }
+    ticks
+}
+
+#[test]
+fn recorded_stage_replays_the_same()
+{
+    let max_ticks = 3000;
+    let mut game = Game::init_headless(DEFAULT_LEVEL_PACK, Box::new(ScriptedInput::new(scripted_frames(max_ticks))));
+    game.start_stage(4, 0xBEEF);
+    let ticks = run(&mut game, max_ticks);
+    assert!(ticks > 0);
+
+    let replay = game.get_recording().clone();
+    let header = replay.header;
+    let mut replayed = Game::init_headless(DEFAULT_LEVEL_PACK, Box::new(ReplayPlayer::new(replay.clone())));
+    replayed.start_from_header(header);
+    let replayed_ticks = run(&mut replayed, replay.len());
+
+    assert_eq!(replayed_ticks, ticks);
+    assert_eq!(replayed.get_score(), game.get_score());
+    assert_eq!(replayed.get_player_health(), game.get_player_health());
+    assert_eq!(replayed.get_gamestate(), game.get_gamestate());
+}
+
+#[test]
+fn replay_survives_encoding()
+{
+    let max_ticks = 600;
+    let mut game = Game::init_headless(DEFAULT_LEVEL_PACK, Box::new(ScriptedInput::new(scripted_frames(max_ticks))));
+    game.start_stage(0, 0x1234);
+    run(&mut game, max_ticks);
+
+    let replay = game.get_recording();
+    let decoded = Replay::from_bytes(&replay.to_bytes()).unwrap();
+    assert_eq!(&decoded, replay);
+}
+
+#[test]
+fn streaming_keeps_live_entities_bounded()
+{
+    let mut loader = LevelLoader::new();
+    loader.level_loader_init_headless(DEFAULT_LEVEL_PACK);
+    let loaded = loader.levels.iter().max_by_key(|level| level.size.0).unwrap().clone();
+    let chunk_width = CHUNK_COLUMNS as f32 * LEVEL_SCALE;
+    let stage_width = loaded.size.0 as f32 * LEVEL_SCALE;
+
+    // Every Chunk at once -> what the Stage cost before Streaming
+    let mut full_world = World::new_headless(1);
+    let mut leveldata = LevelData::new(LEVEL_SCALE);
+    let chunks = LevelChunk::split(&loaded, &mut leveldata, &mut full_world);
+    for chunk in chunks.iter()
+    {
+        leveldata.load_chunk(chunk, &mut full_world);
+    }
+    let full_count = full_world.entity_count();
+
+    let mut world = World::new_headless(1);
+    let mut level = Level::new(&mut world, loaded);
+    let (mut max_live, mut retired) = (0, 0);
+    while world.level_offset + GAME_SIZE_X < stage_width
+    {
+        world.level_offset += LEVEL_SPEED * get_tick_time();
+        level.stream(&mut world);
+        let live = level.get_live_chunks();
+        // Loaded past the right View Edge, retired once well behind the left one
+        assert!(live.end == chunks.len() || live.end as f32 * chunk_width > world.level_offset + GAME_SIZE_X);
+        assert!(live.start as f32 * chunk_width <= world.level_offset.max(0.0));
+        assert!((live.start + 2) as f32 * chunk_width >= world.level_offset - chunk_width);
+        retired = live.start;
+        max_live = max_live.max(world.entity_count());
+    }
+    assert!(retired > chunks.len() / 2);
+    assert!(max_live < full_count / 2, "{} live of {}", max_live, full_count);
+    // Retired Slots get reused -> the Entity List never grows past the live Peak
+    assert!(world.entities.len() <= max_live);
+}
+
+#[test]
+fn continue_restores_the_checkpoint()
+{
+    let max_ticks = 6000;
+    let mut game = Game::init_headless(DEFAULT_LEVEL_PACK, Box::new(ScriptedInput::new(scripted_frames(max_ticks))));
+    game.start_stage(4, 0xBEEF);
+    run(&mut game, max_ticks);
+
+    // Passed the Checkpoint with Elements destroyed, then died further on
+    assert_eq!(game.get_gamestate(), &GameState::GameOver);
+    let checkpoint = game.get_checkpoint().unwrap().clone();
+    assert!(!checkpoint.progress.cleared.is_empty());
+    assert!(game.get_score() > checkpoint.score);
+
+    assert!(game.continue_from_checkpoint());
+    assert_eq!(game.get_gamestate(), &GameState::GameRunning);
+    assert_eq!(game.get_score(), checkpoint.score);
+    assert!(*game.get_player_params() == checkpoint.player);
+
+    // Cleared Elements of the streamed Chunks stay gone
+    let level = game.level.as_ref().unwrap();
+    let live = level.get_live_chunks();
+    let in_view = checkpoint.progress.cleared.iter().filter(|cell| live.contains(&(cell.x as usize / CHUNK_COLUMNS))).count();
+    assert!(in_view > 0);
+    let data = level.get_level_data();
+    let cells: Vec<Vec2> = data.destructibles.iter().map(|e| e.cell)
+        .chain(data.enemy_spawner.iter().map(|e| e.cell))
+        .chain(data.turrets.iter().map(|e| e.cell))
+        .collect();
+    assert!(!cells.is_empty());
+    assert!(cells.iter().all(|cell| !checkpoint.progress.cleared.contains(cell)));
+
+    // Runs on from there
+    assert!(run(&mut game, 60) > 0);
+}